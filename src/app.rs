@@ -1,8 +1,25 @@
-use std::path::PathBuf;
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use crossterm::event::{KeyCode, KeyEvent};
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::layout::{Position, Rect};
+use ratatui::style::Color;
 
-use crate::email::{self, EmailEntry};
+use crate::cli;
+use crate::config::{AccountConfig, ColumnId, Config, EmailSource, Keymap};
+use crate::email::{self, ContactStat, EmailEntry, MailboxStats, PgpStatus, Priority, SortColumn};
+use crate::template::DiffLine;
+use crate::theme::Theme;
+use crate::ui::{self, FileInputEvent, FileInputWidget, HeaderLine, Spinner};
 
 /// Which pane currently has focus.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -12,12 +29,93 @@ pub enum Focus {
     Headers,
     Preview,
     Search,
+    /// Typing a date range for the list (`D`).
+    DateFilter,
+    /// Choosing a destination mailbox for the selected email (`m`).
+    MailboxPicker,
+    /// Typing the recipient of a new draft (`n`), with address suggestions.
+    Compose,
+    /// Mailbox statistics overlay (`?S`).
+    Stats,
+    /// Typing when a snoozed email should return (`z`).
+    SnoozeInput,
+    /// Senders ranked by how many emails they sent (`C-k`).
+    Contacts,
+    /// Choosing the active mail account (`M-a`).
+    AccountPicker,
+    /// Typing a vim-style search (`C-/`) that moves the cursor without filtering.
+    JumpSearch,
+    /// Typing the path of a file to attach to the selected draft (`+`).
+    FileInput,
+}
+
+impl Focus {
+    /// Name shown at the end of the status bar breadcrumb.
+    pub fn label(self) -> &'static str {
+        match self {
+            Focus::Sidebar => "Sidebar",
+            Focus::List => "List",
+            Focus::Headers => "Headers",
+            Focus::Preview => "Preview",
+            Focus::Search => "Search",
+            Focus::DateFilter => "Date filter",
+            Focus::MailboxPicker => "Move",
+            Focus::Compose => "Compose",
+            Focus::Stats => "Stats",
+            Focus::SnoozeInput => "Snooze",
+            Focus::Contacts => "Contacts",
+            Focus::AccountPicker => "Accounts",
+            Focus::JumpSearch => "Jump",
+            Focus::FileInput => "Attach",
+        }
+    }
+}
+
+/// How the background watcher learns about new mail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchMode {
+    /// `email watch` (IMAP IDLE).
+    Idle,
+    /// Polling the inbox directory for new files, when `email` is unavailable.
+    Files,
+}
+
+/// Address header a sender/recipient filter applies to (`F` / `T` in the headers).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressField {
+    From,
+    To,
+}
+
+impl AddressField {
+    /// Prefix shown in the list title, e.g. `from:`.
+    pub fn label(self) -> &'static str {
+        match self {
+            AddressField::From => "from:",
+            AddressField::To => "to:",
+        }
+    }
+}
+
+/// Key that `Q` / `@` is waiting for: the macro slot letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacroPrompt {
+    Record,
+    Play,
+}
+
+/// Queued macro playback: a key to replay, or the end of one macro's keys.
+#[derive(Debug, Clone, Copy)]
+pub enum MacroStep {
+    Key(KeyEvent),
+    End,
 }
 
 /// Messages that drive state transitions (TEA pattern).
 #[derive(Debug)]
 pub enum Message {
     Key(KeyEvent),
+    Mouse(MouseEvent),
     Resize(u16, u16),
     Quit,
     /// Background watcher detected new mail.
@@ -31,14 +129,18 @@ pub enum Mailbox {
     Drafts,
     Sent,
     Archive,
+    Spam,
+    Trash,
 }
 
 impl Mailbox {
-    pub const ALL: [Mailbox; 4] = [
+    pub const ALL: [Mailbox; 6] = [
         Mailbox::Inbox,
         Mailbox::Drafts,
         Mailbox::Sent,
         Mailbox::Archive,
+        Mailbox::Spam,
+        Mailbox::Trash,
     ];
 
     pub fn icon(self) -> &'static str {
@@ -47,6 +149,20 @@ impl Mailbox {
             Mailbox::Drafts => "󰏫",
             Mailbox::Sent => "󰑫",
             Mailbox::Archive => "󰀼",
+            Mailbox::Spam => "\u{f0483}",
+            Mailbox::Trash => "\u{f0a7a}",
+        }
+    }
+
+    /// Lowercase name used for config keys (`[mailbox_labels]`, `[mailbox_icons]`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Mailbox::Inbox => "inbox",
+            Mailbox::Drafts => "drafts",
+            Mailbox::Sent => "sent",
+            Mailbox::Archive => "archive",
+            Mailbox::Spam => "spam",
+            Mailbox::Trash => "trash",
         }
     }
 
@@ -56,6 +172,8 @@ impl Mailbox {
             Mailbox::Drafts => "Drafts",
             Mailbox::Sent => "Sent",
             Mailbox::Archive => "Archive",
+            Mailbox::Spam => "Spam",
+            Mailbox::Trash => "Trash",
         }
     }
 
@@ -66,54 +184,349 @@ impl Mailbox {
             Mailbox::Drafts => 1,
             Mailbox::Sent => 2,
             Mailbox::Archive => 3,
+            Mailbox::Spam => 4,
+            Mailbox::Trash => 5,
         }
     }
 }
 
+/// How a mailbox is shown: the built-in label and icon unless the config
+/// overrides them.
+#[derive(Debug, Clone)]
+pub struct MailboxDisplay {
+    pub label: String,
+    pub icon: String,
+}
+
+impl MailboxDisplay {
+    fn from_config(config: &Config) -> [MailboxDisplay; 6] {
+        Mailbox::ALL.map(|mailbox| MailboxDisplay {
+            label: config
+                .mailbox_labels
+                .get(mailbox.name())
+                .cloned()
+                .unwrap_or_else(|| mailbox.label().to_string()),
+            icon: config
+                .mailbox_icons
+                .get(mailbox.name())
+                .cloned()
+                .unwrap_or_else(|| mailbox.icon().to_string()),
+        })
+    }
+}
+
+/// Keymap actions handled in each context, in lookup order (see `config::Keymap`).
+const GLOBAL_ACTIONS: &[&str] = &[
+    "quit",
+    "help",
+    "filter",
+    "search_body",
+    "goto_inbox",
+    "goto_drafts",
+    "goto_sent",
+    "goto_archive",
+    "goto_spam",
+    "goto_trash",
+    "focus_sidebar",
+    "focus_next",
+    "focus_prev",
+    "undo",
+    "suspend",
+    "shrink_list",
+    "grow_list",
+    "reset_split",
+    "cycle_theme",
+    "toggle_cc",
+    "toggle_age",
+    "contacts",
+    "accounts",
+    "record_macro",
+    "play_macro",
+];
+const SIDEBAR_ACTIONS: &[&str] =
+    &["down", "up", "top", "bottom", "select", "reconcile", "sidebar_position", "mark_all_read"];
+const HEADERS_ACTIONS: &[&str] = &[
+    "down",
+    "up",
+    "select",
+    "copy_address",
+    "open_attachment",
+    "filter_from",
+    "filter_to",
+];
+/// Leaving the headers pane, checked before the global focus keys.
+const HEADERS_NAV_ACTIONS: &[&str] = &["headers_to_list", "headers_to_body"];
+const LIST_ACTIONS: &[&str] = &[
+    "top",
+    "bottom",
+    "down",
+    "up",
+    "edit",
+    "reply",
+    "reply_all",
+    "forward",
+    "archive",
+    "move",
+    "mark_spam",
+    "delete",
+    "empty_trash",
+    "delete_duplicate",
+    "snooze",
+    "attach_file",
+    "export_mbox",
+    "mark_all_read",
+    "export_pdf",
+    "diff_template",
+    "approve",
+    "send",
+    "send_approved",
+    "copy_path",
+    "open_dir",
+    "new",
+    "new_from_template",
+    "fetch",
+    "sync",
+    "show_scheduled",
+    "reconcile",
+    "thread_view",
+    "date_filter",
+    "toggle_star",
+    "show_duplicates",
+    "sort_column",
+    "sort_order",
+    "filter_from",
+    "filter_to",
+    "focus_headers",
+    "half_page_down",
+    "half_page_up",
+    "page_down",
+    "page_up",
+    "jump_search",
+];
+/// Keys that take precedence in the list while a `C-/` search is active.
+const JUMP_ACTIONS: &[&str] = &["next_match", "prev_match", "back"];
+const PREVIEW_ACTIONS: &[&str] = &[
+    "down",
+    "up",
+    "half_page_down",
+    "half_page_up",
+    "next_link",
+    "next_match",
+    "prev_match",
+    "open_link",
+    "edit",
+    "visual_select",
+    "toggle_wrap",
+    "toggle_source",
+    "back",
+];
+/// Horizontal scrolling, active in the preview while wrapping is off.
+const NOWRAP_ACTIONS: &[&str] = &["scroll_left", "scroll_right"];
+/// Keys active while selecting body lines (`v` in the preview).
+const VISUAL_ACTIONS: &[&str] = &["down", "up", "yank", "back"];
+
 /// Side-effects that the main loop must execute (keeps update pure).
 #[derive(Debug)]
 pub enum Action {
     /// Open the currently selected email in $EDITOR.
     EditCurrent,
+    /// Open the selected email in $EDITOR with the cursor at the start of the body.
+    EditBody,
     /// Run `email reply [--all]` on the selected email (interactive).
     Reply(bool),
+    /// Run `email forward` on the selected email, then open the draft in $EDITOR.
+    Forward,
     /// Run `email send` on the selected email (interactive).
     Send,
     /// Run `email send-approved` on the drafts directory (interactive).
     SendApproved,
-    /// Create a new draft, then open in $EDITOR (interactive).
-    NewDraft,
+    /// Create a new draft addressed to the given recipients (may be empty),
+    /// then open in $EDITOR (interactive).
+    NewDraft(String),
     /// Run `email mark-approved` on the selected email (silent).
     Approve,
     /// Archive the selected email (move to archive dir).
     Archive,
-    /// Delete the selected email file.
+    /// Flip the `starred` frontmatter flag of the selected email.
+    ToggleStar,
+    /// Run `email spam` on the selected email (moves it to the spam folder).
+    MarkSpam,
+    /// Move the selected email into another mailbox's directory.
+    MoveTo(Mailbox),
+    /// Move the selected email into the Trash mailbox (local only, nothing is
+    /// deleted on the server until it is deleted from there).
     Delete,
+    /// Run `email delete` on the selected email (server-side + local file).
+    PermanentDelete,
+    /// Permanently delete every email in the Trash mailbox.
+    EmptyTrash,
+    /// Write the given emails to an mbox file in `$HOME`.
+    ExportMbox(Vec<PathBuf>),
+    /// Set `status: read` on these emails in the background (`M-m`).
+    MarkAllRead(Vec<PathBuf>),
+    /// Render the given email to a PDF in the downloads folder.
+    ExportPdf(PathBuf),
+    /// Show the selected draft's changes since the template it came from.
+    DiffFromTemplate,
+    /// Hide the selected inbox email in `<inbox>/.snooze/` until the given time.
+    Snooze(NaiveDateTime),
+    /// Attach the given file to the selected draft.
+    AttachFile(PathBuf),
+    /// Delete duplicate copies of emails (undoable like `Delete`).
+    RemoveDuplicates(Vec<PathBuf>),
     /// Copy the selected email's file path to clipboard.
     CopyPath,
+    /// Copy the given text (a body selection) to the clipboard.
+    CopyText(String),
+    /// Copy an email address from the headers pane to the clipboard.
+    CopyAddress(String),
     /// Run `email fetch` to pull new mail (silent).
     Fetch,
     /// Run `email sync` to full re-sync (silent).
     Sync,
     /// Run `email sync --reconcile` to sync and reconcile (silent).
     Reconcile,
+    /// Open a URL from the body in the system browser.
+    OpenUrl(String),
+    /// Open the selected email's directory in the file manager.
+    OpenDir,
+    /// Create a new draft prefilled from a `mailto:` URI, then open in $EDITOR.
+    NewDraftMailto(String),
+    /// Create a new draft from the named template, then open in $EDITOR (interactive).
+    NewFromTemplate(String),
+    /// Open an attachment file (path, MIME type) with the system default app.
+    OpenAttachment(PathBuf, String),
+    /// Reverse the most recent archive or delete.
+    Undo,
+    /// Stop the process like a shell job (`Ctrl-z`), restoring the terminal around it.
+    Suspend,
 }
 
-/// Which destructive action a confirmation dialog is guarding.
+/// Largest count accepted before a motion.
+const MAX_COUNT: u32 = 9999;
+/// How long a digit bound to another action (1-6 jump to mailboxes) waits
+/// for a motion before it runs that action instead of starting a count.
+const COUNT_JUMP_TIMEOUT: Duration = Duration::from_millis(500);
+/// Maximum number of remembered search queries.
+const SEARCH_HISTORY_LIMIT: usize = 50;
+/// Maximum address suggestions shown under the compose prompt.
+const COMPOSE_SUGGESTIONS: usize = 5;
+/// Columns moved per horizontal scroll step in the unwrapped preview.
+const HSCROLL_STEP: u16 = 8;
+/// Maximum number of emails whose preview scroll offset is remembered.
+const SCROLL_CACHE_LIMIT: usize = 100;
+/// How long the mouse must rest on a list row before its preview tooltip shows.
+const TOOLTIP_DELAY: Duration = Duration::from_millis(500);
+
+/// Default width of the left column (sidebar + list) in percent.
+pub const DEFAULT_SPLIT_PCT: u16 = 35;
+const MIN_SPLIT_PCT: u16 = 15;
+const MAX_SPLIT_PCT: u16 = 70;
+
+/// How long a deleted email's backup is kept for undo.
+pub const UNDO_BACKUP_TTL: Duration = Duration::from_secs(60);
+/// Maximum number of reversible actions remembered.
+const UNDO_STACK_LIMIT: usize = 10;
+
+/// A reversible file operation.
 #[derive(Debug, Clone)]
-pub enum ConfirmAction {
-    Archive,
-    Delete,
-    Send,
-    SendApproved,
+pub enum UndoKind {
+    /// The email was moved from `from` to `to`.
+    Archive { from: PathBuf, to: PathBuf },
+    /// The email was moved between mailboxes from `from` to `to`.
+    Move { from: PathBuf, to: PathBuf },
+    /// The email at `original` was deleted; a copy was saved at `backup`.
+    Delete { backup: PathBuf, original: PathBuf },
 }
 
-/// Data for rendering the confirmation dialog overlay.
+/// An entry on the undo stack.
 #[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub kind: UndoKind,
+    pub created_at: Instant,
+}
+
+/// One choice in a confirmation dialog, picked by pressing `key`.
+#[derive(Debug)]
+pub struct DialogButton {
+    pub label: String,
+    pub key: char,
+    /// What to run when chosen; `None` just closes the dialog.
+    pub action: Option<Action>,
+}
+
+impl DialogButton {
+    fn new(label: &str, key: char, action: Option<Action>) -> Self {
+        Self { label: label.to_string(), key, action }
+    }
+}
+
+/// Data for rendering the confirmation dialog overlay.
+#[derive(Debug)]
 pub struct ConfirmDialog {
     pub title: String,
     pub detail: String,
-    pub action: ConfirmAction,
+    /// Choices in display order.
+    pub buttons: Vec<DialogButton>,
+    /// Button Enter picks, moved with Left/Right.
+    pub selected_button: usize,
+}
+
+impl ConfirmDialog {
+    /// A `[y]es / [n]o` dialog guarding `action`, with yes selected.
+    fn yes_no(title: &str, detail: String, action: Action) -> Self {
+        Self {
+            title: title.to_string(),
+            detail,
+            buttons: vec![
+                DialogButton::new("yes", 'y', Some(action)),
+                DialogButton::new("no", 'n', None),
+            ],
+            selected_button: 0,
+        }
+    }
+
+    /// Like `yes_no`, but no is selected so a stray Enter does not delete or send.
+    fn new_destructive(title: &str, detail: String, action: Action) -> Self {
+        Self { selected_button: 1, ..Self::yes_no(title, detail, action) }
+    }
+
+    /// Replaces the send confirmation when `email::validate_draft` found problems.
+    fn send_anyway(warnings: &[String], action: Action) -> Self {
+        Self {
+            title: "Send anyway?".to_string(),
+            detail: format!("Warnings: {}", warnings.join(", ")),
+            buttons: vec![
+                DialogButton::new("yes send", 'y', Some(action)),
+                DialogButton::new("no cancel", 'n', None),
+            ],
+            selected_button: 1,
+        }
+    }
+}
+
+/// State of the template picker overlay.
+#[derive(Debug, Clone)]
+pub struct TemplatePicker {
+    /// Template names (file stems in `<drafts>/templates/`).
+    pub templates: Vec<String>,
+    pub selected: usize,
+}
+
+/// State of the draft-versus-template diff overlay.
+#[derive(Debug, Clone)]
+pub struct DiffView {
+    /// Template the draft is compared against.
+    pub template: String,
+    pub lines: Vec<DiffLine>,
+    pub scroll: u16,
+}
+
+/// Email list ordering for one mailbox.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SortState {
+    pub column: SortColumn,
+    pub asc: bool,
 }
 
 /// Top-level application state.
@@ -122,97 +535,450 @@ pub struct App {
     pub running: bool,
     pub terminal_width: u16,
     pub terminal_height: u16,
+    /// Width of the left column (sidebar + list) in percent, adjusted with `[`/`]`.
+    pub pane_split_pct: u16,
 
     /// Which mailbox is highlighted in the sidebar.
     pub sidebar_index: usize,
     /// Which mailbox is currently selected (determines email list content).
     pub active_mailbox: Mailbox,
     /// Email count per mailbox, indexed same as Mailbox::ALL.
-    pub mailbox_counts: [usize; 4],
+    pub mailbox_counts: [usize; 6],
+    /// Unread emails per mailbox (sidebar `(N)` suffix), same indexing.
+    pub mailbox_unread: [usize; 6],
+    /// Delivers (total, unread) counts for mailboxes that are not loaded,
+    /// counted on a background thread by `refresh_counts`.
+    count_rx: Option<mpsc::Receiver<[(usize, usize); 6]>>,
     /// Resolved directory paths per mailbox, indexed same as Mailbox::ALL.
-    pub mailbox_dirs: [Option<PathBuf>; 4],
+    pub mailbox_dirs: [Option<PathBuf>; 6],
 
     /// Loaded email entries for the active mailbox.
     pub emails: Vec<EmailEntry>,
     /// Selected email index in the list.
     pub list_index: usize,
-    /// Whether the previous keypress was `g` (for `gg` to go to top).
+    /// Whether the list only shows drafts scheduled with `send_at` (`S` in Drafts).
+    pub scheduled_only: bool,
+    /// Whether the list only shows starred emails (`g*`).
+    pub starred_only: bool,
+    /// Whether the list only shows groups of duplicate emails (`M-d`).
+    pub duplicates_only: bool,
+    /// Emails in the list that duplicate a newer copy (shown as `[DUP]`).
+    pub duplicate_paths: HashSet<PathBuf>,
+    /// Only show emails whose `status` matches (`fa`, `fd`, `fs`, `fu`; `fc` clears).
+    pub status_filter: Option<String>,
+    /// Whether the list only shows high-priority emails (`fp`).
+    pub high_priority_only: bool,
+    /// Whether the previous keypress was `f` (status filter prefix, `ff` fetches).
+    pub filter_pending: bool,
+    /// Number of drafts waiting for their `send_at` time (from the scheduler thread).
+    pub scheduled_pending: usize,
+    /// Whether the list groups emails into conversation threads.
+    pub thread_view: bool,
+    /// Whether the list shows a CC column (`C-c`; only on wide terminals).
+    pub show_cc: bool,
+    /// Whether list rows are dimmed by age (`C-a`).
+    pub age_highlighting: bool,
+    /// Thread groups as indices into `emails` (root first, then replies oldest-first).
+    /// Empty when `thread_view` is off.
+    pub thread_groups: Vec<Vec<usize>>,
+    /// Whether the previous keypress was `g` (for `gg`, `g/` and `g\`).
     pub g_pending: bool,
-    /// Vertical scroll offset for the headers panel.
-    pub headers_scroll: u16,
+    /// Macro slot being recorded (`Qa` ... `Q`).
+    pub recording: Option<char>,
+    /// Recorded key sequences by slot letter (`a`-`z`).
+    pub macros: HashMap<char, Vec<KeyEvent>>,
+    /// Whether `Q` or `@` is waiting for a slot letter.
+    pub macro_prompt: Option<MacroPrompt>,
+    /// Keys still to replay, fed back through `update` by the main loop.
+    pub macro_queue: VecDeque<MacroStep>,
+    /// Macros currently being replayed, innermost last (for recursion checks).
+    pub macro_playing: Vec<char>,
+    /// Whether the list shows search results from all mailboxes (`g/`, `g\`).
+    pub global_search_active: bool,
+    /// Global search matches with their origin mailbox, parallel to `emails`.
+    pub global_results: Vec<(Mailbox, EmailEntry)>,
+    /// Cursor line in the headers panel (index into `ui::header_lines`).
+    pub headers_selected_line: usize,
     /// Vertical scroll offset for the preview/body panel.
     pub preview_scroll: u16,
+    /// Preview scroll offsets per email, least recently used first.
+    scroll_cache: VecDeque<(PathBuf, u16)>,
+    /// Words in the selected email's body (shown in the status bar in Preview).
+    pub body_word_count: usize,
+    /// Lines in the selected email's body.
+    pub body_line_count: usize,
+    /// Whether body lines are being selected for copying (`v` in the preview).
+    pub body_select_mode: bool,
+    /// Wrap long body lines to the pane width (`w` in the preview toggles).
+    pub body_wrap: bool,
+    /// Horizontal scroll offset of the body when wrapping is off.
+    pub preview_hscroll: u16,
+    /// Show the selected email's raw file, frontmatter included (`V` in the preview).
+    pub show_raw: bool,
+    /// Contents of the selected email's file while `show_raw` is on.
+    pub raw_source: Option<String>,
+    /// Rendered body line where the selection started.
+    pub body_select_start: u16,
+    /// Rendered body line the selection currently extends to.
+    pub body_select_end: u16,
+    /// URLs found in the selected email's body (filled on first `u` in the preview).
+    pub urls: Vec<(usize, String)>,
+    /// Which entry of `urls` is highlighted.
+    pub url_index: usize,
     /// Cached emails per mailbox (lazy-loaded).
-    email_cache: [Option<Vec<EmailEntry>>; 4],
+    email_cache: [Option<Vec<EmailEntry>>; 6],
+    /// Mailboxes whose cache may be stale after a watcher event; reloaded on
+    /// next access instead of right away.
+    cache_dirty: [bool; 6],
+    /// List ordering per mailbox (`O` cycles the column, `o` flips direction).
+    pub sort_state: [SortState; 6],
 
     /// An action the main loop should execute after this update cycle.
     pub pending_action: Option<Action>,
+    /// Highlighted entry of the move-to-mailbox picker (index into `Mailbox::ALL`).
+    pub mailbox_picker_index: usize,
+    /// Accounts from the config (`[[account]]`); empty means the env variables.
+    pub accounts: Vec<AccountConfig>,
+    /// Index into `accounts` of the account whose mailboxes are shown.
+    pub active_account: usize,
+    /// Highlighted entry of the account picker.
+    pub account_picker_index: usize,
+    /// Senders with their email counts, computed when the contacts overlay opens.
+    pub contact_stats: Vec<ContactStat>,
+    /// Highlighted row of the contacts overlay.
+    pub contacts_index: usize,
+    /// Label and icon per mailbox, indexed like `Mailbox::ALL`.
+    pub mailbox_display: [MailboxDisplay; 6],
+    /// Aggregates shown in the stats overlay, computed when it opens.
+    pub stats: Option<MailboxStats>,
     /// When set, a confirmation dialog is shown and intercepts all keys.
     pub confirm_dialog: Option<ConfirmDialog>,
+    /// When set, the template picker is shown and intercepts all keys.
+    pub template_picker: Option<TemplatePicker>,
+    /// Diff of the selected draft against its template (`M-c` in Drafts).
+    pub diff_view: Option<DiffView>,
     /// Feedback message shown in the status bar (auto-clears after a few ticks).
     pub status_message: Option<String>,
-    /// Countdown ticks until status_message is cleared (~250ms per tick).
+    /// Countdown ticks until status_message is cleared (`config.tick_ms` per tick).
     pub status_ticks: u8,
+    /// Label of the background operation in progress (shown with `spinner`).
+    pub operation_label: Option<String>,
+    /// Status bar spinner, advanced every tick while an operation runs.
+    pub spinner: Spinner,
     /// Current search query text (empty = no filter active).
     pub search_query: String,
+    /// Vim-style search (`C-/`): `n`/`N` jump between matching rows, nothing is hidden.
+    pub jump_query: Option<String>,
+    /// List rows matching `jump_query`, top to bottom.
+    pub jump_matches: Vec<usize>,
+    /// Position in `jump_matches` of the current match.
+    pub jump_pos: usize,
     /// Whether the current search also matches email body content (`\`).
     pub search_includes_body: bool,
+    /// Match the search query fuzzily against subject, from and to, best match
+    /// first (`C-f` in the search bar).
+    pub search_fuzzy: bool,
+    /// The search query is an address matched only against this header,
+    /// with the display name shown in the list title.
+    pub address_filter: Option<(AddressField, String)>,
+    /// Previously confirmed search queries, oldest first.
+    pub search_history: VecDeque<String>,
+    /// Position in `search_history` while browsing it with Up/Down.
+    pub history_cursor: Option<usize>,
+    /// Recipients typed into the compose prompt (`n`), comma-separated.
+    pub compose_to: String,
+    /// Highlighted address suggestion, once the user moves into the dropdown.
+    pub compose_selected: Option<usize>,
+    /// Known addresses for compose suggestions, most frequent first.
+    pub address_book: Vec<String>,
+    /// Delivers `address_book` from the background scan started in `new`.
+    address_book_rx: Option<mpsc::Receiver<Vec<String>>>,
+    /// Text typed into the snooze prompt (`z`).
+    pub snooze_input: String,
+    /// Path typed into the attach prompt (`+`).
+    pub attach_input: FileInputWidget,
+    /// Emails waiting in `<inbox>/.snooze/` (badge in the status bar).
+    pub snoozed_count: usize,
+    /// Text typed into the date filter bar (`D`).
+    pub date_filter_input: String,
+    /// Inclusive date range the list is restricted to.
+    pub date_range: Option<(NaiveDate, NaiveDate)>,
+    /// Rendered body lines of the selected email containing the content-search query.
+    pub search_match_lines: Vec<u16>,
+    /// Which entry of `search_match_lines` was last jumped to with `n`/`N`.
+    pub search_match_index: Option<usize>,
     /// Whether the help overlay is displayed.
     pub show_help: bool,
+    /// A just-exported PDF that Enter opens while its status message shows.
+    pub exported_pdf: Option<PathBuf>,
+    /// Filter typed into the help overlay.
+    pub help_query: String,
+    /// Files in loaded mailboxes that could not be parsed, with the reason.
+    pub parse_errors: Vec<(PathBuf, String)>,
+    /// Whether the parse errors overlay is displayed (`?E`).
+    pub show_parse_errors: bool,
+    /// Vertical scroll offset of the parse errors overlay.
+    pub parse_errors_scroll: u16,
     /// Whether the background mail watcher is active.
     pub watcher_active: bool,
+    /// Which strategy the watcher thread is using.
+    pub watch_mode: WatchMode,
+    /// Inbox folder polled by the watcher's files fallback, updated when the
+    /// account changes.
+    pub watch_inbox: Arc<Mutex<Option<PathBuf>>>,
+    /// Drafts folder checked by the scheduler thread for due `send_at`
+    /// drafts, updated like `watch_inbox`.
+    pub schedule_drafts: Arc<Mutex<Option<PathBuf>>>,
+    /// When the watcher last became active.
+    pub watcher_connected_at: Option<Instant>,
+    /// When the watcher last stopped.
+    pub watcher_disconnected_at: Option<Instant>,
+    /// Settings from the config file.
+    pub config: Config,
+    /// Key bindings (defaults merged with the `[keys]` config table).
+    pub keymap: Keymap,
+    /// List bindings for mailboxes with their own `[mailbox.<name>.keys]`.
+    mailbox_keymaps: [Option<Keymap>; 6],
+    /// Catppuccin flavour used when drawing (cycled with Ctrl-t).
+    pub theme: Theme,
+    /// Accent color per sender address, filled lazily while rendering the list.
+    pub sender_colors: RefCell<HashMap<String, Color>>,
+    /// Recent archive/delete operations that can be reversed with Alt-z (newest last).
+    pub undo_stack: Vec<UndoEntry>,
+    /// Cached PGP verification results, filled lazily as signed emails are selected.
+    pub pgp_status: HashMap<PathBuf, PgpStatus>,
+    /// Email table area (including its header row) from the last draw, for mouse hits.
+    pub list_area: Cell<Rect>,
+    /// First visible table row from the last draw.
+    pub list_offset: Cell<usize>,
+    /// Email rows that fit in the list pane at the last draw.
+    pub list_visible_rows: Cell<u16>,
+    /// Body pane area from the last draw, for mouse wheel scrolling.
+    pub body_area: Cell<Rect>,
+    /// List row under the mouse pointer.
+    pub hover_index: Option<usize>,
+    /// When the pointer arrived on `hover_index`.
+    pub hover_since: Option<Instant>,
+    /// Whether the body preview tooltip is shown for the hovered row.
+    pub tooltip_visible: bool,
+    /// Count typed before a motion (`5j`), while it is being built up.
+    pub pending_count: Option<u32>,
+    /// Count applying to the key being handled (1 without a count).
+    motion_count: usize,
+    /// A bound digit that started `pending_count`, run as its own action
+    /// when nothing follows within `COUNT_JUMP_TIMEOUT`.
+    count_jump: Option<(KeyEvent, Instant)>,
+    /// Set while that digit is replayed, so it does not start a count again.
+    replaying_count_jump: bool,
+    /// Email list columns from the config, in display order.
+    pub list_columns: Vec<ColumnId>,
 }
 
 impl App {
     pub fn new() -> Self {
-        let dirs = resolve_mailbox_dirs();
+        let mut startup_warning = None;
+        let config = Config::load().unwrap_or_else(|e| {
+            startup_warning = Some(format!("Config: {e:#}"));
+            Config::default()
+        });
+        let keymap = Keymap::with_overrides(&config.keys).unwrap_or_else(|e| {
+            append_warning(&mut startup_warning, format!("Keys: {e}"));
+            Keymap::default()
+        });
+        let mailbox_keymaps = Keymap::per_mailbox(&config, &keymap).unwrap_or_else(|e| {
+            append_warning(&mut startup_warning, format!("Keys: {e:#}"));
+            Default::default()
+        });
+
+        let (list_columns, unknown_columns) = config.columns();
+        if !unknown_columns.is_empty() {
+            let warning = format!("Unknown list_columns: {}", unknown_columns.join(", "));
+            append_warning(&mut startup_warning, warning);
+        }
+
+        let accounts = config.accounts.clone();
+        let dirs = resolve_mailbox_dirs(false, accounts.first());
         let counts = count_emails(&dirs);
+        let address_book_rx = spawn_address_scan(&dirs);
 
         // Eagerly load the starting mailbox (inbox)
-        let emails = dirs[0]
+        let inbox_dir = source_dir(config.email_source, &dirs, Mailbox::Inbox);
+        let (emails, inbox_errors) = inbox_dir
             .as_ref()
-            .map(|d| email::load_emails(d))
+            .map(|d| email::load_mailbox(config.email_source, &dirs, Mailbox::Inbox, d))
             .unwrap_or_default();
 
-        let mut cache: [Option<Vec<EmailEntry>>; 4] = [None, None, None, None];
+        let mut cache: [Option<Vec<EmailEntry>>; 6] = [None, None, None, None, None, None];
         cache[0] = Some(emails.clone());
 
-        Self {
+        let mut app = Self {
             focus: Focus::List,
             running: true,
             terminal_width: 0,
             terminal_height: 0,
+            pane_split_pct: DEFAULT_SPLIT_PCT,
             sidebar_index: 0,
             active_mailbox: Mailbox::Inbox,
             mailbox_counts: counts,
+            mailbox_unread: [0; 6],
+            count_rx: Some(spawn_count_scan(config.email_source, &dirs)),
             mailbox_dirs: dirs,
             emails,
             list_index: 0,
+            scheduled_only: false,
+            starred_only: false,
+            duplicates_only: false,
+            duplicate_paths: HashSet::new(),
+            status_filter: None,
+            high_priority_only: false,
+            filter_pending: false,
+            scheduled_pending: 0,
+            thread_view: false,
+            show_cc: false,
+            age_highlighting: true,
+            thread_groups: Vec::new(),
             g_pending: false,
-            headers_scroll: 0,
+            recording: None,
+            macros: HashMap::new(),
+            macro_prompt: None,
+            macro_queue: VecDeque::new(),
+            macro_playing: Vec::new(),
+            global_search_active: false,
+            global_results: Vec::new(),
+            headers_selected_line: 0,
             preview_scroll: 0,
+            scroll_cache: VecDeque::new(),
+            body_word_count: 0,
+            body_line_count: 0,
+            body_select_mode: false,
+            body_wrap: true,
+            preview_hscroll: 0,
+            show_raw: false,
+            raw_source: None,
+            body_select_start: 0,
+            body_select_end: 0,
+            urls: Vec::new(),
+            url_index: 0,
             email_cache: cache,
+            cache_dirty: [false; 6],
+            sort_state: [SortState::default(); 6],
             pending_action: None,
+            mailbox_picker_index: 0,
+            accounts,
+            active_account: 0,
+            account_picker_index: 0,
+            contact_stats: Vec::new(),
+            contacts_index: 0,
+            mailbox_display: MailboxDisplay::from_config(&config),
+            stats: None,
             confirm_dialog: None,
+            template_picker: None,
+            diff_view: None,
             status_message: None,
             status_ticks: 0,
+            operation_label: None,
+            spinner: Spinner::default(),
             search_query: String::new(),
+            jump_query: None,
+            jump_matches: Vec::new(),
+            jump_pos: 0,
             search_includes_body: false,
+            search_fuzzy: false,
+            address_filter: None,
+            search_history: load_search_history(),
+            history_cursor: None,
+            compose_to: String::new(),
+            compose_selected: None,
+            address_book: Vec::new(),
+            address_book_rx: Some(address_book_rx),
+            snooze_input: String::new(),
+            attach_input: FileInputWidget::default(),
+            snoozed_count: 0,
+            date_filter_input: String::new(),
+            date_range: None,
+            search_match_lines: Vec::new(),
+            search_match_index: None,
             show_help: false,
+            exported_pdf: None,
+            help_query: String::new(),
+            parse_errors: Vec::new(),
+            show_parse_errors: false,
+            parse_errors_scroll: 0,
             watcher_active: false,
+            watch_mode: WatchMode::Idle,
+            watch_inbox: Arc::new(Mutex::new(None)),
+            schedule_drafts: Arc::new(Mutex::new(None)),
+            watcher_connected_at: None,
+            watcher_disconnected_at: None,
+            theme: config.theme,
+            config,
+            keymap,
+            mailbox_keymaps,
+            sender_colors: RefCell::new(HashMap::new()),
+            undo_stack: Vec::new(),
+            pgp_status: HashMap::new(),
+            list_area: Cell::new(Rect::default()),
+            list_offset: Cell::new(0),
+            list_visible_rows: Cell::new(0),
+            body_area: Cell::new(Rect::default()),
+            hover_index: None,
+            hover_since: None,
+            tooltip_visible: false,
+            list_columns,
+            pending_count: None,
+            motion_count: 1,
+            count_jump: None,
+            replaying_count_jump: false,
+        };
+        app.refresh_body_stats();
+        app.refresh_snoozed_count();
+        app.update_shared_dirs();
+        if let Some(inbox) = inbox_dir {
+            app.record_parse_errors(&inbox, inbox_errors);
+        }
+        if app.needs_setup() {
+            app.set_status_for(
+                "No mailbox dirs configured. Set INBOX_DIR etc. in ~/.config/beautifulmail/.env"
+                    .to_string(),
+                10,
+            );
         }
+        if let Some(warning) = startup_warning {
+            app.set_status(warning);
+        }
+        app
     }
 
     /// Process a message and optionally return a follow-up message.
     pub fn update(&mut self, msg: Message) -> Option<Message> {
         match msg {
-            Message::Key(key) => self.handle_key(key),
+            Message::Key(key) => {
+                self.clear_hover();
+                let was_preview = self.focus == Focus::Preview;
+                let next = self.handle_key(key);
+                if was_preview && self.focus != Focus::Preview {
+                    self.remember_scroll();
+                }
+                next
+            }
+            Message::Mouse(mouse) => {
+                let was_preview = self.focus == Focus::Preview;
+                self.handle_mouse(mouse);
+                if was_preview && self.focus != Focus::Preview {
+                    self.remember_scroll();
+                }
+                None
+            }
             Message::Resize(w, h) => {
                 self.terminal_width = w;
                 self.terminal_height = h;
+                self.refresh_search_matches();
                 None
             }
             Message::MailboxChanged => {
+                // Counts are cheap; emails reload when their mailbox is next shown
+                self.cache_dirty = [true; 6];
+                self.refresh_counts();
                 self.pending_action = Some(Action::Fetch);
                 None
             }
@@ -223,25 +989,259 @@ impl App {
         }
     }
 
-    /// Set a status bar message that auto-clears after ~3 seconds.
+    /// Record a watcher state change, timestamping transitions for the status bar.
+    pub fn set_watcher_active(&mut self, active: bool) {
+        if active && !self.watcher_active {
+            self.watcher_connected_at = Some(Instant::now());
+        } else if !active && self.watcher_active {
+            self.watcher_disconnected_at = Some(Instant::now());
+        }
+        self.watcher_active = active;
+    }
+
+    /// Set a status bar message that auto-clears after `config.status_secs`.
     pub fn set_status(&mut self, msg: String) {
+        self.set_status_for(msg, self.config.status_secs);
+    }
+
+    /// Set a status bar message that stays up for `secs` seconds.
+    pub fn set_status_for(&mut self, msg: String, secs: u8) {
+        // Enter only opens the exported PDF while its message is showing
+        self.exported_pdf = None;
         self.status_message = Some(msg);
-        self.status_ticks = 12; // ~3s at 250ms poll interval
+        self.status_ticks = self.config.ticks_for(secs);
+    }
+
+    /// Resize the list/body split (clamped) and briefly show the new ratio.
+    fn set_split(&mut self, pct: u16) {
+        self.pane_split_pct = pct.clamp(MIN_SPLIT_PCT, MAX_SPLIT_PCT);
+        // Body wrapping depends on the column width
+        self.refresh_search_matches();
+        self.set_status_for(
+            format!("Split {}% / {}%", self.pane_split_pct, 100 - self.pane_split_pct),
+            2,
+        );
+    }
+
+    /// Remember a reversible operation, dropping the oldest beyond the cap.
+    /// Returns the backup file of an evicted delete entry so it can be removed.
+    pub fn push_undo(&mut self, kind: UndoKind) -> Option<PathBuf> {
+        self.undo_stack.push(UndoEntry {
+            kind,
+            created_at: Instant::now(),
+        });
+        if self.undo_stack.len() > UNDO_STACK_LIMIT {
+            if let UndoKind::Delete { backup, .. } = self.undo_stack.remove(0).kind {
+                return Some(backup);
+            }
+        }
+        None
+    }
+
+    /// Drop delete entries older than `UNDO_BACKUP_TTL`, returning their backup
+    /// files for the caller to remove.
+    pub fn expire_undo(&mut self) -> Vec<PathBuf> {
+        let mut expired = Vec::new();
+        self.undo_stack.retain(|entry| match &entry.kind {
+            UndoKind::Delete { backup, .. } if entry.created_at.elapsed() >= UNDO_BACKUP_TTL => {
+                expired.push(backup.clone());
+                false
+            }
+            _ => true,
+        });
+        expired
     }
 
     /// Tick down the status message counter. Called when no event is received.
     pub fn tick_status(&mut self) {
+        self.poll_address_book();
+        self.poll_counts();
+        if self.operation_label.is_some() {
+            self.spinner.tick();
+        }
+        if !self.tooltip_visible
+            && self.hover_since.is_some_and(|since| since.elapsed() >= TOOLTIP_DELAY)
+        {
+            self.tooltip_visible = true;
+        }
         if self.status_ticks > 0 {
             self.status_ticks -= 1;
             if self.status_ticks == 0 {
                 self.status_message = None;
+                self.exported_pdf = None;
             }
         }
     }
 
     /// Get the currently selected email (if any).
     pub fn selected_email(&self) -> Option<&EmailEntry> {
-        self.emails.get(self.list_index)
+        self.email_index_at(self.list_index)
+            .and_then(|i| self.emails.get(i))
+    }
+
+    /// Map a list row to its index in `emails` (rows follow thread order in thread view).
+    pub fn email_index_at(&self, row: usize) -> Option<usize> {
+        if !self.thread_view {
+            return (row < self.emails.len()).then_some(row);
+        }
+        self.thread_groups.iter().flatten().nth(row).copied()
+    }
+
+    /// Rows in display order as (index into `emails`, is-a-reply) pairs.
+    pub fn display_rows(&self) -> Vec<(usize, bool)> {
+        if !self.thread_view {
+            return (0..self.emails.len()).map(|i| (i, false)).collect();
+        }
+        self.thread_groups
+            .iter()
+            .flat_map(|group| group.iter().enumerate().map(|(pos, &i)| (i, pos > 0)))
+            .collect()
+    }
+
+    /// Recompute `thread_groups` from the current `emails`.
+    fn rebuild_threads(&mut self) {
+        self.thread_groups.clear();
+        if !self.thread_view {
+            return;
+        }
+
+        // Emails are newest-first, so groups come out ordered by latest activity
+        let mut keys: Vec<String> = Vec::new();
+        for (i, e) in self.emails.iter().enumerate() {
+            let key = email::strip_subject_prefixes(&e.subject).to_lowercase();
+            match keys.iter().position(|k| *k == key) {
+                Some(g) => self.thread_groups[g].push(i),
+                None => {
+                    keys.push(key);
+                    self.thread_groups.push(vec![i]);
+                }
+            }
+        }
+
+        // Within a thread, show the conversation oldest-first
+        for group in &mut self.thread_groups {
+            group.sort_by(|&a, &b| self.emails[a].date_sort.cmp(&self.emails[b].date_sort));
+        }
+    }
+
+    /// Switch between flat and threaded list, keeping the same email selected.
+    fn toggle_thread_view(&mut self) {
+        let selected = self.email_index_at(self.list_index);
+        self.thread_view = !self.thread_view;
+        self.rebuild_threads();
+        if let Some(sel) = selected {
+            if let Some(row) = self.display_rows().iter().position(|&(i, _)| i == sel) {
+                self.list_index = row;
+            }
+        }
+        self.set_status(if self.thread_view {
+            "Thread view".to_string()
+        } else {
+            "Flat view".to_string()
+        });
+    }
+
+    /// Display label of `mailbox` (config override or built-in).
+    pub fn mailbox_label(&self, mailbox: Mailbox) -> &str {
+        &self.mailbox_display[mailbox.index()].label
+    }
+
+    /// Where the focus is: `Inbox › reply › Preview`, or `Inbox › search: "alice"`
+    /// while typing a search.
+    pub fn breadcrumb(&self) -> String {
+        let mut parts = vec![self.mailbox_label(self.active_mailbox).to_string()];
+        if self.focus == Focus::Search {
+            parts.push(format!("search: \"{}\"", self.search_query));
+            return parts.join(" \u{203a} ");
+        }
+        if self.focus != Focus::Sidebar {
+            if let Some(stem) = self.selected_email().and_then(|e| e.path.file_stem()) {
+                parts.push(ui::truncate(&stem.to_string_lossy(), 20));
+            }
+        }
+        parts.push(self.focus.label().to_string());
+        parts.join(" \u{203a} ")
+    }
+
+    /// Key handling mode shown at the left of the status bar: `VISUAL` while
+    /// selecting body lines, `SEARCH` / `INSERT` in text prompts, `CMD` while
+    /// a count or multi-key command is pending, otherwise `NORMAL`.
+    pub fn mode(&self) -> &'static str {
+        match self.focus {
+            _ if self.body_select_mode => "VISUAL",
+            Focus::Search | Focus::JumpSearch => "SEARCH",
+            Focus::Compose | Focus::DateFilter | Focus::SnoozeInput | Focus::FileInput => "INSERT",
+            _ if self.show_help && !self.help_query.is_empty() => "SEARCH",
+            _ if self.pending_count.is_some()
+                || self.g_pending
+                || self.filter_pending
+                || self.macro_prompt.is_some() =>
+            {
+                "CMD"
+            }
+            _ => "NORMAL",
+        }
+    }
+
+    /// Label of the mailbox the selected email lives in (its own one in global search).
+    pub fn selected_mailbox_label(&self) -> String {
+        self.mailbox_label(self.selected_origin_mailbox()).to_string()
+    }
+
+    /// Mailbox the selected email came from (see `origin_mailbox`).
+    pub fn selected_origin_mailbox(&self) -> Mailbox {
+        self.email_index_at(self.list_index)
+            .map_or(self.active_mailbox, |i| self.origin_mailbox(i))
+    }
+
+    /// Display icon of `mailbox` (config override or built-in).
+    pub fn mailbox_icon(&self, mailbox: Mailbox) -> &str {
+        &self.mailbox_display[mailbox.index()].icon
+    }
+
+    /// The active mailbox's list ordering.
+    pub fn sort(&self) -> SortState {
+        self.sort_state[self.active_mailbox.index()]
+    }
+
+    /// Re-apply the sort, keeping the same email selected.
+    fn resort(&mut self) {
+        let selected = self.selected_email().map(|e| e.path.clone());
+        self.filter_emails();
+        if let Some(path) = selected {
+            self.select_path(&path);
+        }
+        let SortState { column, asc } = self.sort();
+        let arrow = if asc { "\u{2191}" } else { "\u{2193}" };
+        self.set_status(format!("Sorted by {} {arrow}", column.label()));
+    }
+
+    /// Accent color for a contact, stable across sessions for the same address.
+    pub fn sender_color(&self, address: &str) -> Color {
+        let addr = address.to_lowercase();
+        if let Some(color) = self.sender_colors.borrow().get(&addr) {
+            return *color;
+        }
+        let mut hasher = DefaultHasher::new();
+        addr.as_bytes().hash(&mut hasher);
+        // Accent colors assigned to senders in the email list
+        let c = self.theme.colors();
+        let palette = [c.blue, c.mauve, c.peach, c.teal, c.yellow, c.red];
+        let color = palette[(hasher.finish() % palette.len() as u64) as usize];
+        self.sender_colors.borrow_mut().insert(addr, color);
+        color
+    }
+
+    /// The selected email's (path, body, signature) when it is signed but not yet
+    /// verified. The main loop starts the check on a worker thread so `update`
+    /// stays free of subprocesses.
+    pub fn pgp_pending(&self) -> Option<(PathBuf, String, String)> {
+        let email = self.selected_email()?;
+        let sig = email.pgp_signature.as_ref()?;
+        if self.pgp_status.contains_key(&email.path) {
+            return None;
+        }
+        Some((email.path.clone(), email.body.clone(), sig.clone()))
     }
 
     /// Get the file path of the currently selected email.
@@ -249,6 +1249,151 @@ impl App {
         self.selected_email().map(|e| e.path.clone())
     }
 
+    /// Reset per-email preview state (scroll offsets, detected links).
+    /// The scroll offset is restored if the email was read before.
+    fn reset_preview(&mut self) {
+        self.headers_selected_line = 0;
+        self.preview_scroll = if self.config.auto_preview { 0 } else { self.cached_scroll() };
+        self.preview_hscroll = 0;
+        if self.show_raw {
+            self.load_raw_source();
+        }
+        self.body_select_mode = false;
+        self.urls.clear();
+        self.url_index = 0;
+        self.refresh_search_matches();
+        self.refresh_body_stats();
+    }
+
+    /// Read the selected email's file for the source view.
+    fn load_raw_source(&mut self) {
+        self.raw_source = self
+            .selected_email()
+            .and_then(|e| std::fs::read_to_string(&e.path).ok());
+    }
+
+    /// Store the preview scroll offset of the selected email, evicting the
+    /// least recently used entry once the cache is full.
+    fn remember_scroll(&mut self) {
+        let Some(path) = self.selected_email_path() else {
+            return;
+        };
+        self.scroll_cache.retain(|(p, _)| *p != path);
+        if self.preview_scroll > 0 {
+            if self.scroll_cache.len() >= SCROLL_CACHE_LIMIT {
+                self.scroll_cache.pop_front();
+            }
+            self.scroll_cache.push_back((path, self.preview_scroll));
+        }
+    }
+
+    /// Remembered scroll offset of the selected email, or 0.
+    fn cached_scroll(&self) -> u16 {
+        let Some(email) = self.selected_email() else {
+            return 0;
+        };
+        self.scroll_cache
+            .iter()
+            .find(|(p, _)| *p == email.path)
+            .map_or(0, |(_, scroll)| *scroll)
+    }
+
+    /// Recount words and lines of the selected email's body.
+    fn refresh_body_stats(&mut self) {
+        let (words, lines) = self
+            .selected_email()
+            .map_or((0, 0), |e| (e.body.split_whitespace().count(), e.body.lines().count()));
+        self.body_word_count = words;
+        self.body_line_count = lines;
+    }
+
+    /// Recompute which rendered body lines contain the content-search query.
+    fn refresh_search_matches(&mut self) {
+        self.search_match_index = None;
+        self.search_match_lines.clear();
+        if !self.search_includes_body || self.search_query.is_empty() {
+            return;
+        }
+        let query = self.search_query.to_lowercase();
+        let width = ui::body_inner_width(self.terminal_width, self.pane_split_pct, &self.config);
+        if let Some(e) = self.selected_email() {
+            self.search_match_lines = ui::body_text_lines(&e.body, width, self.body_wrap)
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| line.to_lowercase().contains(&query))
+                .map(|(i, _)| i as u16)
+                .collect();
+        }
+    }
+
+    /// Rendered (wrapped) body lines of the selected email, as shown in the preview.
+    fn rendered_body_lines(&self) -> Vec<String> {
+        if let Some(source) = self.raw_source.as_ref().filter(|_| self.show_raw) {
+            return source.lines().map(str::to_string).collect();
+        }
+        let width = ui::body_inner_width(self.terminal_width, self.pane_split_pct, &self.config);
+        self.selected_email()
+            .map(|e| ui::body_text_lines(&e.body, width, self.body_wrap))
+            .unwrap_or_default()
+    }
+
+    /// Keys in visual selection mode: extend with j/k, copy with y, leave with Esc.
+    fn handle_visual_key(&mut self, key: KeyEvent) {
+        match self.keymap.lookup(&key, VISUAL_ACTIONS) {
+            Some("down") => {
+                let last = self.rendered_body_lines().len().saturating_sub(1) as u16;
+                self.body_select_end = (self.body_select_end + 1).min(last);
+            }
+            Some("up") => {
+                self.body_select_end = self.body_select_end.saturating_sub(1);
+            }
+            Some("yank") => {
+                let (start, end) = self.body_selection();
+                let text = self
+                    .rendered_body_lines()
+                    .get(start as usize..=end as usize)
+                    .map(|lines| lines.join("\n"))
+                    .unwrap_or_default();
+                self.pending_action = Some(Action::CopyText(text));
+                self.body_select_mode = false;
+            }
+            Some("back") => self.body_select_mode = false,
+            _ => {}
+        }
+
+        // Keep the moving end of the selection on screen
+        let borders = 2 * ui::border_width(&self.config);
+        let visible = self.body_area.get().height.saturating_sub(borders).max(1);
+        if self.body_select_end < self.preview_scroll {
+            self.preview_scroll = self.body_select_end;
+        } else if self.body_select_end >= self.preview_scroll + visible {
+            self.preview_scroll = self.body_select_end + 1 - visible;
+        }
+    }
+
+    /// Selected body line range (inclusive, ordered).
+    pub fn body_selection(&self) -> (u16, u16) {
+        let (a, b) = (self.body_select_start, self.body_select_end);
+        (a.min(b), a.max(b))
+    }
+
+    /// Scroll the body to the next (or previous) line matching the search query.
+    fn jump_to_search_match(&mut self, forward: bool) {
+        let len = self.search_match_lines.len();
+        if len == 0 {
+            self.set_status("No matches in this email".to_string());
+            return;
+        }
+        let next = match (self.search_match_index, forward) {
+            (None, true) => 0,
+            (None, false) => len - 1,
+            (Some(i), true) => (i + 1) % len,
+            (Some(i), false) => (i + len - 1) % len,
+        };
+        self.search_match_index = Some(next);
+        self.preview_scroll = self.search_match_lines[next];
+    }
+
     /// Invalidate cache for a mailbox so it reloads on next access.
     pub fn invalidate_cache(&mut self, mailbox: Mailbox) {
         self.email_cache[mailbox.index()] = None;
@@ -256,7 +1401,47 @@ impl App {
 
     /// Invalidate all caches.
     pub fn invalidate_all_caches(&mut self) {
-        self.email_cache = [None, None, None, None];
+        self.email_cache = [None, None, None, None, None, None];
+    }
+
+    /// Re-read the config file and .env (on SIGHUP). Keeps the current
+    /// settings and reports the error if the new config is invalid.
+    pub fn reload_config(&mut self) {
+        let loaded = Config::load().and_then(|config| {
+            let keymap = Keymap::with_overrides(&config.keys)?;
+            let mailbox_keymaps = Keymap::per_mailbox(&config, &keymap)?;
+            Ok((config, keymap, mailbox_keymaps))
+        });
+        let (config, keymap, mailbox_keymaps) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                self.set_status(format!("Config reload failed: {e:#}"));
+                return;
+            }
+        };
+        self.accounts = config.accounts.clone();
+        self.active_account = self.active_account.min(self.accounts.len().saturating_sub(1));
+        self.mailbox_dirs = resolve_mailbox_dirs(true, self.accounts.get(self.active_account));
+        self.update_shared_dirs();
+        // Compose suggestions come from the reloaded mailbox directories
+        self.address_book.clear();
+        self.address_book_rx = Some(spawn_address_scan(&self.mailbox_dirs));
+        self.mailbox_display = MailboxDisplay::from_config(&config);
+        self.theme = config.theme;
+        self.sender_colors.borrow_mut().clear();
+        let (list_columns, unknown_columns) = config.columns();
+        self.list_columns = list_columns;
+        self.config = config;
+        self.keymap = keymap;
+        self.mailbox_keymaps = mailbox_keymaps;
+        self.invalidate_all_caches();
+        self.reload_current_mailbox();
+        if unknown_columns.is_empty() {
+            self.set_status("Config reloaded".to_string());
+        } else {
+            let unknown = unknown_columns.join(", ");
+            self.set_status(format!("Config reloaded; unknown list_columns: {unknown}"));
+        }
     }
 
     /// Reload the currently active mailbox from disk.
@@ -269,8 +1454,106 @@ impl App {
         } else {
             self.list_index = 0;
         }
-        // Also refresh all mailbox counts
-        self.mailbox_counts = count_emails(&self.mailbox_dirs);
+        self.refresh_counts();
+        self.refresh_body_stats();
+    }
+
+    /// Recount every mailbox without loading any emails. Loaded mailboxes are
+    /// counted from the cache; the rest are counted from the configured
+    /// source in the background and arrive through `poll_counts`.
+    pub fn refresh_counts(&mut self) {
+        if self.config.email_source == EmailSource::Filesystem {
+            self.mailbox_counts = count_emails(&self.mailbox_dirs);
+        }
+        for i in 0..Mailbox::ALL.len() {
+            if let Some((total, unread)) = self.cached_counts(i) {
+                self.mailbox_counts[i] = total;
+                self.mailbox_unread[i] = unread;
+            }
+        }
+        self.count_rx = Some(spawn_count_scan(self.config.email_source, &self.mailbox_dirs));
+        self.refresh_snoozed_count();
+    }
+
+    /// (total, unread) emails in the cache of mailbox `idx`, if it is loaded
+    /// and current.
+    fn cached_counts(&self, idx: usize) -> Option<(usize, usize)> {
+        if self.cache_dirty[idx] {
+            return None;
+        }
+        let emails = self.email_cache[idx].as_ref()?;
+        Some((emails.len(), count_unread_entries(emails)))
+    }
+
+    /// Take the counts from the background scan once it is done.
+    fn poll_counts(&mut self) {
+        let Some(counts) = self.count_rx.as_ref().and_then(|rx| rx.try_recv().ok()) else {
+            return;
+        };
+        self.count_rx = None;
+        for (i, scanned) in counts.into_iter().enumerate() {
+            let (total, unread) = self.cached_counts(i).unwrap_or(scanned);
+            self.mailbox_counts[i] = total;
+            self.mailbox_unread[i] = unread;
+        }
+    }
+
+    /// Whether nothing is configured to read mail from, so first-run setup
+    /// should be offered. `email list --json` needs no directories.
+    pub fn needs_setup(&self) -> bool {
+        self.config.email_source == EmailSource::Filesystem
+            && self.mailbox_dirs.iter().all(Option::is_none)
+    }
+
+    fn refresh_snoozed_count(&mut self) {
+        self.snoozed_count = self.mailbox_dirs[Mailbox::Inbox.index()]
+            .as_ref()
+            .map_or(0, |dir| {
+                let (due, pending) = email::snoozed_emails(dir);
+                due.len() + pending
+            });
+    }
+
+    /// Fill the cache for `mailbox` from disk if it is not loaded yet.
+    fn ensure_loaded(&mut self, mailbox: Mailbox) {
+        let idx = mailbox.index();
+        if std::mem::take(&mut self.cache_dirty[idx]) {
+            self.email_cache[idx] = None;
+        }
+        if self.email_cache[idx].is_none() {
+            let Some(dir) = source_dir(self.config.email_source, &self.mailbox_dirs, mailbox)
+            else {
+                self.email_cache[idx] = Some(Vec::new());
+                return;
+            };
+            let source = self.config.email_source;
+            let (loaded, errors) = email::load_mailbox(source, &self.mailbox_dirs, mailbox, &dir);
+            self.email_cache[idx] = Some(loaded);
+            self.record_parse_errors(&dir, errors);
+        }
+    }
+
+    /// Replace the parse errors remembered for `dir`, pointing at the overlay
+    /// when there are more than before.
+    fn record_parse_errors(&mut self, dir: &Path, errors: Vec<(PathBuf, String)>) {
+        let before = self.parse_errors.len();
+        self.parse_errors.retain(|(path, _)| path.parent() != Some(dir));
+        let kept = self.parse_errors.len();
+        self.parse_errors.extend(errors);
+        if self.parse_errors.len() > before {
+            let new = self.parse_errors.len() - kept;
+            self.set_status(format!("{new} file(s) could not be read -- ?E for details"));
+        }
+    }
+
+    /// Subject of the most recent email in `mailbox`.
+    pub fn newest_subject(&mut self, mailbox: Mailbox) -> Option<String> {
+        self.ensure_loaded(mailbox);
+        self.email_cache[mailbox.index()]
+            .iter()
+            .flatten()
+            .max_by(|a, b| a.date_sort.cmp(&b.date_sort))
+            .map(|e| e.subject.clone())
     }
 
     /// Load (or use cached) emails for a mailbox and set as active.
@@ -280,33 +1563,92 @@ impl App {
         if changing {
             self.search_query.clear();
             self.search_includes_body = false;
+            self.search_fuzzy = false;
+            self.address_filter = None;
+            self.scheduled_only = false;
+            self.starred_only = false;
+            self.duplicates_only = false;
+            self.status_filter = None;
+            self.high_priority_only = false;
+            self.date_filter_input.clear();
+            self.date_range = None;
+            self.clear_jump_search();
         }
         let idx = mailbox.index();
-
-        if let Some(cached) = &self.email_cache[idx] {
-            self.emails = cached.clone();
-        } else {
-            let loaded = self.mailbox_dirs[idx]
-                .as_ref()
-                .map(|d| email::load_emails(d))
-                .unwrap_or_default();
-            self.email_cache[idx] = Some(loaded.clone());
-            self.emails = loaded;
-        }
+        self.ensure_loaded(mailbox);
 
         // Update count to match actual loaded data
-        self.mailbox_counts[idx] = self.emails.len();
+        self.mailbox_counts[idx] = self.email_cache[idx].as_ref().map_or(0, Vec::len);
+        // Keep any active filter applied across reloads of the same mailbox
+        self.filter_emails();
         if changing {
             self.list_index = 0;
+            self.reset_preview();
         }
     }
 
     fn handle_key(&mut self, key: KeyEvent) -> Option<Message> {
+        // Replayed keys are not recorded again; `@a` itself is
+        let replayed = !self.macro_playing.is_empty() || self.replaying_count_jump;
+        if let Some(slot) = self.recording.filter(|_| !replayed) {
+            self.macros.entry(slot).or_default().push(key);
+        }
+
+        // Any other key just dismisses the offer
+        if let Some(pdf) = self.exported_pdf.take() {
+            if key.code == KeyCode::Enter {
+                let target = pdf.to_string_lossy().to_string();
+                self.pending_action = Some(Action::OpenUrl(target));
+                return None;
+            }
+        }
+
         // If a confirmation dialog is open, handle it exclusively
         if self.confirm_dialog.is_some() {
             return self.handle_confirm_key(key);
         }
 
+        if self.template_picker.is_some() {
+            return self.handle_template_picker_key(key);
+        }
+
+        if self.diff_view.is_some() {
+            self.handle_diff_key(key);
+            return None;
+        }
+
+        if self.focus == Focus::MailboxPicker {
+            return self.handle_mailbox_picker_key(key);
+        }
+
+        if self.focus == Focus::AccountPicker {
+            return self.handle_account_picker_key(key);
+        }
+
+        if self.focus == Focus::Compose {
+            return self.handle_compose_key(key);
+        }
+
+        if self.focus == Focus::FileInput {
+            return self.handle_file_input_key(key);
+        }
+
+        if self.focus == Focus::Contacts {
+            return self.handle_contacts_key(key);
+        }
+
+        if self.show_parse_errors {
+            self.handle_parse_errors_key(key);
+            return None;
+        }
+
+        // Any key dismisses the stats overlay
+        if self.focus == Focus::Stats {
+            self.stats = None;
+            self.focus = Focus::List;
+            return None;
+        }
+
         // If help overlay is showing, handle it exclusively
         if self.show_help {
             return self.handle_help_key(key);
@@ -317,64 +1659,197 @@ impl App {
             return self.handle_search_key(key);
         }
 
+        if self.focus == Focus::DateFilter {
+            return self.handle_date_filter_key(key);
+        }
+
+        if self.focus == Focus::JumpSearch {
+            return self.handle_jump_key(key);
+        }
+
+        if self.focus == Focus::SnoozeInput {
+            return self.handle_snooze_key(key);
+        }
+
+        if self.filter_pending {
+            return self.handle_filter_key(key);
+        }
+
+        if let Some(prompt) = self.macro_prompt.take() {
+            self.handle_macro_key(prompt, key);
+            return None;
+        }
+
+        // Counts before list / body / header motions (`5j`, `10j`). A digit bound
+        // to another action (1-6 jump to mailboxes) starts a count too, and runs
+        // its action from `expired_count_jump` when no key follows in time.
+        let replaying = std::mem::take(&mut self.replaying_count_jump);
+        if matches!(self.focus, Focus::List | Focus::Preview | Focus::Headers)
+            && !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            && !replaying
+        {
+            if let Some(digit) = match key.code {
+                KeyCode::Char(c) => c.to_digit(10),
+                _ => None,
+            } {
+                match self.pending_count {
+                    Some(count) => {
+                        let count = count.saturating_mul(10).saturating_add(digit);
+                        self.pending_count = Some(count.min(MAX_COUNT));
+                        self.count_jump = None;
+                        return None;
+                    }
+                    None if digit > 0 => {
+                        self.pending_count = Some(digit);
+                        let bound = self.keymap.lookup(&key, GLOBAL_ACTIONS).is_some()
+                            || (self.focus == Focus::List
+                                && self.list_keymap().lookup(&key, LIST_ACTIONS).is_some());
+                        if bound {
+                            self.count_jump = Some((key, Instant::now()));
+                        }
+                        return None;
+                    }
+                    None => {}
+                }
+            }
+        }
+        // Any other key uses up the count (Esc simply drops it)
+        self.count_jump = None;
+        self.motion_count = self.pending_count.take().map_or(1, |n| n as usize);
+
+        // Without wrapping, h/l scroll sideways; h at the left edge falls through
+        if self.focus == Focus::Preview && !self.body_wrap && !self.body_select_mode {
+            match self.keymap.lookup(&key, NOWRAP_ACTIONS) {
+                Some("scroll_right") => {
+                    self.preview_hscroll = self.preview_hscroll.saturating_add(HSCROLL_STEP);
+                    return None;
+                }
+                Some("scroll_left") if self.preview_hscroll > 0 => {
+                    self.preview_hscroll = self.preview_hscroll.saturating_sub(HSCROLL_STEP);
+                    return None;
+                }
+                _ => {}
+            }
+        }
+
+        // h/l leave the headers sideways, to the list or the body (Tab keeps cycling)
+        if self.focus == Focus::Headers {
+            match self.keymap.lookup(&key, HEADERS_NAV_ACTIONS) {
+                Some("headers_to_list") => {
+                    self.focus = Focus::List;
+                    return None;
+                }
+                Some("headers_to_body") => {
+                    self.focus = Focus::Preview;
+                    return None;
+                }
+                _ => {}
+            }
+        }
+
         // Global keys (work in any pane)
-        match key.code {
-            KeyCode::Char('q') => return Some(Message::Quit),
-            KeyCode::Char('?') => {
+        match self.keymap.lookup(&key, GLOBAL_ACTIONS) {
+            Some("quit") => return Some(Message::Quit),
+            Some("record_macro") => {
+                self.g_pending = false;
+                if let Some(slot) = self.recording.take() {
+                    // Drop the `Q` that stopped the recording
+                    let keys = self.macros.entry(slot).or_default();
+                    keys.pop();
+                    let count = keys.len();
+                    self.set_status(format!("Recorded @{slot} ({count} keys)"));
+                } else {
+                    self.macro_prompt = Some(MacroPrompt::Record);
+                    self.set_status("Record macro: a-z".to_string());
+                }
+                return None;
+            }
+            Some("play_macro") => {
+                self.g_pending = false;
+                self.macro_prompt = Some(MacroPrompt::Play);
+                self.set_status("Play macro: a-z".to_string());
+                return None;
+            }
+            Some("help") => {
                 self.g_pending = false;
                 self.show_help = true;
+                self.help_query.clear();
+                return None;
+            }
+            Some("filter") if self.g_pending => {
+                self.start_global_search(false);
+                return None;
+            }
+            Some("search_body") if self.g_pending => {
+                self.start_global_search(true);
                 return None;
             }
-            KeyCode::Char('/') => {
+            Some("filter") => {
                 self.g_pending = false;
                 self.focus = Focus::Search;
                 self.search_query.clear();
                 self.search_includes_body = false;
+                self.address_filter = None;
                 self.reload_from_cache();
                 return None;
             }
-            KeyCode::Char('\\') => {
+            Some("search_body") => {
                 self.g_pending = false;
                 self.focus = Focus::Search;
                 self.search_query.clear();
                 self.search_includes_body = true;
+                self.address_filter = None;
                 self.reload_from_cache();
                 return None;
             }
-            KeyCode::Char('1') => {
+            Some("goto_inbox") => {
                 self.g_pending = false;
                 self.sidebar_index = 0;
                 self.switch_mailbox(Mailbox::Inbox);
                 self.focus = Focus::List;
                 return None;
             }
-            KeyCode::Char('2') => {
+            Some("goto_drafts") => {
                 self.g_pending = false;
                 self.sidebar_index = 1;
                 self.switch_mailbox(Mailbox::Drafts);
                 self.focus = Focus::List;
                 return None;
             }
-            KeyCode::Char('3') => {
+            Some("goto_sent") => {
                 self.g_pending = false;
                 self.sidebar_index = 2;
                 self.switch_mailbox(Mailbox::Sent);
                 self.focus = Focus::List;
                 return None;
             }
-            KeyCode::Char('4') => {
+            Some("goto_archive") => {
                 self.g_pending = false;
                 self.sidebar_index = 3;
                 self.switch_mailbox(Mailbox::Archive);
                 self.focus = Focus::List;
                 return None;
             }
-            KeyCode::Char('s') => {
+            Some("goto_spam") => {
+                self.g_pending = false;
+                self.sidebar_index = 4;
+                self.switch_mailbox(Mailbox::Spam);
+                self.focus = Focus::List;
+                return None;
+            }
+            Some("goto_trash") => {
+                self.g_pending = false;
+                self.sidebar_index = 5;
+                self.switch_mailbox(Mailbox::Trash);
+                self.focus = Focus::List;
+                return None;
+            }
+            Some("focus_sidebar") => {
                 self.g_pending = false;
                 self.focus = Focus::Sidebar;
                 return None;
             }
-            KeyCode::Tab | KeyCode::Char('l') => {
+            Some("focus_next") => {
                 self.g_pending = false;
                 // In sidebar, also select the highlighted mailbox
                 if self.focus == Focus::Sidebar {
@@ -386,18 +1861,105 @@ impl App {
                     Focus::List => Focus::Preview,
                     Focus::Preview => Focus::Headers,
                     Focus::Headers => Focus::Sidebar,
-                    Focus::Search => Focus::List,
+                    Focus::Search
+                    | Focus::DateFilter
+                    | Focus::MailboxPicker
+                    | Focus::Compose
+                    | Focus::Stats
+                    | Focus::SnoozeInput
+                    | Focus::Contacts
+                    | Focus::AccountPicker
+                    | Focus::JumpSearch
+                    | Focus::FileInput => Focus::List,
                 };
                 return None;
             }
-            KeyCode::BackTab | KeyCode::Char('h') => {
+            Some("undo") => {
+                self.g_pending = false;
+                self.pending_action = Some(Action::Undo);
+                return None;
+            }
+            Some("suspend") => {
+                self.g_pending = false;
+                self.pending_action = Some(Action::Suspend);
+                return None;
+            }
+            Some("shrink_list") => {
+                self.g_pending = false;
+                self.set_split(self.pane_split_pct.saturating_sub(5));
+                return None;
+            }
+            Some("grow_list") => {
+                self.g_pending = false;
+                self.set_split(self.pane_split_pct + 5);
+                return None;
+            }
+            Some("reset_split") => {
+                self.g_pending = false;
+                self.set_split(DEFAULT_SPLIT_PCT);
+                return None;
+            }
+            Some("toggle_cc") => {
+                self.g_pending = false;
+                self.show_cc = !self.show_cc;
+                self.set_status(match (self.show_cc, self.terminal_width >= ui::CC_MIN_WIDTH) {
+                    (true, true) => "CC column shown".to_string(),
+                    (true, false) => {
+                        format!("CC column needs a terminal {} columns wide", ui::CC_MIN_WIDTH)
+                    }
+                    (false, _) => "CC column hidden".to_string(),
+                });
+                return None;
+            }
+            Some("toggle_age") => {
+                self.g_pending = false;
+                self.age_highlighting = !self.age_highlighting;
+                self.set_status(if self.age_highlighting {
+                    "Dimming older emails".to_string()
+                } else {
+                    "Age dimming off".to_string()
+                });
+                return None;
+            }
+            Some("accounts") => {
+                self.g_pending = false;
+                if self.accounts.len() > 1 {
+                    self.account_picker_index = self.active_account;
+                    self.focus = Focus::AccountPicker;
+                } else {
+                    self.set_status("Add [[account]] tables to the config to switch".to_string());
+                }
+                return None;
+            }
+            Some("contacts") => {
+                self.g_pending = false;
+                self.open_contacts();
+                return None;
+            }
+            Some("cycle_theme") => {
+                self.g_pending = false;
+                self.theme = self.theme.next();
+                self.sender_colors.borrow_mut().clear();
+                self.set_status(format!("Theme: {}", self.theme.label()));
+                return None;
+            }
+            Some("focus_prev") => {
                 self.g_pending = false;
                 self.focus = match self.focus {
                     Focus::Sidebar => Focus::Headers,
                     Focus::Headers => Focus::Preview,
                     Focus::Preview => Focus::List,
                     Focus::List => Focus::Sidebar,
-                    Focus::Search => Focus::List,
+                    Focus::Search
+                    | Focus::DateFilter
+                    | Focus::MailboxPicker
+                    | Focus::Compose
+                    | Focus::Stats
+                    | Focus::SnoozeInput
+                    | Focus::Contacts
+                    | Focus::AccountPicker
+                    | Focus::JumpSearch
+                    | Focus::FileInput => Focus::List,
                 };
                 return None;
             }
@@ -410,253 +1972,1139 @@ impl App {
             Focus::List => self.handle_list_key(key),
             Focus::Headers => self.handle_headers_key(key),
             Focus::Preview => self.handle_preview_key(key),
-            Focus::Search => unreachable!(),
+            Focus::Search
+            | Focus::DateFilter
+            | Focus::MailboxPicker
+            | Focus::Compose
+            | Focus::Stats
+            | Focus::SnoozeInput
+            | Focus::Contacts
+            | Focus::AccountPicker
+            | Focus::JumpSearch
+            | Focus::FileInput => unreachable!(),
         }
     }
 
-    fn handle_confirm_key(&mut self, key: KeyEvent) -> Option<Message> {
-        match key.code {
-            KeyCode::Char('y') | KeyCode::Enter => {
-                if let Some(dialog) = self.confirm_dialog.take() {
-                    self.pending_action = Some(match dialog.action {
-                        ConfirmAction::Archive => Action::Archive,
-                        ConfirmAction::Delete => Action::Delete,
-                        ConfirmAction::Send => Action::Send,
-                        ConfirmAction::SendApproved => Action::SendApproved,
-                    });
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        // Overlays are keyboard-only
+        if self.confirm_dialog.is_some()
+            || self.template_picker.is_some()
+            || self.diff_view.is_some()
+            || self.show_help
+            || self.focus == Focus::MailboxPicker
+            || self.focus == Focus::Compose
+            || self.focus == Focus::Stats
+            || self.focus == Focus::SnoozeInput
+            || self.focus == Focus::Contacts
+            || self.focus == Focus::AccountPicker
+            || self.focus == Focus::FileInput
+        {
+            return;
+        }
+
+        let pos = Position::new(mouse.column, mouse.row);
+        let list_area = self.list_area.get();
+        let body_area = self.body_area.get();
+        let old_index = self.list_index;
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if list_area.contains(pos) => {
+                // First row of the table area is the column header
+                if let Some(rel) = (pos.y - list_area.y).checked_sub(1) {
+                    let row = self.list_offset.get() + rel as usize;
+                    if row < self.emails.len() {
+                        self.list_index = row;
+                    }
+                }
+                if !matches!(self.focus, Focus::Search | Focus::DateFilter | Focus::JumpSearch) {
+                    self.focus = Focus::List;
                 }
             }
-            KeyCode::Char('n') | KeyCode::Esc => {
-                self.confirm_dialog = None;
+            MouseEventKind::Down(MouseButton::Left)
+                if body_area.contains(pos)
+                    && !matches!(
+                        self.focus,
+                        Focus::Search | Focus::DateFilter | Focus::JumpSearch
+                    ) =>
+            {
+                self.focus = Focus::Preview;
+            }
+            MouseEventKind::ScrollDown if body_area.contains(pos) => {
+                self.preview_scroll = self.preview_scroll.saturating_add(3);
+            }
+            MouseEventKind::ScrollUp if body_area.contains(pos) => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(3);
+            }
+            MouseEventKind::ScrollDown
+                if list_area.contains(pos) && self.list_index + 1 < self.emails.len() =>
+            {
+                self.list_index += 1;
+            }
+            MouseEventKind::ScrollUp if list_area.contains(pos) => {
+                self.list_index = self.list_index.saturating_sub(1);
+            }
+            MouseEventKind::Moved => {
+                let row = list_area
+                    .contains(pos)
+                    .then(|| (pos.y - list_area.y).checked_sub(1))
+                    .flatten()
+                    .map(|rel| self.list_offset.get() + rel as usize)
+                    .filter(|&row| row < self.emails.len());
+                if row != self.hover_index {
+                    self.clear_hover();
+                    self.hover_index = row;
+                    self.hover_since = row.map(|_| Instant::now());
+                }
             }
             _ => {}
         }
-        None
+
+        if self.list_index != old_index {
+            self.reset_preview();
+        }
     }
 
-    fn handle_sidebar_key(&mut self, key: KeyEvent) -> Option<Message> {
-        self.g_pending = false;
-        match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if self.sidebar_index < Mailbox::ALL.len() - 1 {
-                    self.sidebar_index += 1;
-                }
-                None
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.sidebar_index = self.sidebar_index.saturating_sub(1);
-                None
-            }
-            KeyCode::Enter => {
-                let mailbox = Mailbox::ALL[self.sidebar_index];
-                self.switch_mailbox(mailbox);
-                self.focus = Focus::List;
-                None
+    /// Slot letter after `Q` (start recording) or `@` (replay).
+    fn handle_macro_key(&mut self, prompt: MacroPrompt, key: KeyEvent) {
+        self.status_message = None;
+        let KeyCode::Char(slot @ 'a'..='z') = key.code else {
+            return;
+        };
+        match prompt {
+            MacroPrompt::Record => {
+                self.macros.insert(slot, Vec::new());
+                self.recording = Some(slot);
             }
-            _ => None,
+            MacroPrompt::Play => self.play_macro(slot),
         }
     }
 
-    fn handle_headers_key(&mut self, key: KeyEvent) -> Option<Message> {
-        self.g_pending = false;
-        match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.headers_scroll = self.headers_scroll.saturating_add(1);
-                None
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.headers_scroll = self.headers_scroll.saturating_sub(1);
-                None
-            }
-            _ => None,
+    /// Queue the keys of macro `slot` ahead of anything already queued, so
+    /// nested `@b` calls replay in place.
+    fn play_macro(&mut self, slot: char) {
+        if self.recording == Some(slot) {
+            self.set_status(format!("Cannot play @{slot} while recording it"));
+            return;
+        }
+        if self.macro_playing.contains(&slot) {
+            self.macro_queue.clear();
+            self.macro_playing.clear();
+            self.set_status(format!("Recursive macro @{slot} aborted"));
+            return;
+        }
+        let Some(keys) = self.macros.get(&slot).filter(|keys| !keys.is_empty()) else {
+            self.set_status(format!("Macro @{slot} is empty"));
+            return;
+        };
+        self.macro_queue.push_front(MacroStep::End);
+        for key in keys.iter().rev() {
+            self.macro_queue.push_front(MacroStep::Key(*key));
         }
+        self.macro_playing.push(slot);
     }
 
-    fn handle_list_key(&mut self, key: KeyEvent) -> Option<Message> {
-        if self.emails.is_empty() {
-            self.g_pending = false;
-            // Allow fetch/sync/reconcile/new even when list is empty
-            match key.code {
-                KeyCode::Char('f') => self.pending_action = Some(Action::Fetch),
-                KeyCode::Char('F') => self.pending_action = Some(Action::Sync),
-                KeyCode::Char('S') => self.pending_action = Some(Action::Reconcile),
-                KeyCode::Char('n') => self.pending_action = Some(Action::NewDraft),
-                _ => {}
-            }
+    /// The bound digit that started the pending count, once it has waited
+    /// `COUNT_JUMP_TIMEOUT` without a motion; the main loop replays it as a
+    /// key so it runs its own action (e.g. `2` opens Drafts).
+    pub fn expired_count_jump(&mut self) -> Option<KeyEvent> {
+        let (key, since) = self.count_jump?;
+        if since.elapsed() < COUNT_JUMP_TIMEOUT {
             return None;
         }
+        self.count_jump = None;
+        self.pending_count = None;
+        self.replaying_count_jump = true;
+        Some(key)
+    }
 
-        let old_index = self.list_index;
-
-        match key.code {
-            // -- Navigation --
-            KeyCode::Char('g') => {
-                if self.g_pending {
-                    self.list_index = 0;
-                    self.g_pending = false;
-                } else {
-                    self.g_pending = true;
+    /// Next key of the macro being replayed, if any.
+    pub fn next_macro_key(&mut self) -> Option<KeyEvent> {
+        while let Some(step) = self.macro_queue.pop_front() {
+            match step {
+                MacroStep::Key(key) => return Some(key),
+                MacroStep::End => {
+                    self.macro_playing.pop();
                 }
             }
-            KeyCode::Char('G') => {
-                self.g_pending = false;
-                self.list_index = self.emails.len().saturating_sub(1);
-            }
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.g_pending = false;
-                if self.list_index < self.emails.len() - 1 {
-                    self.list_index += 1;
+        }
+        None
+    }
+
+    /// Forget the hovered list row and hide its tooltip.
+    fn clear_hover(&mut self) {
+        self.hover_index = None;
+        self.hover_since = None;
+        self.tooltip_visible = false;
+    }
+
+    fn handle_confirm_key(&mut self, key: KeyEvent) -> Option<Message> {
+        let Some(dialog) = &self.confirm_dialog else {
+            return None;
+        };
+        let count = dialog.buttons.len();
+        let choice = match key.code {
+            KeyCode::Left | KeyCode::Right if count > 0 => {
+                let step = if key.code == KeyCode::Left { count - 1 } else { 1 };
+                if let Some(dialog) = &mut self.confirm_dialog {
+                    dialog.selected_button = (dialog.selected_button + step) % count;
                 }
+                return None;
             }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.g_pending = false;
-                self.list_index = self.list_index.saturating_sub(1);
-            }
-            // -- Actions --
-            KeyCode::Enter | KeyCode::Char('e') => {
-                self.g_pending = false;
-                self.pending_action = Some(Action::EditCurrent);
-            }
-            KeyCode::Char('r') => {
-                self.g_pending = false;
-                self.pending_action = Some(Action::Reply(false));
-            }
-            KeyCode::Char('R') => {
-                self.g_pending = false;
-                self.pending_action = Some(Action::Reply(true));
+            KeyCode::Enter if dialog.selected_button < count => Some(dialog.selected_button),
+            KeyCode::Char(c) => dialog.buttons.iter().position(|b| b.key == c),
+            KeyCode::Esc => {
+                self.confirm_dialog = None;
+                return None;
             }
-            KeyCode::Char('a') => {
-                self.g_pending = false;
-                if let Some(email) = self.selected_email() {
-                    self.confirm_dialog = Some(ConfirmDialog {
-                        title: "Archive this email?".to_string(),
-                        detail: format!("{} - {}", email.from, email.subject),
-                        action: ConfirmAction::Archive,
-                    });
-                }
+            _ => None,
+        };
+        if let Some(choice) = choice {
+            if let Some(mut dialog) = self.confirm_dialog.take() {
+                self.pending_action = dialog.buttons.swap_remove(choice).action;
             }
-            KeyCode::Char('d') => {
-                self.g_pending = false;
-                if let Some(email) = self.selected_email() {
-                    self.confirm_dialog = Some(ConfirmDialog {
-                        title: "Delete this email?".to_string(),
-                        detail: format!("{} - {}", email.from, email.subject),
-                        action: ConfirmAction::Delete,
-                    });
-                }
+        }
+        None
+    }
+
+    fn handle_mailbox_picker_key(&mut self, key: KeyEvent) -> Option<Message> {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down
+                if self.mailbox_picker_index + 1 < Mailbox::ALL.len() =>
+            {
+                self.mailbox_picker_index += 1;
             }
-            KeyCode::Char('A') => {
-                self.g_pending = false;
-                self.pending_action = Some(Action::Approve);
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.mailbox_picker_index = self.mailbox_picker_index.saturating_sub(1);
             }
-            KeyCode::Char('x') => {
-                self.g_pending = false;
-                if let Some(email) = self.selected_email() {
-                    self.confirm_dialog = Some(ConfirmDialog {
-                        title: "Send this email?".to_string(),
-                        detail: format!("To: {} - {}", email.to, email.subject),
-                        action: ConfirmAction::Send,
-                    });
+            KeyCode::Enter => {
+                let dest = Mailbox::ALL[self.mailbox_picker_index];
+                let current = self.email_index_at(self.list_index).map(|i| self.origin_mailbox(i));
+                if current.is_none() {
+                    self.set_status("No email selected".to_string());
+                } else if current == Some(dest) {
+                    self.set_status(format!("Email is already in {}", self.mailbox_label(dest)));
+                } else if self.mailbox_dirs[dest.index()].is_none() {
+                    let label = self.mailbox_label(dest);
+                    self.set_status(format!("{label} directory is not configured"));
+                } else {
+                    self.pending_action = Some(Action::MoveTo(dest));
                 }
+                self.focus = Focus::List;
             }
-            KeyCode::Char('X') => {
-                self.g_pending = false;
-                self.confirm_dialog = Some(ConfirmDialog {
-                    title: "Send all approved emails?".to_string(),
-                    detail: format!("In {}", self.active_mailbox.label()),
-                    action: ConfirmAction::SendApproved,
-                });
-            }
-            KeyCode::Char('y') => {
-                self.g_pending = false;
-                self.pending_action = Some(Action::CopyPath);
-            }
-            KeyCode::Char('n') => {
-                self.g_pending = false;
-                self.pending_action = Some(Action::NewDraft);
-            }
-            KeyCode::Char('f') => {
-                self.g_pending = false;
-                self.pending_action = Some(Action::Fetch);
-            }
-            KeyCode::Char('F') => {
-                self.g_pending = false;
-                self.pending_action = Some(Action::Sync);
-            }
-            KeyCode::Char('S') => {
-                self.g_pending = false;
-                self.pending_action = Some(Action::Reconcile);
-            }
-
-            _ => {
-                self.g_pending = false;
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.focus = Focus::List;
             }
+            _ => {}
         }
-
-        // Reset scroll when selection changes
-        if self.list_index != old_index {
-            self.headers_scroll = 0;
-            self.preview_scroll = 0;
-        }
-
         None
     }
 
-    fn handle_preview_key(&mut self, key: KeyEvent) -> Option<Message> {
-        self.g_pending = false;
+    fn handle_account_picker_key(&mut self, key: KeyEvent) -> Option<Message> {
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.preview_scroll = self.preview_scroll.saturating_add(1);
-                None
+            KeyCode::Char('j') | KeyCode::Down
+                if self.account_picker_index + 1 < self.accounts.len() =>
+            {
+                self.account_picker_index += 1;
             }
             KeyCode::Char('k') | KeyCode::Up => {
-                self.preview_scroll = self.preview_scroll.saturating_sub(1);
-                None
-            }
-            KeyCode::Char('d') => {
-                // Half-page down (approximate with 10 lines)
-                self.preview_scroll = self.preview_scroll.saturating_add(10);
-                None
+                self.account_picker_index = self.account_picker_index.saturating_sub(1);
             }
-            KeyCode::Char('u') => {
-                // Half-page up
-                self.preview_scroll = self.preview_scroll.saturating_sub(10);
-                None
+            KeyCode::Enter => {
+                self.focus = Focus::List;
+                if self.account_picker_index != self.active_account {
+                    self.switch_account(self.account_picker_index);
+                }
             }
-            KeyCode::Esc => {
+            KeyCode::Esc | KeyCode::Char('q') => {
                 self.focus = Focus::List;
-                None
             }
-            _ => None,
+            _ => {}
         }
+        None
     }
 
-    fn handle_help_key(&mut self, key: KeyEvent) -> Option<Message> {
+    /// Show the mailboxes of `accounts[index]`, reloading everything from its directories.
+    fn switch_account(&mut self, index: usize) {
+        let Some(account) = self.accounts.get(index) else {
+            return;
+        };
+        let name = account.name.clone();
+        self.mailbox_dirs = resolve_mailbox_dirs(false, Some(account));
+        self.active_account = index;
+        self.update_shared_dirs();
+        // Compose suggestions come from the new account's mailboxes
+        self.address_book.clear();
+        self.address_book_rx = Some(spawn_address_scan(&self.mailbox_dirs));
+        self.parse_errors.clear();
+        self.list_index = 0;
+        self.invalidate_all_caches();
+        self.reload_current_mailbox();
+        self.reset_preview();
+        self.set_status(format!("Switched to account {name}"));
+    }
+
+    fn handle_template_picker_key(&mut self, key: KeyEvent) -> Option<Message> {
+        let picker = self.template_picker.as_mut()?;
         match key.code {
-            KeyCode::Char('?') | KeyCode::Esc => {
-                self.show_help = false;
+            KeyCode::Char('j') | KeyCode::Down
+                if picker.selected + 1 < picker.templates.len() =>
+            {
+                picker.selected += 1;
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                picker.selected = picker.selected.saturating_sub(1);
+            }
+            KeyCode::Enter => {
+                if let Some(name) = picker.templates.get(picker.selected) {
+                    self.pending_action = Some(Action::NewFromTemplate(name.clone()));
+                }
+                self.template_picker = None;
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.template_picker = None;
             }
             _ => {}
         }
         None
     }
 
-    fn handle_search_key(&mut self, key: KeyEvent) -> Option<Message> {
-        match key.code {
+    fn handle_diff_key(&mut self, key: KeyEvent) {
+        let page = self.terminal_height / 2;
+        let Some(view) = self.diff_view.as_mut() else {
+            return;
+        };
+        let last = view.lines.len().saturating_sub(1) as u16;
+        match self.keymap.lookup(&key, PREVIEW_ACTIONS) {
+            Some("down") => view.scroll = (view.scroll + 1).min(last),
+            Some("up") => view.scroll = view.scroll.saturating_sub(1),
+            Some("half_page_down") => view.scroll = view.scroll.saturating_add(page).min(last),
+            Some("half_page_up") => view.scroll = view.scroll.saturating_sub(page),
+            _ if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) => self.diff_view = None,
+            _ => {}
+        }
+    }
+
+    /// Parse errors overlay: j/k and half-page motions scroll, any other key closes it.
+    fn handle_parse_errors_key(&mut self, key: KeyEvent) {
+        let page = self.terminal_height / 2;
+        // Each unreadable file takes two lines: its path and the reason
+        let last = (self.parse_errors.len() * 2).saturating_sub(1) as u16;
+        let scroll = &mut self.parse_errors_scroll;
+        match self.keymap.lookup(&key, PREVIEW_ACTIONS) {
+            Some("down") => *scroll = (*scroll + 1).min(last),
+            Some("up") => *scroll = scroll.saturating_sub(1),
+            Some("half_page_down") => *scroll = scroll.saturating_add(page).min(last),
+            Some("half_page_up") => *scroll = scroll.saturating_sub(page),
+            _ => self.show_parse_errors = false,
+        }
+    }
+
+    /// The header line under the headers cursor.
+    pub fn focused_header(&self) -> Option<HeaderLine> {
+        let lines = ui::header_lines(self.selected_email()?);
+        lines.get(self.headers_selected_line.min(lines.len() - 1)).copied()
+    }
+
+    /// Index of the attachment `o` acts on: the one under the cursor, or the
+    /// first attachment when the cursor is on a header field.
+    pub fn focused_attachment(&self) -> Option<usize> {
+        let email = self.selected_email()?;
+        if email.attachments.is_empty() {
+            return None;
+        }
+        match self.focused_header() {
+            Some(HeaderLine::Attachment(idx)) => Some(idx),
+            _ => Some(0),
+        }
+    }
+
+    /// Raw address(es) on the focused From/To/Cc line.
+    fn focused_address(&self) -> Option<String> {
+        let email = self.selected_email()?;
+        match self.focused_header()? {
+            HeaderLine::From => Some(email.from_addr.clone()),
+            HeaderLine::ReplyTo => email.reply_to.as_deref().map(email::extract_email_address),
+            HeaderLine::To => Some(email.to_addr.clone()),
+            HeaderLine::Cc => email.cc.as_deref().map(email::extract_email_addresses),
+            _ => None,
+        }
+        .filter(|addr| !addr.is_empty())
+    }
+
+    fn open_focused_attachment(&mut self) {
+        let Some(email) = self.selected_email() else {
+            return;
+        };
+        let Some(idx) = self.focused_attachment() else {
+            self.set_status("No attachments".to_string());
+            return;
+        };
+        let attachment = &email.attachments[idx];
+        match email.attachment_path(attachment) {
+            Some(path) => {
+                self.pending_action =
+                    Some(Action::OpenAttachment(path, attachment.mime_type.clone()));
+            }
+            None => {
+                let msg = format!("Attachment not found on disk: {}", attachment.filename);
+                self.set_status(msg);
+            }
+        }
+    }
+
+    /// Open the template picker listing `<drafts>/templates/*.md`.
+    fn open_template_picker(&mut self) {
+        let templates: Vec<String> = self.mailbox_dirs[Mailbox::Drafts.index()]
+            .as_ref()
+            .map(|d| email::list_templates(d))
+            .unwrap_or_default()
+            .iter()
+            .map(|p| p.file_stem().unwrap_or_default().to_string_lossy().to_string())
+            .collect();
+        if templates.is_empty() {
+            self.set_status("No templates in drafts/templates/".to_string());
+        } else {
+            self.template_picker = Some(TemplatePicker {
+                templates,
+                selected: 0,
+            });
+        }
+    }
+
+    fn handle_sidebar_key(&mut self, key: KeyEvent) -> Option<Message> {
+        let g_pending = std::mem::take(&mut self.g_pending);
+        match self.keymap.lookup(&key, SIDEBAR_ACTIONS) {
+            Some("top") if g_pending => {
+                self.sidebar_index = 0;
+                None
+            }
+            Some("top") => {
+                self.g_pending = true;
+                None
+            }
+            Some("bottom") => {
+                self.sidebar_index = Mailbox::ALL.len() - 1;
+                None
+            }
+            Some("mark_all_read") => {
+                self.mark_all_read(Mailbox::ALL[self.sidebar_index]);
+                None
+            }
+            Some("sidebar_position") => {
+                let mailbox = Mailbox::ALL[self.sidebar_index];
+                self.set_status(format!(
+                    "Sidebar: {} ({}/{})",
+                    self.mailbox_label(mailbox),
+                    self.sidebar_index + 1,
+                    Mailbox::ALL.len()
+                ));
+                None
+            }
+            Some("down") => {
+                if self.sidebar_index < Mailbox::ALL.len() - 1 {
+                    self.sidebar_index += 1;
+                }
+                None
+            }
+            Some("up") => {
+                self.sidebar_index = self.sidebar_index.saturating_sub(1);
+                None
+            }
+            Some("select") => {
+                let mailbox = Mailbox::ALL[self.sidebar_index];
+                self.switch_mailbox(mailbox);
+                self.focus = Focus::List;
+                None
+            }
+            Some("reconcile") => {
+                self.confirm_reconcile();
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn handle_headers_key(&mut self, key: KeyEvent) -> Option<Message> {
+        self.g_pending = false;
+        match self.keymap.lookup(&key, HEADERS_ACTIONS) {
+            Some("down") => {
+                let count = self.selected_email().map_or(0, |e| ui::header_lines(e).len());
+                let line = self.headers_selected_line + self.motion_count;
+                self.headers_selected_line = line.min(count.saturating_sub(1));
+                None
+            }
+            Some("up") => {
+                self.headers_selected_line =
+                    self.headers_selected_line.saturating_sub(self.motion_count);
+                None
+            }
+            Some("select") => {
+                match self.focused_header() {
+                    Some(HeaderLine::From) => self.pending_action = Some(Action::Reply(false)),
+                    Some(HeaderLine::Attachment(_)) => self.open_focused_attachment(),
+                    _ => {}
+                }
+                None
+            }
+            Some("copy_address") => {
+                match self.focused_address() {
+                    Some(addr) => self.pending_action = Some(Action::CopyAddress(addr)),
+                    None => self.set_status("No address on this line".to_string()),
+                }
+                None
+            }
+            Some("open_attachment") => {
+                self.open_focused_attachment();
+                None
+            }
+            Some("filter_from") => {
+                self.filter_by_address(AddressField::From);
+                None
+            }
+            Some("filter_to") => {
+                self.filter_by_address(AddressField::To);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Filter the list to emails sharing the selected email's sender (or first
+    /// recipient), without going through the search prompt.
+    fn filter_by_address(&mut self, field: AddressField) {
+        let Some(email) = self.selected_email() else {
+            return;
+        };
+        // `from`/`to` hold display names; the bare addresses are kept alongside
+        let (addr, name) = match field {
+            AddressField::From => (email.from_addr.clone(), email.from.clone()),
+            AddressField::To => {
+                let addr = email.to_addr.split(',').next().unwrap_or_default().trim().to_string();
+                let name = email::split_address_list(&email.to).into_iter().next();
+                (addr, name.unwrap_or_default())
+            }
+        };
+        if addr.is_empty() {
+            self.set_status("No address to filter by".to_string());
+            return;
+        }
+        let name = email::extract_display_name(&name);
+        let name = if name.is_empty() { addr.clone() } else { name };
+        self.apply_address_filter(field, addr, name);
+    }
+
+    /// Show only emails whose `field` contains `addr`, labelled `name` in the title.
+    fn apply_address_filter(&mut self, field: AddressField, addr: String, name: String) {
+        self.search_query = addr;
+        self.search_includes_body = false;
+        self.address_filter = Some((field, name));
+        self.apply_search_filter();
+        self.focus = Focus::List;
+    }
+
+    /// Ask before a full reconcile, which can take a long time.
+    fn confirm_reconcile(&mut self) {
+        self.confirm_dialog = Some(ConfirmDialog::yes_no(
+            "Reconcile mailbox?",
+            "Reconcile re-fetches all headers and may take several minutes".to_string(),
+            Action::Reconcile,
+        ));
+    }
+
+    /// Bindings for list keys: the active mailbox's own keymap when it has
+    /// `[mailbox.<name>.keys]`, else the global one.
+    pub fn list_keymap(&self) -> &Keymap {
+        self.mailbox_keymaps[self.active_mailbox.index()]
+            .as_ref()
+            .unwrap_or(&self.keymap)
+    }
+
+    /// Whether a list action applies in the active mailbox (some keys are reused
+    /// with a mailbox-specific meaning).
+    fn list_action_available(&self, action: &str) -> bool {
+        match action {
+            "show_scheduled" => self.active_mailbox == Mailbox::Drafts,
+            "mark_spam" => self.active_mailbox != Mailbox::Spam,
+            "empty_trash" => self.active_mailbox == Mailbox::Trash,
+            "delete_duplicate" => self.duplicates_only,
+            "snooze" => self.active_mailbox == Mailbox::Inbox && !self.global_search_active,
+            "attach_file" => self.active_mailbox == Mailbox::Drafts,
+            "diff_template" => self.active_mailbox == Mailbox::Drafts,
+            "mark_all_read" => !self.global_search_active,
+            _ => true,
+        }
+    }
+
+    /// Rows to jump for a list page divided by `divisor`. Falls back to the
+    /// terminal height before the list has been drawn.
+    fn list_page(&self, divisor: u16) -> usize {
+        let rows = match self.list_visible_rows.get() {
+            0 => self.terminal_height / 2,
+            rows => rows,
+        };
+        (rows / divisor).max(1) as usize
+    }
+
+    /// Move the list cursor by `delta` rows, scrolling the view along with it.
+    fn page_list(&mut self, delta: isize) {
+        self.g_pending = false;
+        let delta = delta * self.motion_count as isize;
+        let last = self.emails.len().saturating_sub(1);
+        self.list_index = self.list_index.saturating_add_signed(delta).min(last);
+    }
+
+    /// `lines` scaled by the motion count, for scroll offsets.
+    fn count_u16(&self, lines: u16) -> u16 {
+        u16::try_from(self.motion_count).unwrap_or(u16::MAX).saturating_mul(lines)
+    }
+
+    fn handle_list_key(&mut self, key: KeyEvent) -> Option<Message> {
+        if self.jump_query.is_some() {
+            match self.keymap.lookup(&key, JUMP_ACTIONS) {
+                Some("next_match") => {
+                    self.step_jump_match(1);
+                    return None;
+                }
+                Some("prev_match") => {
+                    self.step_jump_match(-1);
+                    return None;
+                }
+                Some("back") => {
+                    self.clear_jump_search();
+                    return None;
+                }
+                _ => {}
+            }
+        }
+        if self.global_search_active {
+            if self.keymap.matches("back", &key) {
+                self.g_pending = false;
+                self.exit_global_search();
+                return None;
+            }
+            if key.code == KeyCode::Enter {
+                self.g_pending = false;
+                self.open_global_result();
+                return None;
+            }
+        }
+        let actions: Vec<&'static str> = LIST_ACTIONS
+            .iter()
+            .copied()
+            .filter(|a| self.list_action_available(a))
+            .collect();
+        let action = self.list_keymap().lookup(&key, &actions);
+
+        if self.emails.is_empty() {
+            let g_pending = std::mem::take(&mut self.g_pending);
+            // Allow fetch/sync/reconcile/new even when list is empty
+            match action {
+                Some("top") => self.g_pending = true,
+                Some("toggle_star") if g_pending => self.toggle_starred_only(),
+                Some("show_duplicates") => self.toggle_duplicates_only(),
+                Some("fetch") => self.start_filter_prefix(),
+                Some("sync") => self.pending_action = Some(Action::Sync),
+                Some("reconcile") => self.confirm_reconcile(),
+                Some("new") => self.open_compose(),
+                Some("jump_search") => self.start_jump_search(),
+                Some("new_from_template") => self.open_template_picker(),
+                Some("show_scheduled") => self.toggle_scheduled_only(),
+                Some("date_filter") => self.focus = Focus::DateFilter,
+                _ => {}
+            }
+            return None;
+        }
+
+        let old_index = self.list_index;
+
+        match action {
+            // -- Navigation --
+            Some("top") => {
+                if self.g_pending {
+                    self.list_index = 0;
+                    self.g_pending = false;
+                } else {
+                    self.g_pending = true;
+                }
+            }
+            Some("bottom") => {
+                self.g_pending = false;
+                // With a count, `3G` is the third row from the bottom
+                self.list_index = self.emails.len().saturating_sub(self.motion_count);
+            }
+            Some("down") => {
+                self.g_pending = false;
+                self.list_index =
+                    (self.list_index + self.motion_count).min(self.emails.len() - 1);
+            }
+            Some("up") => {
+                self.g_pending = false;
+                self.list_index = self.list_index.saturating_sub(self.motion_count);
+            }
+            Some("half_page_down") => self.page_list(self.list_page(2) as isize),
+            Some("half_page_up") => self.page_list(-(self.list_page(2) as isize)),
+            Some("page_down") => self.page_list(self.list_page(1) as isize),
+            Some("page_up") => self.page_list(-(self.list_page(1) as isize)),
+            // -- Actions --
+            Some("edit") => {
+                self.g_pending = false;
+                self.pending_action = Some(Action::EditCurrent);
+            }
+            Some("reply") => {
+                self.g_pending = false;
+                self.pending_action = Some(Action::Reply(false));
+            }
+            Some("reply_all") => {
+                self.g_pending = false;
+                self.pending_action = Some(Action::Reply(true));
+            }
+            Some("forward") => {
+                self.g_pending = false;
+                if let Some(email) = self.selected_email() {
+                    self.confirm_dialog = Some(ConfirmDialog::yes_no(
+                        "Forward this email?",
+                        email.subject.clone(),
+                        Action::Forward,
+                    ));
+                }
+            }
+            Some("archive") => {
+                self.g_pending = false;
+                if let Some(email) = self.selected_email() {
+                    self.confirm_dialog = Some(ConfirmDialog::yes_no(
+                        "Archive this email?",
+                        format!("{} - {}", email.from, email.subject),
+                        Action::Archive,
+                    ));
+                }
+            }
+            Some("move") => {
+                self.g_pending = false;
+                self.mailbox_picker_index = self.active_mailbox.index();
+                self.focus = Focus::MailboxPicker;
+            }
+            Some("mark_spam") => {
+                self.g_pending = false;
+                if let Some(email) = self.selected_email() {
+                    self.confirm_dialog = Some(ConfirmDialog::yes_no(
+                        "Mark as spam and move?",
+                        format!("{} - {}", email.from, email.subject),
+                        Action::MarkSpam,
+                    ));
+                }
+            }
+            Some("delete") => {
+                self.g_pending = false;
+                let origin = self.email_index_at(self.list_index).map(|i| self.origin_mailbox(i));
+                let has_trash = self.mailbox_dirs[Mailbox::Trash.index()].is_some();
+                if let Some(email) = self.selected_email() {
+                    let detail = format!("{} - {}", email.from, email.subject);
+                    // Without a trash dir, or inside it, deleting is permanent
+                    self.confirm_dialog = Some(if has_trash && origin != Some(Mailbox::Trash) {
+                        ConfirmDialog {
+                            title: "Move this email to the trash?".to_string(),
+                            detail,
+                            buttons: vec![
+                                DialogButton::new("yes", 'y', Some(Action::Delete)),
+                                DialogButton::new(
+                                    "permanently",
+                                    'p',
+                                    Some(Action::PermanentDelete),
+                                ),
+                                DialogButton::new("no", 'n', None),
+                            ],
+                            selected_button: 2,
+                        }
+                    } else {
+                        ConfirmDialog::new_destructive(
+                            "Delete this email permanently?",
+                            detail,
+                            Action::PermanentDelete,
+                        )
+                    });
+                }
+            }
+            Some("empty_trash") => {
+                self.g_pending = false;
+                self.confirm_dialog = Some(ConfirmDialog::new_destructive(
+                    "Empty the trash?",
+                    format!("{} emails will be deleted permanently", self.emails.len()),
+                    Action::EmptyTrash,
+                ));
+            }
+            Some("approve") => {
+                self.g_pending = false;
+                self.pending_action = Some(Action::Approve);
+            }
+            Some("send") => {
+                self.g_pending = false;
+                if let Some(email) = self.selected_email() {
+                    let warnings = email::validate_draft(email);
+                    self.confirm_dialog = Some(if warnings.is_empty() {
+                        ConfirmDialog::new_destructive(
+                            "Send this email?",
+                            format!("To: {} - {}", email.to, email.subject),
+                            Action::Send,
+                        )
+                    } else {
+                        ConfirmDialog::send_anyway(&warnings, Action::Send)
+                    });
+                }
+            }
+            Some("send_approved") => {
+                self.g_pending = false;
+                let mut warnings: Vec<String> = Vec::new();
+                let approved = self.email_cache[self.active_mailbox.index()]
+                    .iter()
+                    .flatten()
+                    .filter(|e| e.status == "approved");
+                for warning in approved.flat_map(email::validate_draft) {
+                    if !warnings.contains(&warning) {
+                        warnings.push(warning);
+                    }
+                }
+                self.confirm_dialog = Some(if warnings.is_empty() {
+                    ConfirmDialog::new_destructive(
+                        "Send all approved emails?",
+                        format!("In {}", self.mailbox_label(self.active_mailbox)),
+                        Action::SendApproved,
+                    )
+                } else {
+                    ConfirmDialog::send_anyway(&warnings, Action::SendApproved)
+                });
+            }
+            Some("copy_path") => {
+                self.g_pending = false;
+                self.pending_action = Some(Action::CopyPath);
+            }
+            Some("open_dir") => {
+                self.g_pending = false;
+                self.pending_action = Some(Action::OpenDir);
+            }
+            Some("jump_search") => {
+                self.g_pending = false;
+                self.start_jump_search();
+            }
+            Some("new") => {
+                self.g_pending = false;
+                self.open_compose();
+            }
+            Some("new_from_template") => {
+                self.g_pending = false;
+                self.open_template_picker();
+            }
+            Some("fetch") => {
+                self.g_pending = false;
+                self.start_filter_prefix();
+            }
+            Some("sync") => {
+                self.g_pending = false;
+                self.pending_action = Some(Action::Sync);
+            }
+            Some("reconcile") => {
+                self.g_pending = false;
+                self.confirm_reconcile();
+            }
+            Some("show_scheduled") => {
+                self.g_pending = false;
+                self.toggle_scheduled_only();
+            }
+            Some("thread_view") => {
+                self.g_pending = false;
+                self.toggle_thread_view();
+            }
+            Some("date_filter") => {
+                self.g_pending = false;
+                self.focus = Focus::DateFilter;
+            }
+            Some("export_mbox") => {
+                self.g_pending = false;
+                let paths = self.emails.iter().map(|e| e.path.clone()).collect();
+                self.pending_action = Some(Action::ExportMbox(paths));
+            }
+            Some("mark_all_read") => {
+                self.g_pending = false;
+                self.mark_all_read(self.active_mailbox);
+            }
+            Some("diff_template") => {
+                self.g_pending = false;
+                match self.selected_email().map(|e| e.template.is_some()) {
+                    Some(true) => self.pending_action = Some(Action::DiffFromTemplate),
+                    Some(false) => {
+                        self.set_status("This draft was not created from a template".to_string())
+                    }
+                    None => {}
+                }
+            }
+            Some("export_pdf") => {
+                self.g_pending = false;
+                if let Some(path) = self.selected_email_path() {
+                    self.pending_action = Some(Action::ExportPdf(path));
+                }
+            }
+            Some("snooze") => {
+                self.g_pending = false;
+                self.snooze_input.clear();
+                self.focus = Focus::SnoozeInput;
+            }
+            Some("attach_file") => {
+                self.g_pending = false;
+                if self.selected_email_path().is_some() {
+                    self.attach_input.clear();
+                    self.focus = Focus::FileInput;
+                }
+            }
+            Some("sort_column") => {
+                self.g_pending = false;
+                let state = &mut self.sort_state[self.active_mailbox.index()];
+                state.column = state.column.next();
+                self.resort();
+            }
+            Some("sort_order") => {
+                self.g_pending = false;
+                let state = &mut self.sort_state[self.active_mailbox.index()];
+                state.asc = !state.asc;
+                self.resort();
+            }
+            Some("filter_from") => {
+                self.g_pending = false;
+                self.filter_by_address(AddressField::From);
+            }
+            Some("focus_headers") => {
+                self.g_pending = false;
+                self.focus = Focus::Headers;
+            }
+            Some("filter_to") => {
+                self.g_pending = false;
+                self.filter_by_address(AddressField::To);
+            }
+            Some("show_duplicates") => {
+                self.g_pending = false;
+                self.toggle_duplicates_only();
+            }
+            Some("delete_duplicate") => {
+                self.g_pending = false;
+                match self.selected_email() {
+                    Some(e) if self.duplicate_paths.contains(&e.path) => {
+                        self.pending_action = Some(Action::RemoveDuplicates(vec![e.path.clone()]));
+                    }
+                    _ => self.set_status("Not a duplicate (the newest copy is kept)".to_string()),
+                }
+            }
+            Some("toggle_star") if self.g_pending => {
+                self.g_pending = false;
+                self.toggle_starred_only();
+            }
+            Some("toggle_star") => {
+                self.pending_action = Some(Action::ToggleStar);
+            }
+
+            _ => {
+                self.g_pending = false;
+            }
+        }
+
+        // Reset scroll when selection changes
+        if self.list_index != old_index {
+            self.reset_preview();
+        }
+
+        None
+    }
+
+    fn handle_preview_key(&mut self, key: KeyEvent) -> Option<Message> {
+        self.g_pending = false;
+        if self.body_select_mode {
+            self.handle_visual_key(key);
+            return None;
+        }
+        match self.keymap.lookup(&key, PREVIEW_ACTIONS) {
+            Some("visual_select") => {
+                if self.selected_email().is_some() {
+                    self.body_select_mode = true;
+                    self.body_select_start = self.preview_scroll;
+                    self.body_select_end = self.preview_scroll;
+                }
+                None
+            }
+            Some("down") => {
+                self.preview_scroll = self.preview_scroll.saturating_add(self.count_u16(1));
+                None
+            }
+            Some("up") => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(self.count_u16(1));
+                None
+            }
+            Some("half_page_down") => {
+                // Half-page down (approximate with 10 lines)
+                self.preview_scroll = self.preview_scroll.saturating_add(self.count_u16(10));
+                None
+            }
+            Some("half_page_up") => {
+                // Half-page up
+                self.preview_scroll = self.preview_scroll.saturating_sub(self.count_u16(10));
+                None
+            }
+            Some("next_link") => {
+                self.next_url();
+                None
+            }
+            Some("next_match") => {
+                self.jump_to_search_match(true);
+                None
+            }
+            Some("prev_match") => {
+                self.jump_to_search_match(false);
+                None
+            }
+            Some("open_link") => {
+                if let Some((_, url)) = self.urls.get(self.url_index) {
+                    self.pending_action = Some(Action::OpenUrl(url.clone()));
+                }
+                None
+            }
+            Some("edit") => {
+                self.pending_action = Some(Action::EditBody);
+                None
+            }
+            Some("toggle_source") => {
+                self.show_raw = !self.show_raw;
+                self.preview_scroll = 0;
+                if self.show_raw {
+                    self.load_raw_source();
+                } else {
+                    self.raw_source = None;
+                }
+                None
+            }
+            Some("toggle_wrap") => {
+                self.body_wrap = !self.body_wrap;
+                self.preview_hscroll = 0;
+                self.refresh_search_matches();
+                None
+            }
+            Some("back") => {
+                self.focus = Focus::List;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Highlight the next link in the body, detecting links on first use.
+    fn next_url(&mut self) {
+        if self.urls.is_empty() {
+            self.urls = self
+                .selected_email()
+                .map(|e| email::extract_urls(&e.body))
+                .unwrap_or_default();
+            self.url_index = 0;
+            if self.urls.is_empty() {
+                self.set_status("No links in this email".to_string());
+            }
+        } else {
+            self.url_index = (self.url_index + 1) % self.urls.len();
+        }
+    }
+
+    /// Typing filters the help overlay. `?S` / `?E` keep working while the
+    /// filter is empty (matching ignores case, so lowercase covers searches).
+    fn handle_help_key(&mut self, key: KeyEvent) -> Option<Message> {
+        if !self.help_query.is_empty() {
+            match key.code {
+                KeyCode::Esc => self.help_query.clear(),
+                KeyCode::Enter => self.show_help = false,
+                KeyCode::Backspace => {
+                    self.help_query.pop();
+                }
+                KeyCode::Char(c) => self.help_query.push(c),
+                _ => {}
+            }
+            return None;
+        }
+        match key.code {
+            KeyCode::Char('?') | KeyCode::Esc | KeyCode::Enter => {
+                self.show_help = false;
+            }
+            KeyCode::Char('S') => {
+                self.show_help = false;
+                self.open_stats();
+            }
+            KeyCode::Char('E') => {
+                self.show_help = false;
+                if self.parse_errors.is_empty() {
+                    self.set_status("All email files parsed fine".to_string());
+                } else {
+                    self.show_parse_errors = true;
+                    self.parse_errors_scroll = 0;
+                }
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.help_query.push(c);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent) -> Option<Message> {
+        match key.code {
             KeyCode::Enter => {
+                self.push_search_history();
                 self.focus = Focus::List;
             }
+            KeyCode::Up if !self.search_history.is_empty() => {
+                let cursor = match self.history_cursor {
+                    Some(i) => i.saturating_sub(1),
+                    None => self.search_history.len() - 1,
+                };
+                self.history_cursor = Some(cursor);
+                self.search_query = self.search_history[cursor].clone();
+                self.apply_search_filter();
+            }
+            KeyCode::Down if self.history_cursor.is_some() => {
+                let next = self.history_cursor.map(|i| i + 1);
+                match next.filter(|&i| i < self.search_history.len()) {
+                    Some(i) => {
+                        self.history_cursor = Some(i);
+                        self.search_query = self.search_history[i].clone();
+                    }
+                    None => {
+                        self.history_cursor = None;
+                        self.search_query.clear();
+                    }
+                }
+                self.apply_search_filter();
+            }
+            KeyCode::Esc if self.global_search_active => {
+                self.exit_global_search();
+            }
             KeyCode::Esc => {
                 self.search_query.clear();
                 self.search_includes_body = false;
+                self.search_fuzzy = false;
+                self.address_filter = None;
                 self.reload_from_cache();
                 self.focus = Focus::List;
             }
+            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.search_fuzzy = !self.search_fuzzy;
+                self.apply_search_filter();
+            }
+            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some(text) = self.clipboard_line() {
+                    self.history_cursor = None;
+                    self.search_query.push_str(&text);
+                    self.apply_search_filter();
+                }
+            }
             KeyCode::Char(c) => {
+                self.history_cursor = None;
                 self.search_query.push(c);
                 self.apply_search_filter();
             }
             KeyCode::Backspace => {
+                self.history_cursor = None;
                 self.search_query.pop();
                 self.apply_search_filter();
             }
@@ -665,74 +3113,882 @@ impl App {
         None
     }
 
+    /// First line of the clipboard text, for pasting into a prompt (`C-v`).
+    fn clipboard_line(&mut self) -> Option<String> {
+        match cli::paste_from_clipboard() {
+            Ok(text) => Some(text.lines().next().unwrap_or_default().trim().to_string()),
+            Err(e) => {
+                self.set_status(format!("Paste failed: {e}"));
+                None
+            }
+        }
+    }
+
+    /// Show statistics for the active mailbox (all of it, ignoring filters).
+    fn open_stats(&mut self) {
+        self.ensure_loaded(self.active_mailbox);
+        let entries = self.email_cache[self.active_mailbox.index()].as_deref().unwrap_or(&[]);
+        self.stats = Some(email::compute_stats(entries));
+        self.focus = Focus::Stats;
+    }
+
+    /// Rank the address book's senders by email count across all mailboxes.
+    fn open_contacts(&mut self) {
+        self.poll_address_book();
+        if self.address_book.is_empty() {
+            self.set_status("Address book is still loading".to_string());
+            return;
+        }
+        for mailbox in Mailbox::ALL {
+            self.ensure_loaded(mailbox);
+        }
+        let entries = self.email_cache.iter().flatten().flatten();
+        self.contact_stats = email::contact_stats(&self.address_book, entries);
+        self.contacts_index = 0;
+        self.focus = Focus::Contacts;
+    }
+
+    /// Keys in the contacts overlay: j/k move, Enter filters by the sender,
+    /// c copies the address.
+    fn handle_contacts_key(&mut self, key: KeyEvent) -> Option<Message> {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down
+                if self.contacts_index + 1 < self.contact_stats.len() =>
+            {
+                self.contacts_index += 1;
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.contacts_index = self.contacts_index.saturating_sub(1);
+            }
+            KeyCode::Enter => {
+                if let Some(contact) = self.contact_stats.get(self.contacts_index).cloned() {
+                    let name = if contact.name.is_empty() {
+                        contact.address.clone()
+                    } else {
+                        contact.name
+                    };
+                    self.apply_address_filter(AddressField::From, contact.address, name);
+                }
+            }
+            KeyCode::Char('c') => {
+                if let Some(contact) = self.contact_stats.get(self.contacts_index) {
+                    self.pending_action = Some(Action::CopyAddress(contact.address.clone()));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.focus = Focus::List,
+            _ => {}
+        }
+        None
+    }
+
+    /// Show the compose prompt for a new draft's recipient.
+    fn open_compose(&mut self) {
+        self.g_pending = false;
+        self.compose_to.clear();
+        self.compose_selected = None;
+        self.poll_address_book();
+        self.focus = Focus::Compose;
+    }
+
+    /// Point the watcher's files fallback at the current inbox folder and the
+    /// scheduler at the current drafts folder.
+    fn update_shared_dirs(&self) {
+        if let Ok(mut inbox) = self.watch_inbox.lock() {
+            *inbox = self.mailbox_dirs[Mailbox::Inbox.index()].clone();
+        }
+        if let Ok(mut drafts) = self.schedule_drafts.lock() {
+            *drafts = self.mailbox_dirs[Mailbox::Drafts.index()].clone();
+        }
+    }
+
+    /// Take the address book from the background scan once it is done.
+    fn poll_address_book(&mut self) {
+        if let Some(book) = self.address_book_rx.as_ref().and_then(|rx| rx.try_recv().ok()) {
+            self.address_book = book;
+            self.address_book_rx = None;
+        }
+    }
+
+    /// Up to 5 address book entries matching the recipient being typed (the
+    /// text after the last comma).
+    pub fn compose_suggestions(&self) -> Vec<&str> {
+        let partial = self.compose_to.rsplit(',').next().unwrap_or("").trim().to_lowercase();
+        let chosen: Vec<String> = email::split_address_list(&self.compose_to)
+            .iter()
+            .map(|entry| email::extract_email_address(entry).to_lowercase())
+            .collect();
+        self.address_book
+            .iter()
+            .filter(|entry| {
+                let entry = entry.to_lowercase();
+                entry.contains(&partial) && !chosen.contains(&email::extract_email_address(&entry))
+            })
+            .take(COMPOSE_SUGGESTIONS)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Replace the recipient being typed with `entry`.
+    fn complete_recipient(&mut self, entry: &str) {
+        let keep = self.compose_to.rfind(',').map_or(0, |i| i + 1);
+        self.compose_to.truncate(keep);
+        if keep > 0 {
+            self.compose_to.push(' ');
+        }
+        self.compose_to.push_str(entry);
+        self.compose_to.push_str(", ");
+        self.compose_selected = None;
+    }
+
+    fn handle_compose_key(&mut self, key: KeyEvent) -> Option<Message> {
+        self.poll_address_book();
+        let count = self.compose_suggestions().len();
+        match key.code {
+            KeyCode::Down if count > 0 => {
+                self.compose_selected = Some(self.compose_selected.map_or(0, |i| (i + 1) % count));
+            }
+            KeyCode::Up if count > 0 => {
+                self.compose_selected =
+                    Some(self.compose_selected.map_or(count - 1, |i| (i + count - 1) % count));
+            }
+            KeyCode::Tab if count > 0 => {
+                let i = self.compose_selected.unwrap_or(0);
+                let entry = self.compose_suggestions()[i].to_string();
+                self.complete_recipient(&entry);
+            }
+            KeyCode::Enter => {
+                if let Some(i) = self.compose_selected.filter(|&i| i < count) {
+                    let entry = self.compose_suggestions()[i].to_string();
+                    self.complete_recipient(&entry);
+                }
+                let to = email::split_address_list(&self.compose_to).join(", ");
+                self.pending_action = Some(Action::NewDraft(to));
+                self.focus = Focus::List;
+            }
+            KeyCode::Esc => self.focus = Focus::List,
+            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some(text) = self.clipboard_line() {
+                    self.compose_to.push_str(&text);
+                    self.compose_selected = None;
+                }
+            }
+            KeyCode::Char(c) => {
+                self.compose_to.push(c);
+                self.compose_selected = None;
+            }
+            KeyCode::Backspace => {
+                self.compose_to.pop();
+                self.compose_selected = None;
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_file_input_key(&mut self, key: KeyEvent) -> Option<Message> {
+        if key.code == KeyCode::Char('v') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            if let Some(text) = self.clipboard_line() {
+                self.attach_input.insert(&text);
+            }
+            return None;
+        }
+        match self.attach_input.handle_key(key) {
+            FileInputEvent::Editing => {}
+            FileInputEvent::NoMatch => {
+                self.set_status(format!("No files match {}", self.attach_input.input));
+            }
+            FileInputEvent::Submit(path) => {
+                self.pending_action = Some(Action::AttachFile(path));
+                self.focus = Focus::List;
+            }
+            FileInputEvent::Cancel => self.focus = Focus::List,
+        }
+        None
+    }
+
+    fn handle_snooze_key(&mut self, key: KeyEvent) -> Option<Message> {
+        match key.code {
+            KeyCode::Enter => match email::parse_snooze_time(&self.snooze_input) {
+                Some(until) if until > chrono::Local::now().naive_local() => {
+                    self.pending_action = Some(Action::Snooze(until));
+                    self.focus = Focus::List;
+                }
+                Some(_) => self.set_status("Snooze time is in the past".to_string()),
+                None => self.set_status(format!("Unrecognized time: {}", self.snooze_input)),
+            },
+            KeyCode::Esc => self.focus = Focus::List,
+            KeyCode::Char(c) => self.snooze_input.push(c),
+            KeyCode::Backspace => {
+                self.snooze_input.pop();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_date_filter_key(&mut self, key: KeyEvent) -> Option<Message> {
+        match key.code {
+            KeyCode::Enter if self.date_filter_input.trim().is_empty() => {
+                self.date_range = None;
+                self.apply_search_filter();
+                self.focus = Focus::List;
+            }
+            KeyCode::Enter => match email::parse_date_range(&self.date_filter_input) {
+                Some(range) => {
+                    self.date_range = Some(range);
+                    self.apply_search_filter();
+                    self.focus = Focus::List;
+                }
+                None => {
+                    self.set_status(format!("Unrecognized date range: {}", self.date_filter_input));
+                }
+            },
+            KeyCode::Esc => {
+                self.date_filter_input.clear();
+                self.date_range = None;
+                self.apply_search_filter();
+                self.focus = Focus::List;
+            }
+            KeyCode::Char(c) => self.date_filter_input.push(c),
+            KeyCode::Backspace => {
+                self.date_filter_input.pop();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn start_jump_search(&mut self) {
+        self.jump_query = Some(String::new());
+        self.jump_matches.clear();
+        self.focus = Focus::JumpSearch;
+    }
+
+    fn clear_jump_search(&mut self) {
+        self.jump_query = None;
+        self.jump_matches.clear();
+        self.jump_pos = 0;
+    }
+
+    /// Typing the `C-/` query: the cursor follows the first match at or below it.
+    fn handle_jump_key(&mut self, key: KeyEvent) -> Option<Message> {
+        let query = self.jump_query.get_or_insert_with(String::new);
+        match key.code {
+            KeyCode::Enter => {
+                self.focus = Focus::List;
+                if query.is_empty() {
+                    self.clear_jump_search();
+                } else if self.jump_matches.is_empty() {
+                    let message = format!("Pattern not found: {query}");
+                    self.set_status(message);
+                } else {
+                    let count = self.jump_matches.len();
+                    self.set_status(format!("{count} match(es) -- n/N to jump, Esc to clear"));
+                }
+                return None;
+            }
+            KeyCode::Esc => {
+                self.clear_jump_search();
+                self.focus = Focus::List;
+                return None;
+            }
+            KeyCode::Char(c) => query.push(c),
+            KeyCode::Backspace => {
+                query.pop();
+            }
+            _ => return None,
+        }
+        self.apply_jump_search();
+        if let Some(&row) = self.jump_matches.get(self.jump_pos) {
+            self.select_row(row);
+        }
+        None
+    }
+
+    /// Find the list rows whose metadata matches `jump_query`, starting
+    /// `jump_pos` at the first one at or below the cursor.
+    fn apply_jump_search(&mut self) {
+        let query = self.jump_query.as_deref().unwrap_or_default().to_lowercase();
+        self.jump_matches = if query.is_empty() {
+            Vec::new()
+        } else {
+            self.display_rows()
+                .into_iter()
+                .enumerate()
+                .filter(|&(_, (i, _))| {
+                    let email = &self.emails[i];
+                    email.subject.to_lowercase().contains(&query)
+                        || email.from.to_lowercase().contains(&query)
+                        || email.to.to_lowercase().contains(&query)
+                        || email.date_display.contains(&query)
+                })
+                .map(|(row, _)| row)
+                .collect()
+        };
+        self.jump_pos = self
+            .jump_matches
+            .iter()
+            .position(|&row| row >= self.list_index)
+            .unwrap_or(0);
+    }
+
+    /// `n` / `N` after a `C-/` search: move to the next or previous match, wrapping.
+    fn step_jump_match(&mut self, delta: isize) {
+        let on_match = self.jump_matches.get(self.jump_pos) == Some(&self.list_index);
+        if !on_match {
+            // The cursor or the list changed since the last jump
+            self.apply_jump_search();
+        }
+        let count = self.jump_matches.len();
+        if count == 0 {
+            let query = self.jump_query.as_deref().unwrap_or_default();
+            self.set_status(format!("Pattern not found: {query}"));
+            return;
+        }
+        let cursor = self.list_index;
+        self.jump_pos = if on_match {
+            (self.jump_pos as isize + delta).rem_euclid(count as isize) as usize
+        } else if delta > 0 {
+            self.jump_matches.iter().position(|&row| row > cursor).unwrap_or(0)
+        } else {
+            self.jump_matches.iter().rposition(|&row| row < cursor).unwrap_or(count - 1)
+        };
+        self.select_row(self.jump_matches[self.jump_pos]);
+        self.set_status(format!("Match {}/{count}", self.jump_pos + 1));
+    }
+
+    fn select_row(&mut self, row: usize) {
+        if self.list_index != row {
+            self.list_index = row;
+            self.reset_preview();
+        }
+    }
+
+    /// Remember the confirmed search query, skipping a repeat of the newest entry.
+    fn push_search_history(&mut self) {
+        self.history_cursor = None;
+        let query = self.search_query.trim();
+        if query.is_empty() || self.search_history.back().is_some_and(|last| last == query) {
+            return;
+        }
+        self.search_history.push_back(query.to_string());
+        while self.search_history.len() > SEARCH_HISTORY_LIMIT {
+            self.search_history.pop_front();
+        }
+    }
+
+    /// Write the search history to disk (one query per line).
+    pub fn save_search_history(&self) -> Result<()> {
+        let Some(path) = search_history_path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let mut content = String::new();
+        for query in &self.search_history {
+            content.push_str(query);
+            content.push('\n');
+        }
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
     /// Re-filter emails from cache based on the current search query.
     fn apply_search_filter(&mut self) {
+        self.filter_emails();
+        self.list_index = 0;
+        self.reset_preview();
+    }
+
+    /// Rebuild `emails` from the cache with the search query and list filters applied.
+    fn filter_emails(&mut self) {
+        if self.global_search_active {
+            self.filter_global();
+            return;
+        }
         let idx = self.active_mailbox.index();
-        let all_emails = self.email_cache[idx].as_ref().cloned().unwrap_or_default();
+        let mut all_emails = self.email_cache[idx].as_ref().cloned().unwrap_or_default();
+        if self.scheduled_only {
+            all_emails.retain(|e| e.send_at.is_some());
+        }
+        if self.starred_only {
+            all_emails.retain(|e| e.starred);
+        }
+        match self.status_filter.as_deref() {
+            // `fu` matches every unread-like status, as the sidebar badge counts them
+            Some("unread") => all_emails.retain(|e| email::is_unread_status(&e.status)),
+            Some(status) => all_emails.retain(|e| e.status.eq_ignore_ascii_case(status)),
+            None => {}
+        }
+        if self.high_priority_only {
+            all_emails.retain(|e| e.priority == Priority::High);
+        }
+        all_emails.retain(|e| self.in_date_range(e));
 
         if self.search_query.is_empty() {
             self.emails = all_emails;
         } else {
             let query = self.search_query.to_lowercase();
-            let mailbox = self.active_mailbox;
-            let includes_body = self.search_includes_body;
             self.emails = all_emails
                 .into_iter()
-                .filter(|e| {
-                    e.subject.to_lowercase().contains(&query)
-                        || e.display_contact(mailbox).to_lowercase().contains(&query)
-                        || e.date_display.to_lowercase().contains(&query)
-                        || e.from.to_lowercase().contains(&query)
-                        || e.to.to_lowercase().contains(&query)
-                        || (includes_body && e.body.to_lowercase().contains(&query))
-                })
+                .filter(|e| self.search_matches(e, self.active_mailbox, &query))
                 .collect();
         }
+        let SortState { column, asc } = self.sort();
+        email::sort_emails(&mut self.emails, column, asc);
+        if self.fuzzy_ranking() {
+            let query = self.search_query.to_lowercase();
+            self.emails.sort_by_cached_key(|e| Reverse(fuzzy_email_score(e, &query)));
+        }
+        self.duplicate_paths.clear();
+        if self.duplicates_only {
+            self.keep_duplicate_groups();
+        }
+        self.rebuild_threads();
+    }
 
-        self.list_index = 0;
-        self.headers_scroll = 0;
-        self.preview_scroll = 0;
+    /// Reduce `emails` to its duplicate groups (newest copy first in each) and
+    /// record the older copies in `duplicate_paths`.
+    fn keep_duplicate_groups(&mut self) {
+        let groups = email::find_duplicates(&self.emails);
+        let mut emails = Vec::new();
+        for group in groups {
+            for (pos, i) in group.into_iter().enumerate() {
+                if pos > 0 {
+                    self.duplicate_paths.insert(self.emails[i].path.clone());
+                }
+                emails.push(self.emails[i].clone());
+            }
+        }
+        self.emails = emails;
     }
 
-    /// Reload emails from cache without invalidating (restores full unfiltered list).
-    fn reload_from_cache(&mut self) {
-        let idx = self.active_mailbox.index();
-        if let Some(cached) = &self.email_cache[idx] {
-            self.emails = cached.clone();
+    /// Whether `email` matches the lowercased search `query`.
+    fn search_matches(&self, email: &EmailEntry, mailbox: Mailbox, query: &str) -> bool {
+        match self.address_filter {
+            Some((AddressField::From, _)) => return email.from_addr.to_lowercase().contains(query),
+            Some((AddressField::To, _)) => return email.to_addr.to_lowercase().contains(query),
+            None => {}
+        }
+        if self.search_fuzzy {
+            return fuzzy_email_score(email, query).is_some();
+        }
+        email.subject.to_lowercase().contains(query)
+            || email.display_contact(mailbox).to_lowercase().contains(query)
+            || email.date_display.to_lowercase().contains(query)
+            || email.from.to_lowercase().contains(query)
+            || email.to.to_lowercase().contains(query)
+            || (self.search_includes_body && email.body.to_lowercase().contains(query))
+    }
+
+    /// Whether results are ordered by fuzzy match score instead of the sort column.
+    fn fuzzy_ranking(&self) -> bool {
+        self.search_fuzzy && self.address_filter.is_none() && !self.search_query.is_empty()
+    }
+
+    /// Char positions of the fuzzy query within an email's subject and listed
+    /// contact, or `None` when the list is not fuzzy-ranked.
+    pub fn fuzzy_matches(
+        &self,
+        email: &EmailEntry,
+        mailbox: Mailbox,
+    ) -> Option<(Vec<usize>, Vec<usize>)> {
+        if !self.fuzzy_ranking() {
+            return None;
+        }
+        let haystack = format!("{} {} {}", email.subject, email.from, email.to);
+        let positions = email::fuzzy_positions(&haystack, &self.search_query)?;
+        let subject_len = email.subject.chars().count();
+        let from_start = subject_len + 1;
+        let to_start = from_start + email.from.chars().count() + 1;
+        let contact_start = match mailbox {
+            Mailbox::Drafts | Mailbox::Sent => to_start,
+            _ => from_start,
+        };
+        let contact_len = email.display_contact(mailbox).chars().count();
+        let subject = positions.iter().copied().filter(|&p| p < subject_len).collect();
+        let contact = positions
+            .iter()
+            .filter(|&&p| p >= contact_start && p < contact_start + contact_len)
+            .map(|&p| p - contact_start)
+            .collect();
+        Some((subject, contact))
+    }
+
+    /// Whether `email` falls inside the active date filter (always true without one).
+    fn in_date_range(&self, email: &EmailEntry) -> bool {
+        match self.date_range {
+            Some((from, to)) => email.date().is_some_and(|d| from <= d && d <= to),
+            None => true,
+        }
+    }
+
+    /// Apply the search query across every mailbox, in the active mailbox's sort order.
+    fn filter_global(&mut self) {
+        for mailbox in Mailbox::ALL {
+            self.ensure_loaded(mailbox);
+        }
+        let query = self.search_query.to_lowercase();
+        let mut results: Vec<(Mailbox, EmailEntry)> = Vec::new();
+        for mailbox in Mailbox::ALL {
+            for e in self.email_cache[mailbox.index()].iter().flatten() {
+                if self.in_date_range(e)
+                    && (query.is_empty() || self.search_matches(e, mailbox, &query))
+                {
+                    results.push((mailbox, e.clone()));
+                }
+            }
+        }
+        let SortState { column, asc } = self.sort();
+        results.sort_by(|a, b| email::compare_emails(&a.1, &b.1, column, asc));
+        if self.fuzzy_ranking() {
+            results.sort_by_cached_key(|(_, e)| Reverse(fuzzy_email_score(e, &query)));
+        }
+        self.emails = results.iter().map(|(_, e)| e.clone()).collect();
+        self.global_results = results;
+        self.rebuild_threads();
+    }
+
+    /// Enter global search (`g/`, or `g\` to include bodies).
+    fn start_global_search(&mut self, include_body: bool) {
+        self.g_pending = false;
+        self.global_search_active = true;
+        self.scheduled_only = false;
+        self.search_query.clear();
+        self.search_includes_body = include_body;
+        self.address_filter = None;
+        self.focus = Focus::Search;
+        self.apply_search_filter();
+    }
+
+    /// Leave global search and go back to the active mailbox.
+    fn exit_global_search(&mut self) {
+        self.global_search_active = false;
+        self.global_results.clear();
+        self.search_query.clear();
+        self.search_includes_body = false;
+        self.search_fuzzy = false;
+        self.address_filter = None;
+        self.reload_from_cache();
+        self.focus = Focus::List;
+    }
+
+    /// Switch to the mailbox of the selected global result and select it there.
+    fn open_global_result(&mut self) {
+        let Some(idx) = self.email_index_at(self.list_index) else {
+            return;
+        };
+        let Some((mailbox, email)) = self.global_results.get(idx).cloned() else {
+            return;
+        };
+        self.global_search_active = false;
+        self.global_results.clear();
+        self.search_query.clear();
+        self.search_includes_body = false;
+        self.search_fuzzy = false;
+        self.address_filter = None;
+        self.sidebar_index = mailbox.index();
+        self.switch_mailbox(mailbox);
+        self.select_path(&email.path);
+        self.focus = Focus::List;
+    }
+
+    /// Move the selection to the row showing the email at `path`, if listed.
+    pub fn select_path(&mut self, path: &Path) {
+        let rows = self.display_rows();
+        if let Some(row) = rows.iter().position(|&(i, _)| self.emails[i].path == path) {
+            self.list_index = row;
         }
+        self.reset_preview();
+    }
+
+    /// Mailbox an email in the list came from (differs from `active_mailbox`
+    /// only during global search).
+    pub fn origin_mailbox(&self, email_idx: usize) -> Mailbox {
+        if self.global_search_active {
+            if let Some((mailbox, _)) = self.global_results.get(email_idx) {
+                return *mailbox;
+            }
+        }
+        self.active_mailbox
+    }
+
+    /// `f` was pressed: wait for a status filter letter, or `f` again to fetch.
+    fn start_filter_prefix(&mut self) {
+        self.filter_pending = true;
+        self.set_status(
+            "f: a approved, d draft, s sent, u unread, p high priority, c clear, f fetch"
+                .to_string(),
+        );
+    }
+
+    /// Second key after `f`.
+    fn handle_filter_key(&mut self, key: KeyEvent) -> Option<Message> {
+        self.filter_pending = false;
+        self.status_message = None;
+        if self.keymap.matches("fetch", &key) {
+            self.pending_action = Some(Action::Fetch);
+            return None;
+        }
+        if key.code == KeyCode::Char('p') {
+            self.toggle_high_priority_only();
+            return None;
+        }
+        let status = match key.code {
+            KeyCode::Char('a') => Some("approved"),
+            KeyCode::Char('d') => Some("draft"),
+            KeyCode::Char('s') => Some("sent"),
+            KeyCode::Char('u') => Some("unread"),
+            KeyCode::Char('c') => None,
+            _ => return None,
+        };
+        self.status_filter = status.map(str::to_string);
+        if status.is_none() {
+            // `fc` clears the priority filter too
+            self.high_priority_only = false;
+        }
+        self.apply_search_filter();
+        if let Some(status) = status {
+            let count = self.emails.len();
+            self.set_status(format!("{count} {status} email(s)"));
+        }
+        None
+    }
+
+    /// Mark every unread email in `mailbox` read, ignoring the list filters.
+    fn mark_all_read(&mut self, mailbox: Mailbox) {
+        self.ensure_loaded(mailbox);
+        let paths: Vec<PathBuf> = self.email_cache[mailbox.index()]
+            .iter()
+            .flatten()
+            .filter(|e| email::is_unread_status(&e.status))
+            .map(|e| e.path.clone())
+            .collect();
+        if paths.is_empty() {
+            let label = self.mailbox_label(mailbox);
+            self.set_status(format!("No unread emails in {label}"));
+        } else {
+            self.pending_action = Some(Action::MarkAllRead(paths));
+        }
+    }
+
+    /// Toggle showing only high-priority emails.
+    fn toggle_high_priority_only(&mut self) {
+        self.high_priority_only = !self.high_priority_only;
+        self.apply_search_filter();
+        if self.high_priority_only {
+            let count = self.emails.len();
+            self.set_status(format!("{count} high-priority email(s)"));
+        }
+    }
+
+    /// Toggle showing only starred emails.
+    fn toggle_starred_only(&mut self) {
+        self.starred_only = !self.starred_only;
+        self.apply_search_filter();
+        if self.starred_only {
+            let count = self.emails.len();
+            self.set_status(format!("{count} starred email(s)"));
+        }
+    }
+
+    /// Toggle showing only duplicate groups. Duplicates are found on demand,
+    /// not on every reload.
+    fn toggle_duplicates_only(&mut self) {
+        if !self.duplicates_only && email::find_duplicates(&self.emails).is_empty() {
+            self.set_status("No duplicates found".to_string());
+            return;
+        }
+        self.duplicates_only = !self.duplicates_only;
+        self.apply_search_filter();
+        if self.duplicates_only {
+            let count = self.duplicate_paths.len();
+            self.set_status(format!("{count} duplicate(s) -- D removes one"));
+        }
+    }
+
+    /// Toggle showing only drafts with a `send_at` schedule.
+    fn toggle_scheduled_only(&mut self) {
+        self.scheduled_only = !self.scheduled_only;
+        self.apply_search_filter();
+        if self.scheduled_only {
+            let count = self.emails.len();
+            self.set_status(format!("{count} scheduled draft(s)"));
+        }
+    }
+
+    /// Reload emails from cache without invalidating (drops the search,
+    /// scheduled, starred and status filters, keeps the date range).
+    fn reload_from_cache(&mut self) {
+        self.scheduled_only = false;
+        self.starred_only = false;
+        self.duplicates_only = false;
+        self.status_filter = None;
+        self.high_priority_only = false;
+        self.filter_emails();
         self.list_index = 0;
-        self.headers_scroll = 0;
-        self.preview_scroll = 0;
+        self.reset_preview();
+    }
+}
+
+/// Fuzzy score of `query` against an email's subject, sender and recipients.
+fn fuzzy_email_score(email: &EmailEntry, query: &str) -> Option<i64> {
+    email::fuzzy_score(&format!("{} {} {}", email.subject, email.from, email.to), query)
+}
+
+/// Location of the persisted search history (`~/.local/share/beautifulmail/`).
+fn search_history_path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("beautifulmail").join("search_history"))
+}
+
+/// Read the saved search history; a missing or unreadable file yields none.
+fn load_search_history() -> VecDeque<String> {
+    let content = search_history_path()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .unwrap_or_default();
+    let mut history: VecDeque<String> = content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(str::to_string)
+        .collect();
+    while history.len() > SEARCH_HISTORY_LIMIT {
+        history.pop_front();
     }
+    history
+}
+
+/// Build the address book on a background thread so startup is not blocked.
+fn spawn_address_scan(dirs: &[Option<PathBuf>; 6]) -> mpsc::Receiver<Vec<String>> {
+    let dirs: Vec<PathBuf> = dirs.iter().flatten().cloned().collect();
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(email::collect_addresses(&dirs));
+    });
+    rx
 }
 
-/// Load .env and resolve mailbox directory paths.
-fn resolve_mailbox_dirs() -> [Option<PathBuf>; 4] {
-    // Load .env from the email notes directory and standard locations
-    dotenvy::dotenv().ok();
+/// Add `warning` to the startup warnings, after any earlier ones.
+fn append_warning(warnings: &mut Option<String>, warning: String) {
+    *warnings = Some(match warnings.take() {
+        Some(earlier) => format!("{earlier}; {warning}"),
+        None => warning,
+    });
+}
+
+/// Load .env and resolve mailbox directory paths. A mailbox without its env
+/// var falls back to `$XDG_DATA_HOME/beautifulmail/<name>` when that exists.
+/// With `reload`, values from .env replace variables set by an earlier load.
+/// An `account` uses its own directories instead, leaving the ones it does not
+/// configure unset; `email` subprocesses get them through `cli::email_command`.
+fn resolve_mailbox_dirs(reload: bool, account: Option<&AccountConfig>) -> [Option<PathBuf>; 6] {
+    // Load .env from the working directory, then the config directory
+    let env_file = Config::path().and_then(|p| Some(p.parent()?.join(".env")));
+    if reload {
+        dotenvy::dotenv_override().ok();
+        if let Some(env_file) = env_file {
+            dotenvy::from_path_override(env_file).ok();
+        }
+    } else {
+        dotenvy::dotenv().ok();
+        if let Some(env_file) = env_file {
+            dotenvy::from_path(env_file).ok();
+        }
+    }
 
-    // Also try loading from the email project directory
-    let email_project = PathBuf::from("/Users/sylvainhellin/code/personal/email");
-    if email_project.join(".env").exists() {
-        dotenvy::from_path(email_project.join(".env")).ok();
+    let expand = |s: &str| {
+        let s = s.trim_matches('"').trim_matches('\'');
+        PathBuf::from(shellexpand::tilde(s).into_owned())
+    };
+    if let Some(account) = account {
+        return account.dirs().map(|dir| dir.map(expand));
     }
 
-    let env_keys = ["INBOX_DIR", "DRAFTS_DIR", "SENT_DIR", "ARCHIVE_DIR"];
-    let mut dirs: [Option<PathBuf>; 4] = [None, None, None, None];
+    let dir_names = ["inbox", "drafts", "sent", "archive", "spam", "trash"];
+    let data_home = data_home().map(|d| d.join("beautifulmail"));
+    let mut dirs: [Option<PathBuf>; 6] = [None, None, None, None, None, None];
 
-    for (i, key) in env_keys.iter().enumerate() {
-        dirs[i] = std::env::var(key).ok().map(|s| {
-            let s = s.trim_matches('"').trim_matches('\'');
-            PathBuf::from(shellexpand::tilde(s).into_owned())
+    for (i, key) in cli::DIR_ENV_KEYS.iter().enumerate() {
+        dirs[i] = std::env::var(key).ok().map(|s| expand(&s)).or_else(|| {
+            let fallback = data_home.as_ref()?.join(dir_names[i]);
+            fallback.is_dir().then_some(fallback)
         });
     }
 
     dirs
 }
 
+/// `$XDG_DATA_HOME`, or the platform data dir (`~/.local/share` on Linux).
+fn data_home() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(dirs::data_dir)
+}
+
+/// Count (total, unread) emails per mailbox on a background thread, since it
+/// reads every file or runs `email list` for each mailbox.
+fn spawn_count_scan(
+    source: EmailSource,
+    dirs: &[Option<PathBuf>; 6],
+) -> mpsc::Receiver<[(usize, usize); 6]> {
+    let dirs = dirs.clone();
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let counts = match source {
+            EmailSource::Filesystem => {
+                let (totals, unread) = (count_emails(&dirs), count_unread(&dirs));
+                std::array::from_fn(|i| (totals[i], unread[i]))
+            }
+            EmailSource::CliJson => Mailbox::ALL.map(|mailbox| {
+                email::load_emails_from_cli(&dirs, mailbox.name())
+                    .map_or((0, 0), |emails| (emails.len(), count_unread_entries(&emails)))
+            }),
+        };
+        let _ = tx.send(counts);
+    });
+    rx
+}
+
+fn count_unread_entries(emails: &[EmailEntry]) -> usize {
+    emails.iter().filter(|e| email::is_unread_status(&e.status)).count()
+}
+
+/// Directory `mailbox` is loaded from. With `email list --json` a mailbox
+/// needs no directory; its name stands in so load errors are still grouped.
+fn source_dir(
+    source: EmailSource,
+    dirs: &[Option<PathBuf>; 6],
+    mailbox: Mailbox,
+) -> Option<PathBuf> {
+    match (&dirs[mailbox.index()], source) {
+        (Some(dir), _) => Some(dir.clone()),
+        (None, EmailSource::CliJson) => Some(PathBuf::from(mailbox.name())),
+        (None, EmailSource::Filesystem) => None,
+    }
+}
+
+/// Count the emails in each mailbox directory whose `status` is unread-like.
+fn count_unread(dirs: &[Option<PathBuf>; 6]) -> [usize; 6] {
+    let mut counts = [0usize; 6];
+    for (i, dir) in dirs.iter().enumerate() {
+        let Some(Ok(entries)) = dir.as_ref().map(std::fs::read_dir) else {
+            continue;
+        };
+        counts[i] = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+            .filter(|p| email::read_status(p).is_some_and(|s| email::is_unread_status(&s)))
+            .count();
+    }
+    counts
+}
+
 /// Count .md files in each mailbox directory.
-fn count_emails(dirs: &[Option<PathBuf>; 4]) -> [usize; 4] {
-    let mut counts = [0usize; 4];
+fn count_emails(dirs: &[Option<PathBuf>; 6]) -> [usize; 6] {
+    let mut counts = [0usize; 6];
     for (i, dir) in dirs.iter().enumerate() {
         if let Some(path) = dir {
             if path.is_dir() {
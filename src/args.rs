@@ -0,0 +1,137 @@
+use std::path::PathBuf;
+use std::process::exit;
+
+/// Command-line arguments, parsed before the terminal is set up.
+#[derive(Debug, Default)]
+pub struct Cli {
+    /// `mailto:` URL to compose right away (e.g. as the system mailto handler).
+    pub mailto: Option<String>,
+    /// Config file to read instead of `~/.config/beautifulmail/config.toml`.
+    pub config: Option<PathBuf>,
+}
+
+const SHELLS: [&str; 4] = ["bash", "zsh", "fish", "powershell"];
+
+const USAGE: &str = "Usage: beautifulmail [OPTIONS] [MAILTO]";
+
+const HELP: &str = "\
+A terminal mail client for Markdown mailboxes
+
+Usage: beautifulmail [OPTIONS] [MAILTO]
+
+Arguments:
+  [MAILTO]  mailto: URL to start composing a draft to
+
+Options:
+      --config <PATH>        Config file to use instead of the default
+      --completions <SHELL>  Print a completion script [bash, zsh, fish, powershell]
+  -h, --help                 Print help
+  -V, --version              Print version
+";
+
+const BASH_COMPLETION: &str = r#"_beautifulmail() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+    case "$prev" in
+        --completions)
+            COMPREPLY=($(compgen -W "bash zsh fish powershell" -- "$cur"))
+            return ;;
+        --config)
+            COMPREPLY=($(compgen -f -- "$cur"))
+            return ;;
+    esac
+    COMPREPLY=($(compgen -W "--completions --config --help --version" -- "$cur"))
+}
+complete -F _beautifulmail beautifulmail
+"#;
+
+const ZSH_COMPLETION: &str = r#"#compdef beautifulmail
+
+_arguments \
+    '--completions[Print a completion script]:shell:(bash zsh fish powershell)' \
+    '--config[Config file to use instead of the default]:path:_files' \
+    '(- *)'{-h,--help}'[Print help]' \
+    '(- *)'{-V,--version}'[Print version]' \
+    '::mailto URL:'
+"#;
+
+const FISH_COMPLETION: &str = r#"complete -c beautifulmail -l completions -x \
+    -a "bash zsh fish powershell" -d "Print a completion script"
+complete -c beautifulmail -l config -r -F -d "Config file to use instead of the default"
+complete -c beautifulmail -s h -l help -d "Print help"
+complete -c beautifulmail -s V -l version -d "Print version"
+"#;
+
+const POWERSHELL_COMPLETION: &str =
+    r#"Register-ArgumentCompleter -Native -CommandName beautifulmail -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+    $prev = $commandAst.CommandElements |
+        Where-Object { $_.Extent.EndOffset -lt $cursorPosition } |
+        Select-Object -Last 1
+    $candidates = switch ("$prev") {
+        '--completions' { 'bash', 'zsh', 'fish', 'powershell' }
+        '--config' { @() }
+        default { '--completions', '--config', '--help', '--version' }
+    }
+    $candidates | Where-Object { $_ -like "$wordToComplete*" } | ForEach-Object {
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }
+}
+"#;
+
+impl Cli {
+    /// Parse the process arguments. `--help`, `--version` and `--completions`
+    /// print to stdout and exit; bad arguments exit with status 2.
+    pub fn parse() -> Self {
+        let mut cli = Cli::default();
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || inline.clone().or_else(|| args.next()).unwrap_or_else(|| {
+                fail(&format!("a value is required for '{flag}' but none was supplied"))
+            });
+            match flag {
+                "-h" | "--help" => {
+                    print!("{HELP}");
+                    exit(0);
+                }
+                "-V" | "--version" => {
+                    println!("beautifulmail {}", env!("CARGO_PKG_VERSION"));
+                    exit(0);
+                }
+                "--completions" => {
+                    let shell = value();
+                    print!("{}", completion_script(&shell).unwrap_or_else(|| {
+                        fail(&format!(
+                            "invalid value '{shell}' for '--completions' [possible values: {}]",
+                            SHELLS.join(", ")
+                        ))
+                    }));
+                    exit(0);
+                }
+                "--config" => cli.config = Some(PathBuf::from(value())),
+                _ if arg.starts_with("mailto:") && cli.mailto.is_none() => cli.mailto = Some(arg),
+                _ => fail(&format!("unexpected argument '{arg}' found")),
+            }
+        }
+        cli
+    }
+}
+
+/// The completion script for `shell`, if it is one of `SHELLS`.
+fn completion_script(shell: &str) -> Option<&'static str> {
+    match shell {
+        "bash" => Some(BASH_COMPLETION),
+        "zsh" => Some(ZSH_COMPLETION),
+        "fish" => Some(FISH_COMPLETION),
+        "powershell" => Some(POWERSHELL_COMPLETION),
+        _ => None,
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {message}\n\n{USAGE}\n\nFor more information, try '--help'.");
+    exit(2);
+}
@@ -1,18 +1,102 @@
+use std::ffi::OsStr;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+
+use crate::app::{UndoEntry, UndoKind};
+use crate::email;
+use crate::template::{self, TemplateVars};
 
 /// Return the user's preferred editor (from $EDITOR, fallback to hx).
 pub fn editor() -> String {
     std::env::var("EDITOR").unwrap_or_else(|_| "hx".to_string())
 }
 
-/// Open a file in $EDITOR (interactive -- requires TUI suspended).
-pub fn edit_file(path: &Path) -> Result<()> {
+/// An editor returning sooner than this most likely handed the file to a GUI
+/// window instead of editing it.
+const QUICK_EXIT: Duration = Duration::from_secs(2);
+/// How often, and for how long at most, to check for a save after a handoff.
+const SAVE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const SAVE_WAIT_LIMIT: Duration = Duration::from_secs(5 * 60);
+
+/// Open a file in $EDITOR (interactive -- requires TUI suspended). GUI
+/// editors (`code`, `subl`) return at once; see `wait_for_save`.
+pub fn edit_file_interactive(path: &Path) -> Result<()> {
+    edit_interactive(path, None)
+}
+
+/// Open a file in $EDITOR at `line` (1-based). Editors without a known
+/// line syntax just open the file.
+pub fn edit_file_at_line_interactive(path: &Path, line: u16) -> Result<()> {
+    edit_interactive(path, Some(line))
+}
+
+fn edit_interactive(path: &Path, line: Option<u16>) -> Result<()> {
+    let before = modified_time(path);
+    let started = Instant::now();
+    run_editor(path, line)?;
+    // A terminal editor blocks until closed, so only a quick return with the
+    // file untouched suggests the editing happens elsewhere
+    if started.elapsed() < QUICK_EXIT && modified_time(path) == before {
+        wait_for_save(path, before)?;
+    }
+    Ok(())
+}
+
+/// The editor returned at once without touching the file: offer to wait until
+/// it is saved from the editor window, polling its mtime for up to five minutes.
+fn wait_for_save(path: &Path, before: Option<SystemTime>) -> Result<()> {
+    print!("Editor exited immediately. Wait for manual save? [y/n] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    if !answer.trim().eq_ignore_ascii_case("y") {
+        return Ok(());
+    }
+    println!("Waiting for {} to be saved...", path.display());
+    let deadline = Instant::now() + SAVE_WAIT_LIMIT;
+    while Instant::now() < deadline {
+        std::thread::sleep(SAVE_POLL_INTERVAL);
+        if modified_time(path) != before {
+            return Ok(());
+        }
+    }
+    anyhow::bail!("No save within {} minutes", SAVE_WAIT_LIMIT.as_secs() / 60)
+}
+
+/// Modification time of `path`, if the filesystem reports one.
+pub fn modified_time(path: &Path) -> Option<SystemTime> {
+    path.metadata().and_then(|m| m.modified()).ok()
+}
+
+/// Launch $EDITOR, which may carry arguments (`vim -u ~/.vimrc`).
+fn run_editor(path: &Path, line: Option<u16>) -> Result<()> {
     let editor = editor();
-    let status = Command::new(&editor)
-        .arg(path)
+    let parts = split_shell_words(&editor);
+    let Some((program, args)) = parts.split_first() else {
+        anyhow::bail!("EDITOR is empty");
+    };
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    let name = Path::new(program).file_name().and_then(OsStr::to_str).unwrap_or("");
+    match (line, name) {
+        (Some(line), "hx" | "helix") => {
+            let mut arg = path.as_os_str().to_os_string();
+            arg.push(format!(":{line}"));
+            cmd.arg(arg);
+        }
+        (Some(line), "vi" | "vim" | "nvim" | "nano" | "emacs" | "kak") => {
+            cmd.arg(format!("+{line}")).arg(path);
+        }
+        _ => {
+            cmd.arg(path);
+        }
+    }
+    let status = cmd
         .status()
         .with_context(|| format!("Failed to launch editor: {}", editor))?;
     if !status.success() {
@@ -21,32 +105,180 @@ pub fn edit_file(path: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Run `email reply [--all] <file>` non-interactively, returning the draft path.
-pub fn reply(path: &Path, reply_all: bool) -> Result<PathBuf> {
+/// Split a command line into words like a POSIX shell: whitespace separates,
+/// single quotes are literal, double quotes and backslashes escape. A leading
+/// `~` in unquoted words is expanded.
+fn split_shell_words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quoted = false;
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' => {
+                in_word = true;
+                quoted = true;
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                in_word = true;
+                quoted = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        _ => word.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                word.extend(chars.next());
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(expand_word(std::mem::take(&mut word), quoted));
+                }
+                in_word = false;
+                quoted = false;
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(expand_word(word, quoted));
+    }
+    words
+}
+
+fn expand_word(word: String, quoted: bool) -> String {
+    if quoted {
+        word
+    } else {
+        shellexpand::tilde(&word).into_owned()
+    }
+}
+
+/// Variables naming each mailbox directory, in `Mailbox::ALL` order. The
+/// `email` tool reads the same ones.
+pub const DIR_ENV_KEYS: [&str; 6] =
+    ["INBOX_DIR", "DRAFTS_DIR", "SENT_DIR", "ARCHIVE_DIR", "SPAM_DIR", "TRASH_DIR"];
+
+/// An `email` command pointed at `dirs`. A mailbox without a directory has its
+/// variable unset for the child only, so the process environment is untouched.
+pub fn email_command(dirs: &[Option<PathBuf>; 6]) -> Command {
     let mut cmd = Command::new("email");
+    for (key, dir) in DIR_ENV_KEYS.iter().zip(dirs) {
+        match dir {
+            Some(dir) => cmd.env(key, dir),
+            None => cmd.env_remove(key),
+        };
+    }
+    cmd
+}
+
+/// Run `email reply [--all] [--reply-to <addr>] <file>` non-interactively,
+/// returning the draft path. When `email` does not know `--reply-to`, the
+/// address is noted in the draft instead so the recipient can be fixed by hand.
+pub fn reply(
+    dirs: &[Option<PathBuf>; 6],
+    path: &Path,
+    reply_all: bool,
+    reply_to: Option<&str>,
+) -> Result<PathBuf> {
+    let mut output = run_reply(dirs, path, reply_all, reply_to)?;
+    let unsupported = !output.status.success()
+        && String::from_utf8_lossy(&output.stderr).contains("--reply-to");
+    if unsupported {
+        output = run_reply(dirs, path, reply_all, None)?;
+    }
+    if !output.status.success() {
+        let err = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        anyhow::bail!("email reply failed: {}", err);
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for line in stdout.lines() {
+        if let Some(path_str) = line.strip_prefix("✓ Reply draft created: ") {
+            let draft = PathBuf::from(path_str.trim());
+            if let Some(reply_to) = reply_to.filter(|_| unsupported) {
+                note_reply_to(&draft, reply_to)?;
+            }
+            return Ok(draft);
+        }
+    }
+    anyhow::bail!("Could not parse draft path from email reply output")
+}
+
+fn run_reply(
+    dirs: &[Option<PathBuf>; 6],
+    path: &Path,
+    reply_all: bool,
+    reply_to: Option<&str>,
+) -> Result<Output> {
+    let mut cmd = email_command(dirs);
     cmd.arg("reply");
     if reply_all {
         cmd.arg("--all");
     }
+    if let Some(reply_to) = reply_to {
+        cmd.arg("--reply-to").arg(reply_to);
+    }
     cmd.arg(path);
     cmd.env("NO_COLOR", "1");
-    let output = cmd.output().context("Failed to run email reply")?;
+    cmd.output().context("Failed to run email reply")
+}
+
+/// Rewrite the attribution line of a reply draft. Returns whether one was found.
+pub fn set_attribution(draft: &Path, attribution: &str, pattern: Option<&str>) -> Result<bool> {
+    let content = std::fs::read_to_string(draft)
+        .with_context(|| format!("Failed to read {}", draft.display()))?;
+    let Some(updated) = email::replace_attribution(&content, attribution, pattern) else {
+        return Ok(false);
+    };
+    std::fs::write(draft, updated).with_context(|| format!("Failed to write {}", draft.display()))?;
+    Ok(true)
+}
+
+/// Add a YAML comment with the sender's Reply-To address at the top of the
+/// draft's frontmatter.
+fn note_reply_to(draft: &Path, reply_to: &str) -> Result<()> {
+    let content = std::fs::read_to_string(draft)
+        .with_context(|| format!("Failed to read {}", draft.display()))?;
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return Ok(());
+    };
+    let noted = format!("---\n# Sender set Reply-To: {reply_to} -- adjust `to` if needed\n{rest}");
+    std::fs::write(draft, noted).with_context(|| format!("Failed to write {}", draft.display()))
+}
+
+/// Run `email forward <file>` non-interactively, returning the draft path.
+pub fn forward(dirs: &[Option<PathBuf>; 6], path: &Path) -> Result<PathBuf> {
+    let output = email_command(dirs)
+        .arg("forward")
+        .arg(path)
+        .env("NO_COLOR", "1")
+        .output()
+        .context("Failed to run email forward")?;
     if !output.status.success() {
         let err = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        anyhow::bail!("email reply failed: {}", err);
+        anyhow::bail!("email forward failed: {}", err);
     }
     let stdout = String::from_utf8_lossy(&output.stdout);
     for line in stdout.lines() {
-        if let Some(path_str) = line.strip_prefix("✓ Reply draft created: ") {
+        if let Some(path_str) = line.strip_prefix("✓ Forward draft created: ") {
             return Ok(PathBuf::from(path_str.trim()));
         }
     }
-    anyhow::bail!("Could not parse draft path from email reply output")
+    anyhow::bail!("Could not parse draft path from email forward output")
 }
 
 /// Run `email mark-approved <file>` (silent).
-pub fn approve(path: &Path) -> Result<String> {
-    let output = Command::new("email")
+pub fn approve(dirs: &[Option<PathBuf>; 6], path: &Path) -> Result<String> {
+    let output = email_command(dirs)
         .arg("mark-approved")
         .arg(path)
         .output()
@@ -59,9 +291,15 @@ pub fn approve(path: &Path) -> Result<String> {
     Ok(msg)
 }
 
-/// Run `email send <file>` (captures output).
-pub fn send(path: &Path) -> Result<String> {
-    let output = Command::new("email")
+/// Run `email send <file>` (captures output). Refuses drafts whose `send_at`
+/// is still in the future.
+pub fn send(dirs: &[Option<PathBuf>; 6], path: &Path) -> Result<String> {
+    if let Some(at) = email::read_send_at(path) {
+        if at > chrono::Local::now() {
+            anyhow::bail!("scheduled for {}, not due yet", at.format("%Y-%m-%d %H:%M"));
+        }
+    }
+    let output = email_command(dirs)
         .arg("send")
         .arg(path)
         .env("NO_COLOR", "1")
@@ -76,8 +314,8 @@ pub fn send(path: &Path) -> Result<String> {
 }
 
 /// Run `email send-approved [dir]` (captures output).
-pub fn send_approved(dir: &Path) -> Result<String> {
-    let output = Command::new("email")
+pub fn send_approved(dirs: &[Option<PathBuf>; 6], dir: &Path) -> Result<String> {
+    let output = email_command(dirs)
         .arg("send-approved")
         .arg(dir)
         .env("NO_COLOR", "1")
@@ -92,8 +330,8 @@ pub fn send_approved(dir: &Path) -> Result<String> {
 }
 
 /// Run `email fetch` (silent, captures output).
-pub fn fetch() -> Result<String> {
-    let output = Command::new("email")
+pub fn fetch(dirs: &[Option<PathBuf>; 6]) -> Result<String> {
+    let output = email_command(dirs)
         .arg("fetch")
         .output()
         .context("Failed to run email fetch")?;
@@ -106,8 +344,8 @@ pub fn fetch() -> Result<String> {
 }
 
 /// Run `email sync` (silent, captures output).
-pub fn sync() -> Result<String> {
-    let output = Command::new("email")
+pub fn sync(dirs: &[Option<PathBuf>; 6]) -> Result<String> {
+    let output = email_command(dirs)
         .arg("sync")
         .output()
         .context("Failed to run email sync")?;
@@ -119,14 +357,24 @@ pub fn sync() -> Result<String> {
     Ok(msg)
 }
 
+/// Seconds `email sync --reconcile` may run before the tool gives up.
+const RECONCILE_TIMEOUT_SECS: &str = "600";
+
 /// Run `email sync --reconcile` (silent, captures output).
-pub fn sync_reconcile() -> Result<String> {
-    let output = Command::new("email")
-        .args(["sync", "--reconcile"])
+pub fn sync_reconcile(dirs: &[Option<PathBuf>; 6]) -> Result<String> {
+    let output = email_command(dirs)
+        .args(["sync", "--reconcile", "--timeout", RECONCILE_TIMEOUT_SECS])
         .output()
         .context("Failed to run email sync --reconcile")?;
     if !output.status.success() {
-        let err = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        // Fall back to stdout (then the exit status) when stderr is empty
+        let mut err = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if err.is_empty() {
+            err = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        }
+        if err.is_empty() {
+            err = output.status.to_string();
+        }
         anyhow::bail!("email sync --reconcile failed: {}", err);
     }
     let msg = String::from_utf8_lossy(&output.stdout).trim().to_string();
@@ -134,8 +382,8 @@ pub fn sync_reconcile() -> Result<String> {
 }
 
 /// Run `email new <name>` (silent, returns output message).
-pub fn new_draft(name: &str) -> Result<String> {
-    let output = Command::new("email")
+pub fn new_draft(dirs: &[Option<PathBuf>; 6], name: &str) -> Result<String> {
+    let output = email_command(dirs)
         .arg("new")
         .arg(name)
         .output()
@@ -148,9 +396,51 @@ pub fn new_draft(name: &str) -> Result<String> {
     Ok(msg)
 }
 
+/// Create a new draft with `email new <name>`, then fill its frontmatter and
+/// body from a `mailto:` URI.
+pub fn new_draft_mailto(
+    dirs: &[Option<PathBuf>; 6],
+    name: &str,
+    mailto: &str,
+    drafts_dir: &Path,
+) -> Result<String> {
+    let fields = email::parse_mailto(mailto).context("Not a mailto: URI")?;
+    let msg = new_draft(dirs, name)?;
+    let path = drafts_dir.join(format!("{name}.md"));
+    let mut content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let headers = [
+        ("to", Some(fields.to.as_str())),
+        ("cc", fields.cc.as_deref()),
+        ("subject", fields.subject.as_deref()),
+    ];
+    for (key, value) in headers {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            content = email::set_frontmatter_field(&content, key, Some(&email::yaml_quote(value)))
+                .context("Draft has no frontmatter")?;
+        }
+    }
+    if let Some(body) = &fields.body {
+        // Replace whatever body `email new` wrote after the closing `---`
+        let end = content
+            .strip_prefix("---\n")
+            .and_then(|rest| rest.find("\n---\n"))
+            .map(|i| i + "---\n".len() + "\n---\n".len());
+        if let Some(end) = end {
+            content.truncate(end);
+            content.push('\n');
+            content.push_str(body);
+            content.push('\n');
+        }
+    }
+    std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(msg)
+}
+
 /// Run `email delete <file>` (deletes server-side via IMAP + removes locally).
-pub fn delete(path: &Path) -> Result<String> {
-    let output = Command::new("email")
+pub fn delete(dirs: &[Option<PathBuf>; 6], path: &Path) -> Result<String> {
+    let output = email_command(dirs)
         .arg("delete")
         .arg(path)
         .env("NO_COLOR", "1")
@@ -164,9 +454,35 @@ pub fn delete(path: &Path) -> Result<String> {
     Ok(msg)
 }
 
+/// Move an email into `trash_dir` without touching the server. Returns the new path.
+pub fn move_to_trash(path: &Path, trash_dir: &Path) -> Result<PathBuf> {
+    let dest = trash_dir.join(path.file_name().context("Email path has no file name")?);
+    if dest.exists() {
+        anyhow::bail!("{} already exists", dest.display());
+    }
+    std::fs::rename(path, &dest)
+        .with_context(|| format!("Failed to move {} to the trash", path.display()))?;
+    Ok(dest)
+}
+
+/// Outcome of `email archive`: its message and where the file went.
+#[derive(Debug, Clone)]
+pub struct ArchiveResult {
+    pub message: String,
+    pub source_path: PathBuf,
+    /// The archived file, or `None` when it could not be located.
+    pub dest_path: Option<PathBuf>,
+}
+
 /// Run `email archive <file>` (archives server-side via IMAP + moves locally).
-pub fn archive(path: &Path) -> Result<String> {
-    let output = Command::new("email")
+/// The destination is read from the command output when it names an existing
+/// file, and otherwise assumed to be the same file name in `archive_dir`.
+pub fn archive(
+    dirs: &[Option<PathBuf>; 6],
+    path: &Path,
+    archive_dir: Option<&Path>,
+) -> Result<ArchiveResult> {
+    let output = email_command(dirs)
         .arg("archive")
         .arg(path)
         .env("NO_COLOR", "1")
@@ -176,10 +492,275 @@ pub fn archive(path: &Path) -> Result<String> {
         let err = String::from_utf8_lossy(&output.stderr).trim().to_string();
         anyhow::bail!("email archive failed: {}", err);
     }
+    let message = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let dest_path = message
+        .lines()
+        .filter_map(|line| {
+            let candidate = line.rsplit([':', '\u{2192}']).next()?.trim();
+            let candidate = candidate.strip_prefix('>').unwrap_or(candidate).trim();
+            Some(PathBuf::from(shellexpand::tilde(candidate).into_owned()))
+        })
+        .find(|p| p.is_absolute() && p.is_file())
+        .or_else(|| Some(archive_dir?.join(path.file_name()?)));
+    Ok(ArchiveResult { message, source_path: path.to_path_buf(), dest_path })
+}
+
+/// Run `email spam <file>` to report the email and move it to the spam folder.
+pub fn mark_spam(dirs: &[Option<PathBuf>; 6], path: &Path) -> Result<String> {
+    let output = email_command(dirs)
+        .arg("spam")
+        .arg(path)
+        .env("NO_COLOR", "1")
+        .output()
+        .context("Failed to run email spam")?;
+    if !output.status.success() {
+        let err = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        anyhow::bail!("email spam failed: {}", err);
+    }
     let msg = String::from_utf8_lossy(&output.stdout).trim().to_string();
     Ok(msg)
 }
 
+/// Flip the `starred` frontmatter flag of an email file.
+pub fn toggle_star(path: &Path) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let value = if email::read_starred(path) { None } else { Some("true") };
+    let updated = email::set_frontmatter_field(&content, "starred", value)
+        .context("Email has no frontmatter")?;
+    std::fs::write(path, updated).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Set the `status` frontmatter field of an email to `read`.
+pub fn mark_read(path: &Path) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let updated = email::set_frontmatter_field(&content, "status", Some("read"))
+        .context("Email has no frontmatter")?;
+    std::fs::write(path, updated).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Record why a scheduled send failed in the draft's `send_error` field, so
+/// the scheduler stops retrying it until the field is removed.
+pub fn record_send_error(path: &Path, error: &str) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let value = email::yaml_quote(error);
+    let updated = email::set_frontmatter_field(&content, "send_error", Some(&value))
+        .context("Draft has no frontmatter")?;
+    std::fs::write(path, updated).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Set the `to` frontmatter field of a draft.
+pub fn set_recipients(path: &Path, to: &str) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let updated = email::set_frontmatter_field(&content, "to", Some(&email::yaml_quote(to)))
+        .context("Draft has no frontmatter")?;
+    std::fs::write(path, updated).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Copy `file_path` next to a draft (into `<draft dir>/<stem>/`) and list it
+/// in the draft's `attachments` frontmatter, replacing a same-named entry.
+pub fn attach(draft_path: &Path, file_path: &Path) -> Result<()> {
+    if !file_path.is_file() {
+        anyhow::bail!("not a file: {}", file_path.display());
+    }
+    let filename = file_path
+        .file_name()
+        .context("File path has no file name")?
+        .to_string_lossy()
+        .to_string();
+    let stem = draft_path.file_stem().context("Draft path has no file name")?;
+    let dir = draft_path.parent().unwrap_or(Path::new(".")).join(stem);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let dest = dir.join(&filename);
+    let size = std::fs::copy(file_path, &dest)
+        .with_context(|| format!("Failed to copy {}", file_path.display()))?;
+
+    let mut attachments = email::read_attachments(draft_path)?;
+    attachments.retain(|a| a.filename != filename);
+    attachments.push(email::AttachmentInfo {
+        mime_type: guess_mime_type(&filename).to_string(),
+        filename,
+        size_bytes: size,
+    });
+    let list = attachments
+        .iter()
+        .map(|a| {
+            format!(
+                "{{filename: {}, mime_type: {}, size_bytes: {}}}",
+                email::yaml_quote(&a.filename),
+                email::yaml_quote(&a.mime_type),
+                a.size_bytes
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let content = std::fs::read_to_string(draft_path)
+        .with_context(|| format!("Failed to read {}", draft_path.display()))?;
+    let count = attachments.len().to_string();
+    let updated = email::set_frontmatter_field(&content, "attachments", Some(&format!("[{list}]")))
+        .and_then(|c| email::set_frontmatter_field(&c, "attachment_count", Some(&count)))
+        .and_then(|c| email::set_frontmatter_field(&c, "has_attachments", Some("true")))
+        .context("Draft has no frontmatter")?;
+    std::fs::write(draft_path, updated)
+        .with_context(|| format!("Failed to write {}", draft_path.display()))
+}
+
+/// MIME type for common attachment extensions.
+fn guess_mime_type(filename: &str) -> &'static str {
+    let ext = filename.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("pdf") => "application/pdf",
+        Some("zip") => "application/zip",
+        Some("json") => "application/json",
+        Some("doc") => "application/msword",
+        Some("docx") => {
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        }
+        Some("xlsx") => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        Some("txt" | "md") => "text/plain",
+        Some("csv") => "text/csv",
+        Some("html" | "htm") => "text/html",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Paths completing `input`: entries of its directory whose names start with
+/// the last component, directories suffixed with `/`. `~` is expanded for
+/// the lookup but kept in the results.
+pub fn complete_path(input: &str) -> Vec<String> {
+    let (dir_part, name_part) = match input.rfind('/') {
+        Some(i) => input.split_at(i + 1),
+        None => ("", input),
+    };
+    let dir = match dir_part {
+        "" => PathBuf::from("."),
+        dir => PathBuf::from(shellexpand::tilde(dir).as_ref()),
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut matches: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Hidden entries only when asked for
+            let hidden = name.starts_with('.') && !name_part.starts_with('.');
+            if hidden || !name.starts_with(name_part) {
+                return None;
+            }
+            let slash = if entry.path().is_dir() { "/" } else { "" };
+            Some(format!("{dir_part}{name}{slash}"))
+        })
+        .collect();
+    matches.sort();
+    matches
+}
+
+/// Stamp `snoozed_until` into an inbox email and move it to the snooze
+/// directory, returning its new path.
+pub fn snooze(path: &Path, until: NaiveDateTime, inbox_dir: &Path) -> Result<PathBuf> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let stamp = email::yaml_quote(&until.format(email::SNOOZE_FORMAT).to_string());
+    let updated = email::set_frontmatter_field(&content, "snoozed_until", Some(&stamp))
+        .context("Email has no frontmatter")?;
+    let dir = email::snooze_dir(inbox_dir);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let dest = dir.join(path.file_name().context("Email path has no file name")?);
+    std::fs::write(&dest, updated).with_context(|| format!("Failed to write {}", dest.display()))?;
+    std::fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    Ok(dest)
+}
+
+/// Move a snoozed email back into the inbox, dropping `snoozed_until`.
+pub fn unsnooze(path: &Path, inbox_dir: &Path) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let updated = email::set_frontmatter_field(&content, "snoozed_until", None).unwrap_or(content);
+    let dest = inbox_dir.join(path.file_name().context("Email path has no file name")?);
+    std::fs::write(&dest, updated).with_context(|| format!("Failed to write {}", dest.display()))?;
+    std::fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))
+}
+
+/// Write `paths` to `output` as an mbox file, calling `progress` with the
+/// number of messages written so far. Returns the total written.
+pub fn export_mbox(
+    paths: &[PathBuf],
+    output: &Path,
+    mut progress: impl FnMut(usize),
+) -> Result<usize> {
+    let file = std::fs::File::create(output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+    let mut writer = std::io::BufWriter::new(file);
+    for (i, path) in paths.iter().enumerate() {
+        let msg = email::to_mbox_message(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        writer
+            .write_all(msg.as_bytes())
+            .with_context(|| format!("Failed to write {}", output.display()))?;
+        progress(i + 1);
+    }
+    writer.flush().with_context(|| format!("Failed to write {}", output.display()))?;
+    Ok(paths.len())
+}
+
+/// Where exported files go: `$XDG_DOWNLOAD_DIR`, else the platform's
+/// downloads folder, else `~/Downloads`.
+fn download_dir() -> Result<PathBuf> {
+    std::env::var_os("XDG_DOWNLOAD_DIR")
+        .map(PathBuf::from)
+        .or_else(dirs::download_dir)
+        .or_else(|| dirs::home_dir().map(|home| home.join("Downloads")))
+        .context("No downloads directory")
+}
+
+/// Render an email to `<downloads>/<stem>.pdf` with pandoc, returning the
+/// output path.
+pub fn export_pdf(path: &Path) -> Result<PathBuf> {
+    let stem = path.file_stem().context("Email path has no file name")?;
+    let dir = download_dir()?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let mut output = dir.join(stem);
+    output.set_extension("pdf");
+    let result = Command::new("pandoc")
+        .arg(path)
+        .arg("-o")
+        .arg(&output)
+        .arg("--pdf-engine=xelatex")
+        .output();
+    let result = match result {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            anyhow::bail!("pandoc not found; install pandoc and xelatex to export PDFs")
+        }
+        other => other.context("Failed to run pandoc")?,
+    };
+    if !result.status.success() {
+        let err = String::from_utf8_lossy(&result.stderr).trim().to_string();
+        anyhow::bail!("pandoc failed: {}", err);
+    }
+    Ok(output)
+}
+
+/// Move an email file into `dest_dir`, returning a status message.
+pub fn move_email(path: &Path, dest_dir: &Path) -> Result<String> {
+    let name = path.file_name().context("Email path has no file name")?;
+    let dest = dest_dir.join(name);
+    if dest.exists() {
+        anyhow::bail!("{} already exists", dest.display());
+    }
+    std::fs::rename(path, &dest)
+        .with_context(|| format!("Failed to move to {}", dest_dir.display()))?;
+    Ok(format!("Moved to {}", dest_dir.display()))
+}
+
 /// Copy text to system clipboard.
 pub fn copy_to_clipboard(text: &str) -> Result<()> {
     let mut clipboard =
@@ -189,3 +770,146 @@ pub fn copy_to_clipboard(text: &str) -> Result<()> {
         .context("Failed to copy to clipboard")?;
     Ok(())
 }
+
+/// Read text from the system clipboard.
+pub fn paste_from_clipboard() -> Result<String> {
+    let mut clipboard =
+        arboard::Clipboard::new().context("Failed to access clipboard")?;
+    clipboard.get_text().context("Clipboard has no text")
+}
+
+/// Build the platform's "open with default application" command for `target`.
+fn system_open(target: &OsStr) -> Command {
+    #[cfg(target_os = "macos")]
+    let cmd = {
+        let mut c = Command::new("open");
+        c.arg(target);
+        c
+    };
+    #[cfg(target_os = "windows")]
+    let cmd = {
+        let mut c = Command::new("cmd");
+        c.args(["/C", "start", ""]).arg(target);
+        c
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let cmd = {
+        let mut c = Command::new("xdg-open");
+        c.arg(target);
+        c
+    };
+    cmd
+}
+
+/// Open a URL with the platform's default handler (browser, mail client, ...).
+pub fn open_url(url: &str) -> Result<()> {
+    let status = system_open(OsStr::new(url))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to launch URL opener")?;
+    if !status.success() {
+        anyhow::bail!("URL opener exited with status: {}", status);
+    }
+    Ok(())
+}
+
+/// Show the directory containing `path` in the file manager, without waiting for it.
+pub fn open_dir(path: &Path) -> Result<()> {
+    let dir = path.parent().unwrap_or(path);
+    system_open(dir.as_os_str())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("No file manager found")?;
+    Ok(())
+}
+
+/// Open an attachment file with the default application for its type.
+pub fn open_attachment(path: &Path, mime: &str) -> Result<()> {
+    let status = system_open(path.as_os_str())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to launch file opener")?;
+    if !status.success() {
+        let kind = if mime.is_empty() { "file" } else { mime };
+        anyhow::bail!("No application could open {} ({})", path.display(), kind);
+    }
+    Ok(())
+}
+
+/// Create a draft named `<name>.md` in the drafts directory from a template in
+/// `<drafts>/templates/`, filling in `{{VAR}}` placeholders from `vars`.
+pub fn new_draft_from_template(
+    name: &str,
+    template_path: &Path,
+    vars: &TemplateVars,
+) -> Result<String> {
+    let drafts_dir = template_path
+        .parent()
+        .and_then(Path::parent)
+        .context("Template is not inside a drafts directory")?;
+    let dest = drafts_dir.join(format!("{name}.md"));
+    if dest.exists() {
+        anyhow::bail!("Draft already exists: {}", dest.display());
+    }
+    let content = std::fs::read_to_string(template_path)
+        .with_context(|| format!("Failed to read template {}", template_path.display()))?;
+    let (content, unknown) = template::render(&content, &vars.as_map());
+    let template_name = template_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    // Remember the origin so the draft can be diffed against it later
+    let content = email::set_frontmatter_field(
+        &content,
+        "template",
+        Some(&email::yaml_quote(&template_name)),
+    )
+    .unwrap_or(content);
+    std::fs::write(&dest, content)
+        .with_context(|| format!("Failed to write {}", dest.display()))?;
+    if unknown.is_empty() {
+        Ok(format!("Draft created from template {template_name}"))
+    } else {
+        let names: Vec<String> = unknown.iter().map(|n| format!("{{{{{n}}}}}")).collect();
+        Ok(format!(
+            "Draft created from template {template_name} (unknown: {})",
+            names.join(", ")
+        ))
+    }
+}
+
+/// Save a copy of an email in the temp dir before it is deleted, for undo.
+pub fn backup_for_undo(path: &Path) -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join("beautifulmail-undo");
+    std::fs::create_dir_all(&dir).context("Failed to create undo directory")?;
+    let stamp = chrono::Local::now().format("%Y%m%d%H%M%S%f");
+    let name = path.file_name().context("Email path has no file name")?;
+    let backup = dir.join(format!("{stamp}-{}", name.to_string_lossy()));
+    std::fs::copy(path, &backup)
+        .with_context(|| format!("Failed to back up {}", path.display()))?;
+    Ok(backup)
+}
+
+/// Reverse an archive or delete by moving the local file back into place.
+/// Server-side changes made by the `email` CLI are not reverted.
+pub fn undo(entry: &UndoEntry) -> Result<()> {
+    let (src, dest) = match &entry.kind {
+        UndoKind::Archive { from, to } | UndoKind::Move { from, to } => (to, from),
+        UndoKind::Delete { backup, original } => (backup, original),
+    };
+    if dest.exists() {
+        anyhow::bail!("{} already exists", dest.display());
+    }
+    if !src.exists() {
+        anyhow::bail!("{} no longer exists", src.display());
+    }
+    // rename fails across filesystems (temp dir), so fall back to copy + remove
+    if std::fs::rename(src, dest).is_err() {
+        std::fs::copy(src, dest).with_context(|| format!("Failed to restore {}", dest.display()))?;
+        std::fs::remove_file(src).ok();
+    }
+    Ok(())
+}
@@ -0,0 +1,573 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use crate::app::Mailbox;
+use crate::theme::Theme;
+
+/// User configuration read from `~/.config/beautifulmail/config.toml`.
+/// Every field is optional; a missing file yields the defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Key overrides from the `[keys]` table (action name -> key spec).
+    pub keys: HashMap<String, String>,
+    /// Color each contact in the email list by a hash of their address.
+    pub sender_colors: bool,
+    /// Mailbox label overrides from `[mailbox_labels]` (mailbox name -> label).
+    pub mailbox_labels: HashMap<String, String>,
+    /// Mailbox icon overrides from `[mailbox_icons]` (mailbox name -> icon).
+    pub mailbox_icons: HashMap<String, String>,
+    /// Catppuccin flavour: `latte`, `frappe`, `macchiato` or `mocha`.
+    pub theme: Theme,
+    /// Event poll interval in milliseconds (`BEAUTIFULMAIL_TICK_MS`).
+    pub tick_ms: u64,
+    /// How long status messages stay up, in seconds (`BEAUTIFULMAIL_STATUS_SECS`).
+    pub status_secs: u8,
+    /// Desktop notification when the watcher brings in new inbox mail.
+    pub notifications: bool,
+    /// Play a sound with new-mail notifications.
+    pub notifications_sound: bool,
+    /// Treat the body pane as a passive preview of the list: moving the list
+    /// cursor always shows the next email from its top.
+    pub auto_preview: bool,
+    /// Mail accounts from `[[account]]` tables; the first one is used at startup.
+    #[serde(rename = "account")]
+    pub accounts: Vec<AccountConfig>,
+    /// Email list columns in order: `date`, `contact`, `subject`, `status`,
+    /// `attachments`. Subject is always shown.
+    pub list_columns: Vec<String>,
+    /// Pattern recognising reply attribution lines in the body, where `*`
+    /// matches any text (default: `On * wrote:`).
+    pub attribution_pattern: Option<String>,
+    /// Attribution line written into reply drafts, with `{date}` and `{from}`
+    /// filled in from the original email. Unset keeps the `email` tool's line.
+    pub attribution_template: Option<String>,
+    /// Program and arguments run by the mail watcher instead of `email watch`
+    /// (e.g. `["mbsync", "-a"]`). It is rerun every `watcher_interval_secs`.
+    pub watcher_command: Option<Vec<String>>,
+    /// `email watch --timeout`, or the pause between runs of `watcher_command`.
+    pub watcher_interval_secs: u64,
+    /// Watcher exit code meaning new mail arrived.
+    pub watcher_exit_changed: u8,
+    /// Watcher exit code meaning nothing happened before the timeout.
+    pub watcher_exit_timeout: u8,
+    /// Pane borders: `rounded`, `plain`, `double`, `thick` or `none`.
+    pub border_style: BorderStyle,
+    /// Where mailboxes are read from: `filesystem` or `cli_json`.
+    pub email_source: EmailSource,
+    /// Per-mailbox settings from `[mailbox.<name>]` tables.
+    #[serde(rename = "mailbox")]
+    pub mailboxes: HashMap<String, MailboxConfig>,
+}
+
+/// One `[mailbox.<name>]` table.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MailboxConfig {
+    /// Email list key overrides for this mailbox (`[mailbox.drafts.keys]`),
+    /// applied on top of `[keys]`.
+    pub keys: Option<HashMap<String, String>>,
+}
+
+/// How mailbox contents are loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailSource {
+    /// Parse the `.md` files in each mailbox directory.
+    #[default]
+    Filesystem,
+    /// Ask `email list --json --mailbox <name>` for the metadata.
+    CliJson,
+}
+
+/// Border line set drawn around panes and overlays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderStyle {
+    #[default]
+    Rounded,
+    Plain,
+    Double,
+    Thick,
+    /// No borders; panes gain the two cells they would take.
+    None,
+}
+
+/// How the background watcher runs its command, from the config.
+#[derive(Debug, Clone)]
+pub struct WatcherSettings {
+    /// Program followed by its arguments.
+    pub argv: Vec<String>,
+    /// Pause after each run; zero for `email watch`, which blocks by itself.
+    pub pause: Duration,
+    pub exit_changed: i32,
+    pub exit_timeout: i32,
+}
+
+/// A column of the email list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnId {
+    Date,
+    Contact,
+    Subject,
+    Status,
+    Attachments,
+    /// Not configurable: follows Contact while `C-c` is on.
+    Cc,
+}
+
+impl ColumnId {
+    /// Parse a `list_columns` entry.
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "date" => Some(ColumnId::Date),
+            "contact" => Some(ColumnId::Contact),
+            "subject" => Some(ColumnId::Subject),
+            "status" => Some(ColumnId::Status),
+            "attachments" => Some(ColumnId::Attachments),
+            _ => None,
+        }
+    }
+
+    /// Header cell text.
+    pub fn title(self) -> &'static str {
+        match self {
+            ColumnId::Date => "DATE",
+            ColumnId::Contact => "CONTACT",
+            ColumnId::Subject => "SUBJECT",
+            ColumnId::Status => "STATUS",
+            ColumnId::Attachments => "",
+            ColumnId::Cc => "CC",
+        }
+    }
+}
+
+/// One `[[account]]` table. While it is active its directories replace the
+/// `INBOX_DIR`-style variables, for this app and for the `email` tool.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AccountConfig {
+    pub name: String,
+    pub inbox_dir: Option<String>,
+    pub drafts_dir: Option<String>,
+    pub sent_dir: Option<String>,
+    pub archive_dir: Option<String>,
+    pub spam_dir: Option<String>,
+    pub trash_dir: Option<String>,
+}
+
+impl AccountConfig {
+    /// Configured directories in `Mailbox::ALL` order.
+    pub fn dirs(&self) -> [Option<&str>; 6] {
+        [
+            self.inbox_dir.as_deref(),
+            self.drafts_dir.as_deref(),
+            self.sent_dir.as_deref(),
+            self.archive_dir.as_deref(),
+            self.spam_dir.as_deref(),
+            self.trash_dir.as_deref(),
+        ]
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            keys: HashMap::new(),
+            sender_colors: true,
+            mailbox_labels: HashMap::new(),
+            mailbox_icons: HashMap::new(),
+            theme: Theme::default(),
+            tick_ms: env_or("BEAUTIFULMAIL_TICK_MS", 250).max(MIN_TICK_MS),
+            status_secs: env_or("BEAUTIFULMAIL_STATUS_SECS", 3),
+            notifications: true,
+            notifications_sound: false,
+            auto_preview: false,
+            accounts: Vec::new(),
+            list_columns: ["date", "contact", "attachments", "subject"]
+                .map(str::to_string)
+                .to_vec(),
+            attribution_pattern: None,
+            attribution_template: None,
+            watcher_command: None,
+            watcher_interval_secs: 300,
+            watcher_exit_changed: 0,
+            watcher_exit_timeout: 2,
+            border_style: BorderStyle::default(),
+            email_source: EmailSource::default(),
+            mailboxes: HashMap::new(),
+        }
+    }
+}
+
+/// Lower bound for `tick_ms`, so the UI never busy-loops.
+const MIN_TICK_MS: u64 = 10;
+
+/// Parse an environment variable, falling back to `default` when unset or invalid.
+fn env_or<T: std::str::FromStr>(var: &str, default: T) -> T {
+    std::env::var(var)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(default)
+}
+
+/// Config file given with `--config`, replacing the default location.
+static PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+impl Config {
+    /// Use `path` as the config file for the rest of the run (`--config`).
+    pub fn set_path(path: PathBuf) {
+        let _ = PATH_OVERRIDE.set(path);
+    }
+
+    /// Location of the config file (`--config`, else `$XDG_CONFIG_HOME` or `~/.config`).
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = PATH_OVERRIDE.get() {
+            return Some(path.clone());
+        }
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|h| h.join(".config")))?;
+        Some(base.join("beautifulmail").join("config.toml"))
+    }
+
+    /// Load the config file, falling back to defaults when it does not exist.
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut config: Self = toml::from_str(&content)
+            .with_context(|| format!("Invalid config in {}", path.display()))?;
+        config.tick_ms = config.tick_ms.max(MIN_TICK_MS);
+        let known = |name: &String| Mailbox::ALL.iter().any(|m| m.name() == name.as_str());
+        for (table, overrides) in
+            [("mailbox_labels", &config.mailbox_labels), ("mailbox_icons", &config.mailbox_icons)]
+        {
+            if let Some(name) = overrides.keys().find(|k| !known(k)) {
+                anyhow::bail!("Unknown mailbox in [{table}]: {name}");
+            }
+        }
+        if let Some(name) = config.mailboxes.keys().find(|k| !known(k)) {
+            anyhow::bail!("Unknown mailbox: [mailbox.{name}]");
+        }
+        if config.accounts.iter().any(|a| a.name.trim().is_empty()) {
+            anyhow::bail!("Every [[account]] needs a name");
+        }
+        Ok(config)
+    }
+
+    /// The configured list columns without duplicates (Subject appended when
+    /// missing), and the entries that are not column names.
+    pub fn columns(&self) -> (Vec<ColumnId>, Vec<String>) {
+        let mut columns = Vec::new();
+        let mut unknown = Vec::new();
+        for name in &self.list_columns {
+            match ColumnId::parse(name) {
+                Some(col) if !columns.contains(&col) => columns.push(col),
+                Some(_) => {}
+                None => unknown.push(name.clone()),
+            }
+        }
+        if !columns.contains(&ColumnId::Subject) {
+            columns.push(ColumnId::Subject);
+        }
+        (columns, unknown)
+    }
+
+    /// The watcher command: `watcher_command` when set, else `email watch`.
+    pub fn watcher(&self) -> WatcherSettings {
+        let interval = self.watcher_interval_secs.max(1);
+        let (argv, pause) = match self.watcher_command.clone().filter(|argv| !argv.is_empty()) {
+            Some(argv) => (argv, Duration::from_secs(interval)),
+            None => {
+                let argv = ["email", "watch", "--timeout"].map(str::to_string);
+                let mut argv = argv.to_vec();
+                argv.push(interval.to_string());
+                (argv, Duration::ZERO)
+            }
+        };
+        WatcherSettings {
+            argv,
+            pause,
+            exit_changed: self.watcher_exit_changed.into(),
+            exit_timeout: self.watcher_exit_timeout.into(),
+        }
+    }
+
+    /// The event poll interval.
+    pub fn tick_rate(&self) -> Duration {
+        Duration::from_millis(self.tick_ms)
+    }
+
+    /// Number of ticks covering `secs` seconds, saturating at `u8::MAX`.
+    pub fn ticks_for(&self, secs: u8) -> u8 {
+        (u64::from(secs) * 1000 / self.tick_ms).min(u64::from(u8::MAX)) as u8
+    }
+}
+
+/// Built-in bindings: action name -> space-separated key specs.
+const DEFAULT_BINDINGS: &[(&str, &str)] = &[
+    // Global
+    ("quit", "q"),
+    ("help", "?"),
+    ("filter", "/"),
+    ("search_body", "\\"),
+    ("goto_inbox", "1"),
+    ("goto_drafts", "2"),
+    ("goto_sent", "3"),
+    ("goto_archive", "4"),
+    ("goto_spam", "5"),
+    ("goto_trash", "6"),
+    ("focus_sidebar", "s"),
+    ("focus_next", "Tab l"),
+    ("focus_prev", "BackTab h"),
+    // C-z suspends to the shell like other terminal programs, so undo is M-z
+    ("undo", "M-z"),
+    ("suspend", "C-z"),
+    ("shrink_list", "["),
+    ("grow_list", "]"),
+    ("reset_split", "="),
+    ("cycle_theme", "C-t"),
+    ("toggle_cc", "C-c"),
+    ("toggle_age", "C-a"),
+    ("contacts", "C-k"),
+    ("accounts", "M-a"),
+    // q already quits, so macros record with Q
+    ("record_macro", "Q"),
+    ("play_macro", "@"),
+    // Shared navigation
+    ("down", "j Down"),
+    ("up", "k Up"),
+    // Sidebar
+    ("select", "Enter"),
+    ("sidebar_position", "C-g"),
+    // Email list
+    ("top", "g"),
+    ("bottom", "G"),
+    ("edit", "Enter e"),
+    ("reply", "r"),
+    ("reply_all", "R"),
+    ("forward", "F"),
+    ("archive", "a"),
+    ("move", "m"),
+    ("mark_spam", "!"),
+    ("delete", "d"),
+    ("empty_trash", "C-x"),
+    ("delete_duplicate", "D"),
+    ("snooze", "z"),
+    ("attach_file", "+"),
+    ("export_mbox", "C-e"),
+    // Terminals send Ctrl-M as Enter, so marking everything read is M-m
+    ("mark_all_read", "M-m"),
+    ("export_pdf", "C-p"),
+    ("diff_template", "M-c"),
+    ("approve", "A"),
+    ("send", "x"),
+    ("send_approved", "X"),
+    ("copy_path", "y"),
+    ("open_dir", "C-o"),
+    ("new", "n"),
+    ("new_from_template", "T"),
+    ("fetch", "f"),
+    ("sync", "C-s"),
+    ("show_scheduled", "S"),
+    ("reconcile", "C-r"),
+    ("thread_view", "t"),
+    ("date_filter", "D"),
+    ("toggle_star", "*"),
+    ("show_duplicates", "M-d"),
+    ("sort_column", "O"),
+    ("sort_order", "o"),
+    ("focus_headers", "H"),
+    // Terminals send Ctrl-/ as Ctrl-7
+    ("jump_search", "C-/ C-7"),
+    // Headers
+    ("copy_address", "c"),
+    ("headers_to_list", "h"),
+    ("headers_to_body", "l"),
+    ("open_attachment", "o"),
+    // F and T already forward / use a template in the list, so M-f / M-t work there
+    ("filter_from", "F M-f"),
+    ("filter_to", "T M-t"),
+    // Body preview
+    ("half_page_down", "d C-d"),
+    ("half_page_up", "C-u"),
+    ("page_down", "C-f"),
+    ("page_up", "C-b"),
+    ("next_link", "u"),
+    ("open_link", "Enter"),
+    ("visual_select", "v"),
+    ("toggle_wrap", "w"),
+    ("toggle_source", "V"),
+    ("scroll_left", "h Left"),
+    ("scroll_right", "l Right"),
+    ("yank", "y"),
+    ("next_match", "n"),
+    ("prev_match", "N"),
+    ("back", "Esc"),
+];
+
+/// Maps action names to key specs such as `a`, `C-r`, `M-x`, `S-Tab` or `Enter`.
+/// An action may list several specs separated by spaces (`"j Down"`).
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    pub bindings: HashMap<String, String>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: DEFAULT_BINDINGS
+                .iter()
+                .map(|(action, spec)| (action.to_string(), spec.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Defaults with user overrides applied. Fails on unknown actions or bad specs.
+    pub fn with_overrides(overrides: &HashMap<String, String>) -> Result<Self> {
+        Self::default().overridden(overrides)
+    }
+
+    /// A copy of this keymap with `overrides` applied on top.
+    fn overridden(&self, overrides: &HashMap<String, String>) -> Result<Self> {
+        let mut keymap = self.clone();
+        for (action, spec) in overrides {
+            if !keymap.bindings.contains_key(action) {
+                anyhow::bail!("Unknown action in [keys]: {action}");
+            }
+            for part in spec.split_whitespace() {
+                if parse_key_spec(part).is_none() {
+                    anyhow::bail!("Invalid key spec for {action}: {part}");
+                }
+            }
+            keymap.bindings.insert(action.clone(), spec.clone());
+        }
+        Ok(keymap)
+    }
+
+    /// List keymaps in `Mailbox::ALL` order for the mailboxes with
+    /// `[mailbox.<name>.keys]`: that table applied on top of `global`, the
+    /// keymap built from `[keys]`. Only the mailbox tables are validated here.
+    pub fn per_mailbox(config: &Config, global: &Keymap) -> Result<[Option<Self>; 6]> {
+        let mut keymaps: [Option<Self>; 6] = Default::default();
+        for (keymap, mailbox) in keymaps.iter_mut().zip(Mailbox::ALL) {
+            let name = mailbox.name();
+            let Some(keys) = config.mailboxes.get(name).and_then(|m| m.keys.as_ref()) else {
+                continue;
+            };
+            *keymap =
+                Some(global.overridden(keys).with_context(|| format!("[mailbox.{name}.keys]"))?);
+        }
+        Ok(keymaps)
+    }
+
+    /// The shortest key bound to `action`, for hints (`e` for `"Enter e"`).
+    pub fn hint(&self, action: &str) -> Option<&str> {
+        let spec = self.bindings.get(action)?;
+        spec.split_whitespace().min_by_key(|part| part.chars().count())
+    }
+
+    /// Whether `key` triggers `action`.
+    pub fn matches(&self, action: &str, key: &KeyEvent) -> bool {
+        self.bindings.get(action).is_some_and(|spec| {
+            spec.split_whitespace()
+                .filter_map(parse_key_spec)
+                .any(|(code, mods)| key_matches(key, code, mods))
+        })
+    }
+
+    /// The first of `actions` bound to `key`, if any.
+    pub fn lookup(&self, key: &KeyEvent, actions: &[&'static str]) -> Option<&'static str> {
+        actions.iter().copied().find(|action| self.matches(action, key))
+    }
+}
+
+/// Compare an incoming key against a parsed spec. Shift is implied by the
+/// character itself (`G`), so it is only checked for non-character keys.
+fn key_matches(key: &KeyEvent, code: KeyCode, mods: KeyModifiers) -> bool {
+    let relevant = if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+        KeyModifiers::CONTROL | KeyModifiers::ALT
+    } else {
+        KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT
+    };
+    let key_code = match key.code {
+        // Terminals report Ctrl+letter in either case; compare case-insensitively
+        KeyCode::Char(c) if mods.contains(KeyModifiers::CONTROL) => {
+            KeyCode::Char(c.to_ascii_lowercase())
+        }
+        other => other,
+    };
+    let code = match code {
+        KeyCode::Char(c) if mods.contains(KeyModifiers::CONTROL) => {
+            KeyCode::Char(c.to_ascii_lowercase())
+        }
+        other => other,
+    };
+    key_code == code && (key.modifiers & relevant) == (mods & relevant)
+}
+
+/// Parse a single key spec (`a`, `C-x`, `M-x`, `S-x`, `Enter`, `F5`, ...).
+pub fn parse_key_spec(spec: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let mut mods = KeyModifiers::NONE;
+    let mut rest = spec;
+    loop {
+        if rest.len() <= 2 {
+            break;
+        }
+        match rest.get(..2) {
+            Some("C-") => mods |= KeyModifiers::CONTROL,
+            Some("M-") => mods |= KeyModifiers::ALT,
+            Some("S-") => mods |= KeyModifiers::SHIFT,
+            _ => break,
+        }
+        rest = &rest[2..];
+    }
+
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => {
+            if mods.contains(KeyModifiers::SHIFT) {
+                mods.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            } else {
+                KeyCode::Char(c)
+            }
+        }
+        _ => match rest {
+            "Enter" => KeyCode::Enter,
+            "Esc" => KeyCode::Esc,
+            "Tab" if mods.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            "Tab" => KeyCode::Tab,
+            "BackTab" => KeyCode::BackTab,
+            "Space" => KeyCode::Char(' '),
+            "Backspace" => KeyCode::Backspace,
+            "Delete" => KeyCode::Delete,
+            "Up" => KeyCode::Up,
+            "Down" => KeyCode::Down,
+            "Left" => KeyCode::Left,
+            "Right" => KeyCode::Right,
+            "Home" => KeyCode::Home,
+            "End" => KeyCode::End,
+            "PageUp" => KeyCode::PageUp,
+            "PageDown" => KeyCode::PageDown,
+            f if f.starts_with('F') => KeyCode::F(f[1..].parse().ok()?),
+            _ => return None,
+        },
+    };
+    Some((code, mods))
+}
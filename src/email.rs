@@ -1,12 +1,17 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-use anyhow::Result;
-use chrono::NaiveDate;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
 use gray_matter::engine::YAML;
 use gray_matter::Matter;
 use serde::Deserialize;
 
 use crate::app::Mailbox;
+use crate::cli;
+use crate::config::EmailSource;
 
 /// Parsed email entry for display in the list and preview.
 #[derive(Debug, Clone)]
@@ -14,22 +19,126 @@ use crate::app::Mailbox;
 pub struct EmailEntry {
     pub path: PathBuf,
     pub from: String,
+    /// Bare sender address (`alice@example.com`), without the display name.
+    pub from_addr: String,
     pub to: String,
+    /// Bare recipient addresses, comma-separated.
+    pub to_addr: String,
     pub cc: Option<String>,
+    /// Where replies should go when the sender set a `reply_to` address.
+    pub reply_to: Option<String>,
     pub subject: String,
     pub status: String,
     pub date_display: String,
     pub date_sort: String,
     pub body: String,
     pub has_attachments: bool,
+    /// Number of attachments from the `attachment_count` frontmatter key.
+    pub attachment_count: Option<usize>,
+    pub attachments: Vec<AttachmentInfo>,
+    /// Detached ASCII-armored PGP signature over the body, if signed.
+    pub pgp_signature: Option<String>,
+    /// When a scheduled draft should be sent.
+    pub send_at: Option<DateTime<FixedOffset>>,
+    /// Why the last scheduled send failed; the draft is not retried while set.
+    pub send_error: Option<String>,
+    /// Pinned to the top of the list (`starred: true`).
+    pub starred: bool,
+    /// When a snoozed email returns to the inbox (local time).
+    pub snoozed_until: Option<NaiveDateTime>,
+    /// File modification time; the date of undated emails (fresh drafts).
+    pub modified_at: Option<SystemTime>,
+    /// Name of the template a draft was created from (`template` key).
+    pub template: Option<String>,
+    /// Importance from the `priority` frontmatter key.
+    pub priority: Priority,
+}
+
+/// Email importance, as carried over from `X-Priority` / `Importance` headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl Priority {
+    /// `high`, `1` or `urgent` is High; `low` or `5` is Low; anything else Normal.
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "high" | "1" | "urgent" => Priority::High,
+            "low" | "5" => Priority::Low,
+            _ => Priority::Normal,
+        }
+    }
+}
+
+/// An attachment listed in the `attachments` frontmatter key.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AttachmentInfo {
+    pub filename: String,
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default)]
+    pub size_bytes: u64,
 }
 
 impl EmailEntry {
+    /// `reply_to`, when it points somewhere other than the sender.
+    pub fn distinct_reply_to(&self) -> Option<&str> {
+        self.reply_to
+            .as_deref()
+            .filter(|r| !extract_email_address(r).eq_ignore_ascii_case(&self.from_addr))
+    }
+
+    /// Address identifying the contact shown for this mailbox (see `display_contact`).
+    pub fn contact_address(&self, mailbox: Mailbox) -> &str {
+        match mailbox {
+            Mailbox::Inbox | Mailbox::Archive | Mailbox::Spam | Mailbox::Trash => &self.from_addr,
+            Mailbox::Drafts | Mailbox::Sent => &self.to,
+        }
+    }
+
+    /// Locate an attachment on disk: `<stem>/<file>`, `attachments/<file>`, or
+    /// next to the email itself.
+    pub fn attachment_path(&self, attachment: &AttachmentInfo) -> Option<PathBuf> {
+        let dir = self.path.parent()?;
+        let stem = self.path.file_stem()?;
+        [
+            dir.join(stem).join(&attachment.filename),
+            dir.join("attachments").join(&attachment.filename),
+            dir.join(&attachment.filename),
+        ]
+        .into_iter()
+        .find(|p| p.exists())
+    }
+
+    /// Calendar date of the email, taken from `date_sort`.
+    pub fn date(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(self.date_sort.get(..10)?, "%Y-%m-%d").ok()
+    }
+
+    /// First Cc recipient's display name, with `+N` for the others.
+    pub fn cc_summary(&self) -> Option<String> {
+        let cc = split_address_list(self.cc.as_deref()?);
+        let first = extract_display_name(cc.first()?);
+        Some(match cc.len() {
+            1 => first,
+            n => format!("{first} +{}", n - 1),
+        })
+    }
+
+    /// Known number of attachments: the explicit count, else the listed files.
+    pub fn num_attachments(&self) -> usize {
+        self.attachment_count.unwrap_or(self.attachments.len())
+    }
+
     /// The contact to display depends on the mailbox:
-    /// Inbox/Archive show `from`, Drafts/Sent show `to`.
+    /// Inbox/Archive/Spam show `from`, Drafts/Sent show `to`.
     pub fn display_contact(&self, mailbox: Mailbox) -> &str {
         match mailbox {
-            Mailbox::Inbox | Mailbox::Archive => &self.from,
+            Mailbox::Inbox | Mailbox::Archive | Mailbox::Spam | Mailbox::Trash => &self.from,
             Mailbox::Drafts | Mailbox::Sent => &self.to,
         }
     }
@@ -41,16 +150,55 @@ struct Frontmatter {
     from: Option<String>,
     to: Option<String>,
     cc: Option<String>,
+    reply_to: Option<String>,
     subject: Option<String>,
     status: Option<String>,
     date: Option<String>,
     sent_at: Option<String>,
     has_attachments: Option<bool>,
+    attachment_count: Option<usize>,
+    #[serde(default)]
+    attachments: Vec<AttachmentInfo>,
+    pgp_signature: Option<String>,
+    send_at: Option<String>,
+    send_error: Option<String>,
+    starred: Option<bool>,
+    snoozed_until: Option<String>,
+    template: Option<String>,
+    #[serde(default, deserialize_with = "string_or_number")]
+    priority: Option<String>,
+}
+
+/// Accept a bare number (`priority: 1`) as well as a string.
+fn string_or_number<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Text(String),
+        Number(i64),
+    }
+    Ok(Option::<Raw>::deserialize(deserializer)?.map(|raw| match raw {
+        Raw::Text(text) => text,
+        Raw::Number(n) => n.to_string(),
+    }))
 }
 
-/// Load all emails from a directory.
+/// Files larger than this are not treated as emails.
+const MAX_EMAIL_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Load all emails from a directory, skipping files that fail to parse.
 pub fn load_emails(dir: &Path) -> Vec<EmailEntry> {
+    load_emails_with_errors(dir).0
+}
+
+/// Load all emails from a directory, also returning why each skipped file
+/// could not be parsed.
+pub fn load_emails_with_errors(dir: &Path) -> (Vec<EmailEntry>, Vec<(PathBuf, String)>) {
     let mut entries = Vec::new();
+    let mut errors = Vec::new();
 
     let walker = walkdir::WalkDir::new(dir)
         .max_depth(1)
@@ -64,27 +212,245 @@ pub fn load_emails(dir: &Path) -> Vec<EmailEntry> {
     for entry in walker {
         match parse_email(entry.path()) {
             Ok(email) => entries.push(email),
-            Err(_) => continue, // Skip unparseable files
+            Err(e) => errors.push((entry.path().to_path_buf(), format!("{e:#}"))),
         }
     }
 
-    // Sort by date descending (newest first)
-    entries.sort_by(|a, b| b.date_sort.cmp(&a.date_sort));
-    entries
+    sort_emails(&mut entries, SortColumn::Date, false);
+    (entries, errors)
+}
+
+/// Load a mailbox from the configured source. A failing `email list` is
+/// reported as a parse error inside `dir`, so it shows up under `?E`.
+pub fn load_mailbox(
+    source: EmailSource,
+    dirs: &[Option<PathBuf>; 6],
+    mailbox: Mailbox,
+    dir: &Path,
+) -> (Vec<EmailEntry>, Vec<(PathBuf, String)>) {
+    match source {
+        EmailSource::Filesystem => load_emails_with_errors(dir),
+        EmailSource::CliJson => match load_emails_from_cli(dirs, mailbox.name()) {
+            Ok(entries) => (entries, Vec::new()),
+            Err(e) => (Vec::new(), vec![(dir.join("email list"), format!("{e:#}"))]),
+        },
+    }
+}
+
+/// One element of the `email list --json` array: the frontmatter keys plus
+/// the file path and body.
+#[derive(Deserialize)]
+struct CliEmail {
+    path: PathBuf,
+    #[serde(default)]
+    body: String,
+    #[serde(flatten)]
+    frontmatter: Frontmatter,
+}
+
+/// Load a mailbox from `email list --json --mailbox <name>` instead of
+/// walking its directory.
+pub fn load_emails_from_cli(
+    dirs: &[Option<PathBuf>; 6],
+    mailbox: &str,
+) -> Result<Vec<EmailEntry>> {
+    let output = cli::email_command(dirs)
+        .args(["list", "--json", "--mailbox", mailbox])
+        .stdin(std::process::Stdio::null())
+        .output()
+        .context("Failed to run email list")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("email list failed: {}", stderr.trim());
+    }
+    let emails: Vec<CliEmail> =
+        serde_json::from_slice(&output.stdout).context("Invalid JSON from email list")?;
+    let mut entries: Vec<EmailEntry> = emails
+        .into_iter()
+        .map(|e| {
+            let modified = std::fs::metadata(&e.path).and_then(|m| m.modified()).ok();
+            entry_from_frontmatter(&e.path, e.frontmatter, &e.body, modified)
+        })
+        .collect();
+    sort_emails(&mut entries, SortColumn::Date, false);
+    Ok(entries)
+}
+
+/// Group entries sharing `(from, to, subject, date_sort)`, keeping only groups
+/// with more than one member. Each group lists the most recently modified
+/// file first; the rest are the duplicates.
+pub fn find_duplicates(entries: &[EmailEntry]) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut by_key: HashMap<_, usize> = HashMap::new();
+    for (i, e) in entries.iter().enumerate() {
+        let key = (&e.from, &e.to, &e.subject, &e.date_sort);
+        match by_key.get(&key) {
+            Some(&g) => groups[g].push(i),
+            None => {
+                by_key.insert(key, groups.len());
+                groups.push(vec![i]);
+            }
+        }
+    }
+    groups.retain(|g| g.len() > 1);
+    for group in &mut groups {
+        group.sort_by_key(|&i| {
+            let modified = std::fs::metadata(&entries[i].path).and_then(|m| m.modified()).ok();
+            std::cmp::Reverse(modified)
+        });
+    }
+    groups
+}
+
+/// Aggregates over one mailbox for the stats overlay.
+#[derive(Debug, Clone, Default)]
+pub struct MailboxStats {
+    pub total: usize,
+    pub oldest: Option<NaiveDate>,
+    pub newest: Option<NaiveDate>,
+    /// Most frequent senders with their email counts, busiest first (at most 5).
+    pub top_senders: Vec<(String, usize)>,
+    /// Emails per weekday, Monday first.
+    pub weekdays: [usize; 7],
+}
+
+/// Count emails, date span, top senders and weekday distribution.
+pub fn compute_stats(entries: &[EmailEntry]) -> MailboxStats {
+    let mut stats = MailboxStats {
+        total: entries.len(),
+        ..MailboxStats::default()
+    };
+    let mut senders: HashMap<&str, usize> = HashMap::new();
+    for e in entries {
+        *senders.entry(e.from.as_str()).or_default() += 1;
+        if let Some(date) = e.date() {
+            stats.oldest = Some(stats.oldest.map_or(date, |d| d.min(date)));
+            stats.newest = Some(stats.newest.map_or(date, |d| d.max(date)));
+            stats.weekdays[date.weekday().num_days_from_monday() as usize] += 1;
+        }
+    }
+    let mut senders: Vec<(String, usize)> =
+        senders.into_iter().map(|(s, n)| (s.to_string(), n)).collect();
+    senders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    senders.truncate(5);
+    stats.top_senders = senders;
+    stats
+}
+
+/// How many emails a sender has across all mailboxes (contacts overlay).
+#[derive(Debug, Clone)]
+pub struct ContactStat {
+    /// Bare address, lowercase.
+    pub address: String,
+    /// Display name from the address book, empty when none was seen.
+    pub name: String,
+    pub count: usize,
+}
+
+/// Address book entries that have sent at least one of `entries`, busiest first.
+pub fn contact_stats<'a>(
+    address_book: &[String],
+    entries: impl Iterator<Item = &'a EmailEntry>,
+) -> Vec<ContactStat> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for e in entries {
+        *counts.entry(e.from_addr.to_lowercase()).or_default() += 1;
+    }
+    let mut contacts: Vec<ContactStat> = address_book
+        .iter()
+        .filter_map(|entry| {
+            let address = extract_email_address(entry).to_lowercase();
+            let count = *counts.get(&address)?;
+            let name = extract_display_name(entry);
+            let name = if name.eq_ignore_ascii_case(&address) { String::new() } else { name };
+            Some(ContactStat { address, name, count })
+        })
+        .collect();
+    contacts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.address.cmp(&b.address)));
+    contacts
+}
+
+/// Column the email list is sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortColumn {
+    #[default]
+    Date,
+    From,
+    Subject,
+}
+
+impl SortColumn {
+    pub fn label(self) -> &'static str {
+        match self {
+            SortColumn::Date => "Date",
+            SortColumn::From => "From",
+            SortColumn::Subject => "Subject",
+        }
+    }
+
+    /// Date -> From -> Subject -> Date.
+    pub fn next(self) -> SortColumn {
+        match self {
+            SortColumn::Date => SortColumn::From,
+            SortColumn::From => SortColumn::Subject,
+            SortColumn::Subject => SortColumn::Date,
+        }
+    }
+}
+
+/// Sort starred emails first, then by `col` (ties newest first).
+pub fn sort_emails(emails: &mut [EmailEntry], col: SortColumn, asc: bool) {
+    emails.sort_by(|a, b| compare_emails(a, b, col, asc));
+}
+
+/// Ordering used by `sort_emails`.
+pub fn compare_emails(a: &EmailEntry, b: &EmailEntry, col: SortColumn, asc: bool) -> Ordering {
+    let ord = match col {
+        SortColumn::Date => a.date_sort.cmp(&b.date_sort),
+        SortColumn::From => a.from.to_lowercase().cmp(&b.from.to_lowercase()),
+        SortColumn::Subject => strip_subject_prefixes(&a.subject)
+            .to_lowercase()
+            .cmp(&strip_subject_prefixes(&b.subject).to_lowercase()),
+    };
+    let ord = if asc { ord } else { ord.reverse() };
+    b.starred
+        .cmp(&a.starred)
+        .then(ord)
+        .then_with(|| b.date_sort.cmp(&a.date_sort))
 }
 
 /// Parse a single email markdown file.
 fn parse_email(path: &Path) -> Result<EmailEntry> {
-    let content = std::fs::read_to_string(path)?;
+    let metadata = std::fs::metadata(path).context("Cannot read file")?;
+    let size = metadata.len();
+    if size > MAX_EMAIL_BYTES {
+        anyhow::bail!("File is {}, too large for an email", format_size(size));
+    }
+    let content = std::fs::read_to_string(path).context("Not a UTF-8 text file")?;
     let matter = Matter::<YAML>::new();
     let result = matter.parse(&content);
 
-    let fm: Frontmatter = result
-        .data
-        .and_then(|d| d.deserialize().ok())
-        .unwrap_or_default();
+    let fm: Frontmatter = match result.data {
+        Some(data) => data.deserialize().context("Invalid frontmatter")?,
+        None => Frontmatter::default(),
+    };
+    // Empty fields are fine (fresh drafts), but a file without any of them is not an email
+    if fm.subject.is_none() && fm.from.is_none() && fm.to.is_none() {
+        anyhow::bail!("No subject, from or to field; not an email");
+    }
 
-    let body = result.content;
+    Ok(entry_from_frontmatter(path, fm, &result.content, metadata.modified().ok()))
+}
+
+/// Build an entry from parsed frontmatter and the raw body, whether it was
+/// read from the file or reported by `email list --json`.
+fn entry_from_frontmatter(
+    path: &Path,
+    fm: Frontmatter,
+    body: &str,
+    modified_at: Option<SystemTime>,
+) -> EmailEntry {
+    let body = decode_body(body);
 
     let from = fm.from.unwrap_or_default();
     let to = fm.to.unwrap_or_default();
@@ -92,27 +458,263 @@ fn parse_email(path: &Path) -> Result<EmailEntry> {
     let status = fm.status.unwrap_or_else(|| "unknown".to_string());
 
     // Resolve date: try `date` field (RFC 2822), then `sent_at` (ISO 8601),
-    // then fall back to filename prefix (YYYY-MM-DD).
-    let (date_display, date_sort) = resolve_date(&fm.date, &fm.sent_at, path);
+    // then fall back to filename prefix (YYYY-MM-DD), then to when the file
+    // was last edited.
+    let (mut date_display, mut date_sort) = resolve_date(&fm.date, &fm.sent_at, path);
+    if let Some(modified) = modified_at.filter(|_| date_sort.is_empty()) {
+        let local = chrono::DateTime::<chrono::Local>::from(modified);
+        date_sort = local.format("%Y-%m-%dT%H:%M:%S").to_string();
+        date_display = edited_label(local);
+    }
 
-    Ok(EmailEntry {
+    EmailEntry {
         path: path.to_path_buf(),
         from: extract_display_name(&from),
+        from_addr: extract_email_address(&from),
         to: extract_display_name(&to),
+        to_addr: extract_email_addresses(&to),
         cc: fm.cc,
+        reply_to: fm.reply_to.filter(|r| !r.trim().is_empty()),
         subject,
         status,
         date_display,
         date_sort,
         body,
-        has_attachments: fm.has_attachments.unwrap_or(false),
+        has_attachments: fm
+            .has_attachments
+            .unwrap_or(!fm.attachments.is_empty() || fm.attachment_count.is_some_and(|n| n > 0)),
+        attachment_count: fm.attachment_count,
+        attachments: fm.attachments,
+        pgp_signature: fm.pgp_signature.filter(|s| !s.trim().is_empty()),
+        send_at: fm
+            .send_at
+            .and_then(|s| DateTime::parse_from_rfc3339(s.trim()).ok()),
+        send_error: fm.send_error.filter(|e| !e.trim().is_empty()),
+        starred: fm.starred.unwrap_or(false),
+        snoozed_until: fm
+            .snoozed_until
+            .and_then(|s| NaiveDateTime::parse_from_str(s.trim(), SNOOZE_FORMAT).ok()),
+        modified_at,
+        template: fm.template.filter(|t| !t.trim().is_empty()),
+        priority: fm.priority.as_deref().map_or(Priority::Normal, Priority::parse),
+    }
+}
+
+/// Date column text for an email dated by its modification time: `edited 2h`,
+/// or the plain date once it is a month old.
+fn edited_label(modified: chrono::DateTime<chrono::Local>) -> String {
+    let age = chrono::Local::now() - modified;
+    match (age.num_minutes(), age.num_hours(), age.num_days()) {
+        (..=0, _, _) => "edited now".to_string(),
+        (m, 0, _) => format!("edited {m}m"),
+        (_, h, 0) => format!("edited {h}h"),
+        (_, _, d @ ..=30) => format!("edited {d}d"),
+        _ => modified.format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// Decode body text left encoded by the `email` tool: strips a UTF-8 BOM,
+/// decodes base64 and quoted-printable MIME parts, and decodes quoted-printable
+/// outside of parts when the body declares it (a `Content-Transfer-Encoding`
+/// header or `=` soft line breaks) or on lines made only of `=XX` escapes.
+/// Anything that fails to decode is kept as is.
+pub fn decode_body(raw: &str) -> String {
+    let body = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+    let body = decode_mime_parts(body).unwrap_or_else(|| body.to_string());
+    if !body.contains('=') {
+        return body;
+    }
+    if is_quoted_printable(&body) {
+        return decode_qp_text(&body).unwrap_or(body);
+    }
+    // Plain text like `year=2024` is left alone; only pure escape lines decode
+    let mut decoded: Vec<String> = body
+        .split('\n')
+        .map(|line| {
+            let escapes = line.trim_end_matches('\r');
+            let only_escapes = !escapes.is_empty()
+                && escapes.len() % 3 == 0
+                && escapes.as_bytes().chunks(3).all(|c| c[0] == b'=');
+            only_escapes
+                .then(|| decode_qp_text(escapes))
+                .flatten()
+                .unwrap_or_else(|| line.to_string())
+        })
+        .collect();
+    if decoded.len() == 1 {
+        return decoded.remove(0);
+    }
+    decoded.join("\n")
+}
+
+/// Whether the whole body is quoted-printable: it carries the header, or a
+/// line ends in an `=` soft line break.
+fn is_quoted_printable(body: &str) -> bool {
+    body.lines().any(|line| {
+        let declared = line.split_once(':').is_some_and(|(key, value)| {
+            key.trim().eq_ignore_ascii_case("content-transfer-encoding")
+                && value.trim().eq_ignore_ascii_case("quoted-printable")
+        });
+        declared || (line.ends_with('=') && !line.ends_with("=="))
     })
 }
 
+/// Decode quoted-printable text to UTF-8, `None` when it does not decode
+/// cleanly.
+fn decode_qp_text(text: &str) -> Option<String> {
+    decode_quoted_printable(text)
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        // Stray `=12`-like text decodes to control characters
+        .filter(|decoded| !has_control_chars(decoded))
+}
+
+fn has_control_chars(text: &str) -> bool {
+    text.chars().any(|ch| ch.is_control() && !matches!(ch, '\n' | '\r' | '\t'))
+}
+
+/// Replace MIME parts (`--boundary`, headers, blank line, payload) whose
+/// `Content-Transfer-Encoding` is base64 or quoted-printable with their
+/// decoded text. Returns `None` when no part was decoded.
+fn decode_mime_parts(body: &str) -> Option<String> {
+    let lines: Vec<&str> = body.lines().collect();
+    let mut out: Vec<String> = Vec::with_capacity(lines.len());
+    let mut closing: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if closing.iter().any(|c| c == line.trim_end()) {
+            i += 1;
+            continue;
+        }
+        if let Some((text, end)) = decode_mime_part(&lines, i) {
+            closing.push(format!("{}--", line.trim_end()));
+            out.push(text);
+            i = end;
+            continue;
+        }
+        out.push(line.to_string());
+        i += 1;
+    }
+    if closing.is_empty() {
+        return None;
+    }
+    let mut decoded = out.join("\n");
+    if body.ends_with('\n') {
+        decoded.push('\n');
+    }
+    Some(decoded)
+}
+
+/// Decode the part opened by the boundary line at `start`, returning its text
+/// and the index of the line after its payload.
+fn decode_mime_part(lines: &[&str], start: usize) -> Option<(String, usize)> {
+    let boundary = lines[start].trim_end();
+    if !boundary.starts_with("--") || boundary.ends_with("--") || boundary.len() <= 2 {
+        return None;
+    }
+    let header_start = start + 1;
+    let header_end = header_start + lines[header_start..].iter().position(|l| l.trim().is_empty())?;
+    let headers = &lines[header_start..header_end];
+    let is_header = |l: &&str| l.starts_with([' ', '\t']) || l.contains(':');
+    if headers.is_empty() || !headers.iter().all(is_header) {
+        return None;
+    }
+    let encoding = headers.iter().find_map(|h| {
+        let (key, value) = h.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case("content-transfer-encoding")
+            .then(|| value.trim().to_ascii_lowercase())
+    })?;
+
+    let payload_start = header_end + 1;
+    let payload_end = lines[payload_start..]
+        .iter()
+        .position(|l| l.starts_with(boundary))
+        .map_or(lines.len(), |p| payload_start + p);
+    let payload = &lines[payload_start..payload_end];
+    let bytes = match encoding.as_str() {
+        "base64" => decode_base64(&payload.concat())?,
+        "quoted-printable" => decode_quoted_printable(&payload.join("\n"))?,
+        _ => return None,
+    };
+    let text = String::from_utf8(bytes).ok()?;
+    Some((text.trim_end().to_string(), payload_end))
+}
+
+/// Decode standard base64, ignoring whitespace. `None` on invalid characters.
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    for b in input.bytes().filter(|b| !b.is_ascii_whitespace()) {
+        let value = match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ => return None,
+        };
+        acc = (acc << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// Decode quoted-printable (`=3D` escapes, `=` soft line breaks).
+/// `None` when an `=` is neither.
+fn decode_quoted_printable(input: &str) -> Option<Vec<u8>> {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'=' {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        let rest = &bytes[i + 1..];
+        if rest.starts_with(b"\r\n") {
+            i += 3;
+        } else if rest.starts_with(b"\n") {
+            i += 2;
+        } else {
+            let hex = rest.get(..2).filter(|h| h.iter().all(u8::is_ascii_hexdigit))?;
+            let hex = std::str::from_utf8(hex).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        }
+    }
+    Some(out)
+}
+
+/// Problems worth a second look before sending a draft, e.g. `missing subject`.
+pub fn validate_draft(entry: &EmailEntry) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if entry.to_addr.trim().is_empty() || entry.to.trim().eq_ignore_ascii_case("unknown") {
+        warnings.push("no recipient".to_string());
+    }
+    if entry.subject.trim().is_empty() || entry.subject == "(no subject)" {
+        warnings.push("missing subject".to_string());
+    }
+    if entry.body.trim().chars().count() < 5 {
+        warnings.push("empty body".to_string());
+    }
+    if entry.from_addr.trim().is_empty() {
+        warnings.push("no sender".to_string());
+    }
+    warnings
+}
+
 /// Extract a short display name from an email address.
 /// "Sylvain Hellin <sylvain.hellin@tum.de>" -> "Sylvain Hellin"
 /// "sylvain.hellin@tum.de" -> "sylvain.hellin@tum.de"
-fn extract_display_name(addr: &str) -> String {
+pub fn extract_display_name(addr: &str) -> String {
     let addr = addr.trim().trim_matches('"');
     if let Some(idx) = addr.find('<') {
         let name = addr[..idx].trim().trim_matches('"');
@@ -127,6 +729,138 @@ fn extract_display_name(addr: &str) -> String {
     }
 }
 
+/// Extract the bare address from a mailbox string (inverse of `extract_display_name`).
+/// "Sylvain Hellin <sylvain.hellin@tum.de>" -> "sylvain.hellin@tum.de"
+pub fn extract_email_address(addr: &str) -> String {
+    let addr = addr.trim();
+    match (addr.find('<'), addr.rfind('>')) {
+        (Some(start), Some(end)) if start < end => addr[start + 1..end].trim().to_string(),
+        _ => addr.trim_matches('"').to_string(),
+    }
+}
+
+/// Bare addresses of a comma-separated recipient list, joined with ", ".
+pub fn extract_email_addresses(list: &str) -> String {
+    split_address_list(list)
+        .iter()
+        .map(|p| extract_email_address(p))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Split a comma-separated recipient list into trimmed, non-empty entries.
+/// Commas inside quoted display names do not split.
+pub fn split_address_list(list: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for ch in list.chars() {
+        match ch {
+            '"' => {
+                quoted = !quoted;
+                current.push(ch);
+            }
+            ',' if !quoted => parts.push(std::mem::take(&mut current)),
+            _ => current.push(ch),
+        }
+    }
+    parts.push(current);
+    parts
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Every distinct address in the `from`/`to`/`cc` fields of the emails in
+/// `dirs`, most frequent first. Entries keep a display name when one was seen
+/// (`Alice <alice@example.com>`).
+pub fn collect_addresses(dirs: &[PathBuf]) -> Vec<String> {
+    // bare lowercase address -> (best full form, occurrences)
+    let mut seen: HashMap<String, (String, usize)> = HashMap::new();
+    let matter = Matter::<YAML>::new();
+    for dir in dirs {
+        let files = walkdir::WalkDir::new(dir)
+            .max_depth(1)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                e.file_type().is_file()
+                    && e.path().extension().is_some_and(|ext| ext == "md")
+            });
+        for file in files {
+            let Ok(content) = std::fs::read_to_string(file.path()) else {
+                continue;
+            };
+            let fm: Frontmatter = matter
+                .parse(&content)
+                .data
+                .and_then(|d| d.deserialize().ok())
+                .unwrap_or_default();
+            let fields = [fm.from, fm.to, fm.cc];
+            for entry in fields.iter().flatten().flat_map(|list| split_address_list(list)) {
+                let addr = extract_email_address(&entry).to_lowercase();
+                if !addr.contains('@') {
+                    continue;
+                }
+                let slot = seen.entry(addr).or_insert_with(|| (entry.clone(), 0));
+                if !slot.0.contains('<') && entry.contains('<') {
+                    slot.0 = entry;
+                }
+                slot.1 += 1;
+            }
+        }
+    }
+    let mut book: Vec<(String, usize)> = seen.into_values().collect();
+    book.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    book.into_iter().map(|(entry, _)| entry).collect()
+}
+
+/// Render an email file as one mbox message (RFC 4155): a `From ` separator
+/// line, From/To/Cc/Subject/Date headers, a blank line, and the body with
+/// `From ` lines escaped as `>From `.
+pub fn to_mbox_message(path: &Path) -> Result<String> {
+    let content = std::fs::read_to_string(path)?;
+    let result = Matter::<YAML>::new().parse(&content);
+    let fm: Frontmatter = result
+        .data
+        .and_then(|d| d.deserialize().ok())
+        .unwrap_or_default();
+
+    let (_, date_sort) = resolve_date(&fm.date, &fm.sent_at, path);
+    let when = NaiveDateTime::parse_from_str(&date_sort, "%Y-%m-%dT%H:%M:%S")
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(&date_sort, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))
+        .unwrap_or_default();
+    let from = fm.from.unwrap_or_default();
+    let sender = match extract_email_address(&from) {
+        addr if addr.is_empty() => "MAILER-DAEMON".to_string(),
+        addr => addr,
+    };
+
+    let mut msg = format!("From {sender} {}\n", when.format("%a %b %e %H:%M:%S %Y"));
+    msg.push_str(&format!("From: {from}\n"));
+    msg.push_str(&format!("To: {}\n", fm.to.unwrap_or_default()));
+    if let Some(cc) = fm.cc.filter(|cc| !cc.is_empty()) {
+        msg.push_str(&format!("Cc: {cc}\n"));
+    }
+    msg.push_str(&format!("Subject: {}\n", fm.subject.unwrap_or_default()));
+    let date = fm
+        .date
+        .unwrap_or_else(|| when.format("%a, %d %b %Y %H:%M:%S +0000").to_string());
+    msg.push_str(&format!("Date: {date}\n\n"));
+    for line in result.content.lines() {
+        if line.starts_with("From ") {
+            msg.push('>');
+        }
+        msg.push_str(line);
+        msg.push('\n');
+    }
+    // Messages are separated by an empty line
+    msg.push('\n');
+    Ok(msg)
+}
+
 /// Resolve date for display and sorting.
 fn resolve_date(
     date_field: &Option<String>,
@@ -187,3 +921,482 @@ fn resolve_date(
 
     ("".to_string(), "".to_string())
 }
+
+/// Find http(s) URLs in a body, returning (byte offset, url) pairs in order.
+pub fn extract_urls(body: &str) -> Vec<(usize, String)> {
+    let mut urls = Vec::new();
+    let mut pos = 0;
+
+    while pos < body.len() {
+        let rest = &body[pos..];
+        let start = match (rest.find("http://"), rest.find("https://")) {
+            (Some(a), Some(b)) => a.min(b),
+            (Some(a), None) | (None, Some(a)) => a,
+            (None, None) => break,
+        };
+        let candidate = &rest[start..];
+        let end = candidate
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'' | '`'))
+            .unwrap_or(candidate.len());
+        let mut url = &candidate[..end];
+
+        // Drop trailing punctuation that belongs to the sentence, not the link
+        loop {
+            let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?']);
+            let trimmed = if (trimmed.ends_with(')') && !trimmed.contains('('))
+                || (trimmed.ends_with(']') && !trimmed.contains('['))
+            {
+                &trimmed[..trimmed.len() - 1]
+            } else {
+                trimmed
+            };
+            if trimmed.len() == url.len() {
+                break;
+            }
+            url = trimmed;
+        }
+
+        // Require something after the scheme
+        if url.split_once("://").is_some_and(|(_, host)| !host.is_empty()) {
+            urls.push((pos + start, url.to_string()));
+        }
+        pos += start + end.max(1);
+    }
+
+    urls
+}
+
+/// Score `pattern` as a case-insensitive subsequence of `haystack` (`jhn sm`
+/// matches `John Smith`), or `None` when it does not match. Consecutive
+/// characters and matches at word starts score higher; gaps cost a little.
+pub fn fuzzy_score(haystack: &str, pattern: &str) -> Option<i64> {
+    let mut pattern = pattern.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0i64;
+    let mut prev: Option<char> = None;
+    let mut gap = 0i64;
+    let mut last_matched = false;
+    for c in haystack.chars() {
+        let Some(&want) = pattern.peek() else {
+            break;
+        };
+        if c.to_lowercase().eq(std::iter::once(want)) {
+            score += 16;
+            if last_matched {
+                score += 8;
+            }
+            if prev.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 8;
+            }
+            score -= gap.min(8);
+            gap = 0;
+            last_matched = true;
+            pattern.next();
+        } else {
+            if score > 0 {
+                gap += 1;
+            }
+            last_matched = false;
+        }
+        prev = Some(c);
+    }
+    pattern.peek().is_none().then_some(score)
+}
+
+/// Char indices of `haystack` picked by the same greedy match as [`fuzzy_score`].
+pub fn fuzzy_positions(haystack: &str, pattern: &str) -> Option<Vec<usize>> {
+    let mut pattern = pattern.chars().flat_map(char::to_lowercase).peekable();
+    let mut positions = Vec::new();
+    for (i, c) in haystack.chars().enumerate() {
+        let Some(&want) = pattern.peek() else {
+            break;
+        };
+        if c.to_lowercase().eq(std::iter::once(want)) {
+            positions.push(i);
+            pattern.next();
+        }
+    }
+    pattern.peek().is_none().then_some(positions)
+}
+
+/// Strip reply/forward prefixes ("Re:", "Fwd:", "Fw:", "Re[2]:") from a subject,
+/// leaving the thread's root subject.
+pub fn strip_subject_prefixes(s: &str) -> String {
+    let mut rest = s.trim();
+    loop {
+        let lower = rest.to_ascii_lowercase();
+        let prefix_len = ["re", "fwd", "fw"].iter().find_map(|p| {
+            let after = lower.strip_prefix(p)?;
+            // Optional reply counter, e.g. "Re[2]:"
+            let after = match after.strip_prefix('[') {
+                Some(counted) => counted
+                    .trim_start_matches(|c: char| c.is_ascii_digit())
+                    .strip_prefix(']')?,
+                None => after,
+            };
+            after.strip_prefix(':').map(|tail| lower.len() - tail.len())
+        });
+        match prefix_len {
+            Some(len) => rest = rest[len..].trim_start(),
+            None => break,
+        }
+    }
+    rest.to_string()
+}
+
+/// Parse a date filter into an inclusive range. Accepts `today`, `yesterday`,
+/// `last week`, `last month`, a single `YYYY-MM-DD`, or `YYYY-MM-DD..YYYY-MM-DD`
+/// (either side may be left open).
+pub fn parse_date_range(input: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let today = chrono::Local::now().date_naive();
+    let input = input.trim().to_lowercase();
+    let parse = |s: &str| NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok();
+    let range = match input.as_str() {
+        "today" => (today, today),
+        "yesterday" => {
+            let day = today.pred_opt()?;
+            (day, day)
+        }
+        "last week" => (today - chrono::Duration::days(7), today),
+        "last month" => (today.checked_sub_months(chrono::Months::new(1))?, today),
+        _ => match input.split_once("..") {
+            Some((from, to)) => {
+                let from = if from.trim().is_empty() { NaiveDate::MIN } else { parse(from)? };
+                let to = if to.trim().is_empty() { NaiveDate::MAX } else { parse(to)? };
+                (from, to)
+            }
+            None => {
+                let day = parse(&input)?;
+                (day, day)
+            }
+        },
+    };
+    (range.0 <= range.1).then_some(range)
+}
+
+/// Frontmatter format of `snoozed_until`.
+pub const SNOOZE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// Parse a snooze time: `tomorrow` (9:00), `YYYY-MM-DD` (9:00),
+/// `YYYY-MM-DD HH:MM` or `YYYY-MM-DDTHH:MM[:SS]`.
+pub fn parse_snooze_time(input: &str) -> Option<NaiveDateTime> {
+    let input = input.trim();
+    let morning = NaiveTime::from_hms_opt(9, 0, 0)?;
+    if input.eq_ignore_ascii_case("tomorrow") {
+        let today = chrono::Local::now().date_naive();
+        return Some(today.succ_opt()?.and_time(morning));
+    }
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(input, fmt).ok())
+        .or_else(|| Some(NaiveDate::parse_from_str(input, "%Y-%m-%d").ok()?.and_time(morning)))
+}
+
+/// Where snoozed inbox emails wait (`<inbox>/.snooze/`).
+pub fn snooze_dir(inbox_dir: &Path) -> PathBuf {
+    inbox_dir.join(".snooze")
+}
+
+/// Scan the snooze directory, returning (paths whose time has passed, still-snoozed count).
+pub fn snoozed_emails(inbox_dir: &Path) -> (Vec<PathBuf>, usize) {
+    let now = chrono::Local::now().naive_local();
+    let mut due = Vec::new();
+    let mut pending = 0;
+    for entry in load_emails(&snooze_dir(inbox_dir)) {
+        match entry.snoozed_until {
+            Some(until) if until > now => pending += 1,
+            // A missing or unreadable time should not hide the email forever
+            _ => due.push(entry.path),
+        }
+    }
+    (due, pending)
+}
+
+/// List draft templates (`.md` files in `<drafts>/templates/`), sorted by name.
+pub fn list_templates(drafts_dir: &Path) -> Vec<PathBuf> {
+    let dir = drafts_dir.join("templates");
+    let mut templates: Vec<PathBuf> = walkdir::WalkDir::new(dir)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_type().is_file()
+                && e.path().extension().is_some_and(|ext| ext == "md")
+        })
+        .map(|e| e.into_path())
+        .collect();
+    templates.sort();
+    templates
+}
+
+/// Human-readable file size ("512 B", "42 KB", "3.1 MB").
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{bytes} B")
+    } else if bytes < 1024 * 1024 {
+        format!("{} KB", bytes / 1024)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+/// Outcome of checking a PGP signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgpStatus {
+    Valid,
+    Invalid,
+    /// Verification is still running on a worker thread.
+    Checking,
+    /// gpg unavailable, missing public key, or malformed signature.
+    Unknown,
+}
+
+/// Verify a detached signature over `body` by shelling out to `gpg --verify`.
+/// Blocks until gpg exits, so call it off the UI thread.
+pub fn verify_pgp_signature(body: &str, sig: &str) -> PgpStatus {
+    let body_path = write_private_temp("txt", body);
+    let sig_path = write_private_temp("asc", sig);
+
+    let status = match (&body_path, &sig_path) {
+        (Ok(body_path), Ok(sig_path)) => std::process::Command::new("gpg")
+            .args(["--batch", "--verify"])
+            .arg(sig_path)
+            .arg(body_path)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .ok(),
+        _ => None,
+    };
+
+    for path in [body_path, sig_path].into_iter().flatten() {
+        let _ = std::fs::remove_file(path);
+    }
+
+    // gpg exits 1 for a bad signature and 2 for other errors (e.g. missing key)
+    match status.and_then(|s| s.code()) {
+        Some(0) => PgpStatus::Valid,
+        Some(1) => PgpStatus::Invalid,
+        _ => PgpStatus::Unknown,
+    }
+}
+
+/// Write `contents` to a new file in the temp dir that only the user can read.
+/// The name is unique per call, and an existing file is never reused.
+fn write_private_temp(ext: &str, contents: &str) -> std::io::Result<PathBuf> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    loop {
+        let name = format!(
+            "beautifulmail-{}-{}-{}.{ext}",
+            std::process::id(),
+            chrono::Local::now().timestamp_nanos_opt().unwrap_or_default(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = std::env::temp_dir().join(name);
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&path);
+        match file {
+            Ok(mut file) => return file.write_all(contents.as_bytes()).map(|_| path),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Whether a frontmatter `status` marks an email as not read yet.
+pub fn is_unread_status(status: &str) -> bool {
+    ["unread", "new", "unseen"].iter().any(|s| status.eq_ignore_ascii_case(s))
+}
+
+/// The `status` frontmatter value of a single email file, found by scanning
+/// the frontmatter lines instead of parsing the whole email.
+pub fn read_status(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let rest = content.strip_prefix("---\n")?;
+    let frontmatter = &rest[..rest.find("\n---")?];
+    frontmatter.lines().find_map(|line| {
+        let value = line.strip_prefix("status:")?.trim();
+        Some(value.trim_matches(|c| c == '"' || c == '\'').to_string())
+    })
+}
+
+/// Whether a line introduces a quoted reply: it matches `pattern` (`*` for
+/// any text), or by default looks like `On ..., ... wrote:`.
+pub fn is_attribution(line: &str, pattern: Option<&str>) -> bool {
+    match pattern {
+        Some(pattern) => wildcard_match(pattern, line),
+        None => line.starts_with("On ") && line.ends_with("wrote:"),
+    }
+}
+
+/// Match `text` against `pattern`, where `*` stands for any run of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    // No `*` at all: the whole text must match
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Attribution line for a reply to `email`, filling `{date}` and `{from}`
+/// in `template`.
+pub fn format_attribution(email: &EmailEntry, template: &str) -> String {
+    let from = if email.from.is_empty() { &email.from_addr } else { &email.from };
+    template.replace("{date}", &email.date_display).replace("{from}", from)
+}
+
+/// Replace the first attribution line of a draft's body with `attribution`.
+/// Returns `None` when the body has no attribution line.
+pub fn replace_attribution(
+    content: &str,
+    attribution: &str,
+    pattern: Option<&str>,
+) -> Option<String> {
+    let body_start = body_start_line(content) - 1;
+    let mut lines: Vec<&str> = content.lines().collect();
+    let index = (body_start..lines.len()).find(|&i| {
+        let line = lines[i].trim_start_matches(['>', ' ']).trim();
+        is_attribution(line, pattern)
+    })?;
+    // Keep any quote prefix the line had
+    let prefix_len = lines[index].len() - lines[index].trim_start_matches(['>', ' ']).len();
+    let replaced = format!("{}{attribution}", &lines[index][..prefix_len]);
+    lines[index] = &replaced;
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
+/// Read the `send_at` schedule of a single email file, if any.
+pub fn read_send_at(path: &Path) -> Option<DateTime<FixedOffset>> {
+    parse_email(path).ok()?.send_at
+}
+
+/// Fields of a `mailto:` URI (RFC 6068), percent-decoded.
+#[derive(Debug, Default, Clone)]
+pub struct Mailto {
+    pub to: String,
+    pub cc: Option<String>,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+}
+
+/// Parse `mailto:a@b.com?subject=Hi&cc=c@d.com&body=...`. Extra `to` query
+/// parameters are appended to the address list.
+pub fn parse_mailto(uri: &str) -> Option<Mailto> {
+    let rest = uri.trim().strip_prefix("mailto:")?;
+    let decode = |s: &str| percent_encoding::percent_decode_str(s).decode_utf8_lossy().to_string();
+    let (to, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let mut mailto = Mailto {
+        to: decode(to),
+        ..Mailto::default()
+    };
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = decode(value);
+        match key.to_ascii_lowercase().as_str() {
+            "to" if mailto.to.is_empty() => mailto.to = value,
+            "to" => mailto.to = format!("{}, {value}", mailto.to),
+            "cc" => mailto.cc = Some(value),
+            "subject" => mailto.subject = Some(value),
+            "body" => mailto.body = Some(value),
+            _ => {}
+        }
+    }
+    Some(mailto)
+}
+
+/// Quote a string as a YAML double-quoted scalar.
+pub fn yaml_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+/// 1-based line where the body starts (just after the closing `---` of the
+/// frontmatter), or 1 when the file has no frontmatter.
+pub fn body_start_line(content: &str) -> usize {
+    let mut lines = content.lines().enumerate();
+    if lines.next().is_some_and(|(_, l)| l.trim_end() == "---") {
+        if let Some((i, _)) = lines.find(|(_, l)| l.trim_end() == "---") {
+            return i + 2;
+        }
+    }
+    1
+}
+
+/// Whether a single email file is starred.
+pub fn read_starred(path: &Path) -> bool {
+    parse_email(path).is_ok_and(|e| e.starred)
+}
+
+/// Attachments listed in a single email file.
+pub fn read_attachments(path: &Path) -> Result<Vec<AttachmentInfo>> {
+    parse_email(path).map(|e| e.attachments)
+}
+
+/// Set a top-level frontmatter key (or remove it with `None`), leaving the rest
+/// of the file untouched. Returns `None` when the file has no frontmatter.
+pub fn set_frontmatter_field(content: &str, key: &str, value: Option<&str>) -> Option<String> {
+    let rest = content.strip_prefix("---\n")?;
+    let end = rest.find("\n---")?;
+    let (frontmatter, tail) = rest.split_at(end);
+    let prefix = format!("{key}:");
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut found = false;
+    let mut skipping = false;
+    for line in frontmatter.lines() {
+        // Indented lines and list items continue the previous key's value
+        if skipping && (line.starts_with(char::is_whitespace) || line.starts_with("- ")) {
+            continue;
+        }
+        skipping = line.starts_with(&prefix);
+        if !skipping {
+            lines.push(line.to_string());
+        } else if !found {
+            found = true;
+            if let Some(value) = value {
+                lines.push(format!("{key}: {value}"));
+            }
+        }
+    }
+    if let Some(value) = value.filter(|_| !found) {
+        lines.push(format!("{key}: {value}"));
+    }
+    Some(format!("---\n{}{tail}", lines.join("\n")))
+}
+
+/// Scan a drafts directory for scheduled drafts, returning (due paths, still-pending count).
+/// Drafts with a `send_error` from an earlier attempt are left alone.
+pub fn scheduled_drafts(dir: &Path) -> (Vec<PathBuf>, usize) {
+    let now = chrono::Local::now();
+    let mut due = Vec::new();
+    let mut pending = 0;
+    for entry in load_emails(dir).into_iter().filter(|e| e.send_error.is_none()) {
+        match entry.send_at {
+            Some(at) if at <= now => due.push(entry.path),
+            Some(_) => pending += 1,
+            None => {}
+        }
+    }
+    (due, pending)
+}
@@ -5,11 +5,9 @@ use crossterm::event::{self, Event, KeyEventKind};
 
 use crate::app::Message;
 
-const TICK_RATE: Duration = Duration::from_millis(250);
-
-/// Poll for terminal events and convert them to app messages.
-pub fn poll_event() -> Result<Option<Message>> {
-    if event::poll(TICK_RATE)? {
+/// Poll for terminal events for up to `tick_rate` and convert them to app messages.
+pub fn poll_event(tick_rate: Duration) -> Result<Option<Message>> {
+    if event::poll(tick_rate)? {
         match event::read()? {
             Event::Key(key) if key.kind == KeyEventKind::Press => {
                 return Ok(Some(Message::Key(key)));
@@ -17,6 +15,9 @@ pub fn poll_event() -> Result<Option<Message>> {
             Event::Resize(w, h) => {
                 return Ok(Some(Message::Resize(w, h)));
             }
+            Event::Mouse(mouse) => {
+                return Ok(Some(Message::Mouse(mouse)));
+            }
             _ => {}
         }
     }
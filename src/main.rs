@@ -1,55 +1,177 @@
 mod app;
+mod args;
 mod cli;
+mod config;
 mod email;
 mod event;
+mod markdown;
+mod notify;
+mod setup;
+mod template;
 mod theme;
 mod ui;
 
+use std::collections::HashSet;
 use std::io::{self, stdout};
 use std::panic;
-use std::sync::mpsc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, SystemTime};
 
 use anyhow::Result;
 use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 
-use app::{Action, App, Mailbox};
+use app::{Action, App, Mailbox, UndoKind, WatchMode};
+use config::WatcherSettings;
+use email::PgpStatus;
+use template::TemplateVars;
 
 enum WatchEvent {
     Changed,
     Error(String),
+    /// The watch succeeded again after one or more failures.
+    Reconnected,
+    /// `email watch` is unavailable; the inbox directory is polled instead.
+    FilesFallback,
+    /// New files appeared in the inbox directory (no fetch needed).
+    FilesChanged,
 }
 
+/// Results reported by background worker threads.
+enum BackgroundTask {
+    /// Scheduled drafts whose `send_at` has passed, and how many are still waiting.
+    ScheduledSend { due: Vec<PathBuf>, pending: usize },
+    /// The worker sending due drafts finished, with the failure messages.
+    ScheduledSent { sent: usize, failures: Vec<String> },
+    /// New spinner label for the running operation (e.g. export progress).
+    Progress(String),
+    /// A fetch/sync/reconcile finished; `name` is used in the status message.
+    OperationDone { name: &'static str, result: Result<String> },
+    /// A PDF export finished, with the written file.
+    PdfExported(Result<PathBuf>),
+    /// gpg finished checking the signature of the email at `path`.
+    PgpVerified { path: PathBuf, status: PgpStatus },
+}
+
+/// Watch retry delay after the first failure; doubles up to `WATCH_MAX_BACKOFF_SECS`.
+const WATCH_INITIAL_BACKOFF_SECS: u64 = 5;
+const WATCH_MAX_BACKOFF_SECS: u64 = 300;
+/// Consecutive watch failures before the watcher thread gives up.
+const WATCH_MAX_FAILURES: u32 = 5;
+/// How often the inbox directory is rescanned when `email watch` is unavailable.
+const FILES_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often the scheduler re-checks drafts for a due `send_at`.
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60);
+
 fn main() -> Result<()> {
+    // `beautifulmail mailto:...` (e.g. as the system mailto handler) composes right away
+    let cli = args::Cli::parse();
+    if let Some(path) = cli.config {
+        config::Config::set_path(path);
+    }
+
     install_panic_hook();
     let mut terminal = init_terminal()?;
-    let result = run(&mut terminal);
+    let result = run(&mut terminal, cli.mailto);
     restore_terminal()?;
     result
 }
 
-fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+fn run(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    mailto: Option<String>,
+) -> Result<()> {
     let mut app = App::new();
 
+    // Nothing configured at all: offer first-run setup, then start with its directories
+    if app.needs_setup() && setup::run_setup_wizard(terminal)? {
+        app = App::new();
+    }
+
     // Capture initial terminal size
     let size = terminal.size()?;
     app.terminal_width = size.width;
     app.terminal_height = size.height;
 
+    wake_snoozed(&mut app);
+
+    let (task_tx, task_rx) = mpsc::channel::<BackgroundTask>();
+
+    // SIGHUP asks for the config file to be re-read
+    let reload_requested = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(signal_hook::consts::SIGHUP, Arc::clone(&reload_requested))?;
+    // SIGCONT after a stop (Ctrl-z or an outside SIGSTOP) puts the terminal back in raw mode
+    let continued = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(signal_hook::consts::SIGCONT, Arc::clone(&continued))?;
+    // SIGTERM quits through the normal exit path, so the terminal is restored. A
+    // second one while the loop is blocked (editor, send) terminates right away.
+    let terminate_requested = Arc::new(AtomicBool::new(false));
+    let sigterm = signal_hook::consts::SIGTERM;
+    signal_hook::flag::register_conditional_shutdown(
+        sigterm,
+        128 + sigterm,
+        Arc::clone(&terminate_requested),
+    )?;
+    signal_hook::flag::register(sigterm, Arc::clone(&terminate_requested))?;
+
+    if let Some(mailto) = mailto {
+        handle_action(&mut app, terminal, &task_tx, Action::NewDraftMailto(mailto))?;
+    }
+
     // Spawn background mail watcher thread
     let (watch_tx, watch_rx) = mpsc::channel::<WatchEvent>();
-    app.watcher_active = true;
+    app.set_watcher_active(true);
+    let inbox_dir = Arc::clone(&app.watch_inbox);
+    let watcher = app.config.watcher();
     std::thread::spawn(move || {
-        watcher_loop(watch_tx);
+        watcher_loop(watch_tx, inbox_dir, watcher);
+    });
+    // Inbox count when the watcher last reported a change, pending the fetch it triggers
+    let mut inbox_before_fetch: Option<usize> = None;
+    // Set while due drafts are being sent, so a tick does not send them twice
+    let mut scheduled_sending = false;
+
+    // Spawn the scheduled-send checker for drafts with a `send_at` time; its
+    // tick also wakes snoozed emails, so it runs without a drafts dir too
+    let drafts_dir = Arc::clone(&app.schedule_drafts);
+    let scheduler_tx = task_tx.clone();
+    std::thread::spawn(move || {
+        scheduler_loop(&drafts_dir, scheduler_tx);
     });
 
     while app.running {
+        if terminate_requested.load(Ordering::Relaxed) {
+            break;
+        }
+        if continued.swap(false, Ordering::Relaxed) {
+            resume_terminal(terminal)?;
+        }
+
+        // Verify the selected email's PGP signature once, in the background
+        if let Some((path, body, sig)) = app.pgp_pending() {
+            app.pgp_status.insert(path.clone(), PgpStatus::Checking);
+            let tx = task_tx.clone();
+            std::thread::spawn(move || {
+                let status = email::verify_pgp_signature(&body, &sig);
+                let _ = tx.send(BackgroundTask::PgpVerified { path, status });
+            });
+        }
+
         terminal.draw(|frame| ui::view(&app, frame))?;
 
-        if let Some(msg) = event::poll_event()? {
+        // Replay one macro key per iteration so its actions run in between
+        let next = match app.next_macro_key().or_else(|| app.expired_count_jump()) {
+            Some(key) => Some(app::Message::Key(key)),
+            None => event::poll_event(app.config.tick_rate())?,
+        };
+        if let Some(msg) = next {
             let mut current_msg = Some(msg);
             while let Some(m) = current_msg {
                 current_msg = app.update(m);
@@ -59,9 +181,16 @@ fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
             app.tick_status();
         }
 
+        if reload_requested.swap(false, Ordering::Relaxed) {
+            app.reload_config();
+        }
+
         // Check background watcher
         match watch_rx.try_recv() {
             Ok(WatchEvent::Changed) => {
+                wake_snoozed(&mut app);
+                // Remember the inbox size so the fetch that follows can report new mail
+                inbox_before_fetch.get_or_insert(app.mailbox_counts[Mailbox::Inbox.index()]);
                 let mut current_msg = Some(app::Message::MailboxChanged);
                 while let Some(m) = current_msg {
                     current_msg = app.update(m);
@@ -69,17 +198,113 @@ fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
             }
             Ok(WatchEvent::Error(e)) => {
                 app.set_status(format!("Watch: {e}"));
-                app.watcher_active = false;
+                app.set_watcher_active(false);
+            }
+            Ok(WatchEvent::Reconnected) => {
+                app.set_status("Watcher reconnected".to_string());
+                app.set_watcher_active(true);
+            }
+            Ok(WatchEvent::FilesFallback) => {
+                app.watch_mode = WatchMode::Files;
+                app.set_status("email watch unavailable, watching the inbox folder".to_string());
+            }
+            Ok(WatchEvent::FilesChanged) => {
+                wake_snoozed(&mut app);
+                let before = app.mailbox_counts[Mailbox::Inbox.index()];
+                app.invalidate_all_caches();
+                app.reload_current_mailbox();
+                notify_new_mail(&mut app, before);
             }
             Err(mpsc::TryRecvError::Empty) => {}
             Err(mpsc::TryRecvError::Disconnected) => {
-                app.watcher_active = false;
+                app.set_watcher_active(false);
+            }
+        }
+
+        // Drain everything queued so progress updates do not lag behind
+        while let Ok(task) = task_rx.try_recv() {
+            match task {
+                // Send drafts whose schedule has come due
+                BackgroundTask::ScheduledSend { due, pending } => {
+                    app.scheduled_pending = pending;
+                    if !scheduled_sending && !due.is_empty() {
+                        scheduled_sending = true;
+                        send_scheduled(&task_tx, app.mailbox_dirs.clone(), due);
+                    }
+                    // Snoozes come due without any new mail to trigger the watcher
+                    wake_snoozed(&mut app);
+                }
+                BackgroundTask::Progress(label) => {
+                    app.operation_label = Some(label);
+                }
+                BackgroundTask::OperationDone { name, result } => {
+                    app.operation_label = None;
+                    match result {
+                        Ok(msg) => {
+                            app.set_status(if msg.is_empty() {
+                                format!("{name} complete")
+                            } else {
+                                msg
+                            });
+                            app.invalidate_all_caches();
+                            app.reload_current_mailbox();
+                            if let Some(before) = inbox_before_fetch.take() {
+                                notify_new_mail(&mut app, before);
+                            }
+                        }
+                        Err(e) => {
+                            // A partial run (e.g. mark read) may still have changed files
+                            app.invalidate_all_caches();
+                            app.reload_current_mailbox();
+                            app.set_status(format!("{name} failed: {e:#}"));
+                        }
+                    }
+                }
+                BackgroundTask::ScheduledSent { sent, failures } => {
+                    scheduled_sending = false;
+                    if failures.is_empty() {
+                        app.set_status(format!("Sent {sent} scheduled email(s)"));
+                    } else {
+                        let failed = failures.join("; ");
+                        app.set_status(format!("Scheduled send: {sent} sent, failed: {failed}"));
+                    }
+                    app.invalidate_all_caches();
+                    app.reload_current_mailbox();
+                }
+                BackgroundTask::PgpVerified { path, status } => {
+                    app.pgp_status.insert(path, status);
+                }
+                BackgroundTask::PdfExported(result) => {
+                    app.operation_label = None;
+                    match result {
+                        Ok(pdf) => {
+                            let shown = home_relative(&pdf);
+                            app.set_status_for(format!("Exported to {shown} -- Enter to open"), 8);
+                            app.exported_pdf = Some(pdf);
+                        }
+                        Err(e) => app.set_status(format!("PDF export failed: {e}")),
+                    }
+                }
             }
         }
 
         // Process pending action (side-effects outside the pure update)
         if let Some(action) = app.pending_action.take() {
-            handle_action(&mut app, terminal, action)?;
+            handle_action(&mut app, terminal, &task_tx, action)?;
+        }
+
+        // Deleted-email backups are only kept for a short undo window
+        for backup in app.expire_undo() {
+            let _ = std::fs::remove_file(backup);
+        }
+    }
+
+    let _ = app.save_search_history();
+
+    // Undo history does not survive a restart
+    for entry in app.undo_stack.drain(..) {
+        if let UndoKind::Delete { backup, .. } = entry.kind {
+            let _ = std::fs::remove_file(backup);
         }
     }
 
@@ -89,28 +314,36 @@ fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
 fn handle_action(
     app: &mut App,
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    tasks: &mpsc::Sender<BackgroundTask>,
     action: Action,
 ) -> Result<()> {
     match action {
-        Action::EditCurrent => {
+        Action::EditCurrent => edit_selected(app, terminal, None)?,
+
+        Action::EditBody => {
             if let Some(path) = app.selected_email_path() {
-                suspend_terminal(terminal)?;
-                let result = cli::edit_file(&path);
-                resume_terminal(terminal)?;
-                match result {
-                    Ok(()) => app.set_status("Returned from editor".to_string()),
-                    Err(e) => app.set_status(format!("Edit failed: {e}")),
-                }
-                app.reload_current_mailbox();
+                let line = std::fs::read_to_string(&path)
+                    .map(|content| email::body_start_line(&content))
+                    .unwrap_or(1);
+                edit_selected(app, terminal, Some(line.min(u16::MAX as usize) as u16))?;
             }
         }
 
         Action::Reply(reply_all) => {
             if let Some(path) = app.selected_email_path() {
-                match cli::reply(&path, reply_all) {
+                let reply_to = app.selected_email().and_then(|e| e.reply_to.clone());
+                match cli::reply(&app.mailbox_dirs, &path, reply_all, reply_to.as_deref()) {
                     Ok(draft_path) => {
+                        let template = app.config.attribution_template.as_deref();
+                        if let (Some(template), Some(original)) = (template, app.selected_email()) {
+                            let line = email::format_attribution(original, template);
+                            let pattern = app.config.attribution_pattern.as_deref();
+                            if let Err(e) = cli::set_attribution(&draft_path, &line, pattern) {
+                                app.set_status(format!("Attribution not updated: {e}"));
+                            }
+                        }
                         suspend_terminal(terminal)?;
-                        let edit_result = cli::edit_file(&draft_path);
+                        let edit_result = cli::edit_file_interactive(&draft_path);
                         resume_terminal(terminal)?;
                         match edit_result {
                             Ok(()) => app.set_status("Reply draft ready".to_string()),
@@ -124,9 +357,28 @@ fn handle_action(
             }
         }
 
+        Action::Forward => {
+            if let Some(path) = app.selected_email_path() {
+                match cli::forward(&app.mailbox_dirs, &path) {
+                    Ok(draft_path) => {
+                        suspend_terminal(terminal)?;
+                        let edit_result = cli::edit_file_interactive(&draft_path);
+                        resume_terminal(terminal)?;
+                        match edit_result {
+                            Ok(()) => app.set_status("Forward draft ready".to_string()),
+                            Err(e) => app.set_status(format!("Editor failed: {e}")),
+                        }
+                        app.invalidate_cache(Mailbox::Drafts);
+                    }
+                    Err(e) => app.set_status(format!("Forward failed: {e}")),
+                }
+                app.reload_current_mailbox();
+            }
+        }
+
         Action::Send => {
             if let Some(path) = app.selected_email_path() {
-                match cli::send(&path) {
+                match cli::send(&app.mailbox_dirs, &path) {
                     Ok(msg) => {
                         app.set_status(if msg.is_empty() {
                             "Email sent".to_string()
@@ -144,7 +396,7 @@ fn handle_action(
         Action::SendApproved => {
             if let Some(dir) = &app.mailbox_dirs[app.active_mailbox.index()] {
                 let dir = dir.clone();
-                match cli::send_approved(&dir) {
+                match cli::send_approved(&app.mailbox_dirs, &dir) {
                     Ok(msg) => {
                         app.set_status(if msg.is_empty() {
                             "Approved emails sent".to_string()
@@ -159,19 +411,52 @@ fn handle_action(
             }
         }
 
-        Action::NewDraft => {
+        Action::NewDraft(to) => {
             let name = chrono::Local::now().format("draft-%Y%m%d-%H%M%S").to_string();
-            match cli::new_draft(&name) {
-                Ok(msg) => {
+            match cli::new_draft(&app.mailbox_dirs, &name) {
+                Ok(mut msg) => {
                     // Try to open the new draft in the editor
+                    let mut changed = true;
                     if let Some(drafts_dir) = &app.mailbox_dirs[Mailbox::Drafts.index()] {
                         let draft_path = drafts_dir.join(format!("{name}.md"));
                         if draft_path.exists() {
+                            if !to.is_empty() {
+                                if let Err(e) = cli::set_recipients(&draft_path, &to) {
+                                    msg = format!("{msg} (could not set To: {e})");
+                                }
+                            }
+                            let before = cli::modified_time(&draft_path);
                             suspend_terminal(terminal)?;
-                            let _ = cli::edit_file(&draft_path);
+                            let _ = cli::edit_file_interactive(&draft_path);
                             resume_terminal(terminal)?;
+                            changed = file_changed(&draft_path, before);
                         }
                     }
+                    app.set_status(if changed {
+                        msg
+                    } else {
+                        format!("{msg} (no changes made)")
+                    });
+                    // The new file exists even if unedited, so always reload
+                    app.invalidate_cache(Mailbox::Drafts);
+                    app.reload_current_mailbox();
+                }
+                Err(e) => app.set_status(format!("New draft failed: {e}")),
+            }
+        }
+
+        Action::NewDraftMailto(mailto) => {
+            let name = chrono::Local::now().format("draft-%Y%m%d-%H%M%S").to_string();
+            let Some(drafts_dir) = app.mailbox_dirs[Mailbox::Drafts.index()].clone() else {
+                app.set_status("New draft failed: DRAFTS_DIR is not set".to_string());
+                return Ok(());
+            };
+            match cli::new_draft_mailto(&app.mailbox_dirs, &name, &mailto, &drafts_dir) {
+                Ok(msg) => {
+                    let draft_path = drafts_dir.join(format!("{name}.md"));
+                    suspend_terminal(terminal)?;
+                    let _ = cli::edit_file_interactive(&draft_path);
+                    resume_terminal(terminal)?;
                     app.set_status(msg);
                     app.invalidate_cache(Mailbox::Drafts);
                     app.reload_current_mailbox();
@@ -182,7 +467,7 @@ fn handle_action(
 
         Action::Approve => {
             if let Some(path) = app.selected_email_path() {
-                match cli::approve(&path) {
+                match cli::approve(&app.mailbox_dirs, &path) {
                     Ok(msg) => {
                         app.set_status(msg);
                         app.reload_current_mailbox();
@@ -194,98 +479,399 @@ fn handle_action(
 
         Action::Archive => {
             if let Some(path) = app.selected_email_path() {
-                match cli::archive(&path) {
+                let origin = app.selected_origin_mailbox();
+                let source = app.selected_mailbox_label();
+                let archive_dir = app.mailbox_dirs[Mailbox::Archive.index()].clone();
+                match cli::archive(&app.mailbox_dirs, &path, archive_dir.as_deref()) {
+                    Ok(result) => {
+                        // "Archive/2025/01/": the destination folder under the archive's parent
+                        let dest_folder = result.dest_path.as_deref().and_then(|dest| {
+                            let root = archive_dir.as_deref()?.parent()?;
+                            Some(dest.parent()?.strip_prefix(root).ok()?.display().to_string())
+                        });
+                        let msg = match &dest_folder {
+                            Some(folder) => format!("Archived from {source} to {folder}/"),
+                            None if result.message.is_empty() => "Email archived".to_string(),
+                            None => result.message,
+                        };
+                        match result.dest_path {
+                            Some(to) => {
+                                let from = result.source_path;
+                                push_undo(app, UndoKind::Archive { from, to });
+                                app.set_status_for(format!("{msg} -- press Alt-z to undo"), 5);
+                            }
+                            None => app.set_status(msg),
+                        }
+                        // During global search the email may come from another mailbox
+                        app.invalidate_cache(origin);
+                        app.invalidate_cache(Mailbox::Archive);
+                        app.reload_current_mailbox();
+                    }
+                    Err(e) => app.set_status(format!("Archive failed: {e}")),
+                }
+            }
+        }
+
+        Action::ToggleStar => {
+            if let Some(path) = app.selected_email_path() {
+                match cli::toggle_star(&path) {
+                    Ok(()) => {
+                        app.set_status(if email::read_starred(&path) {
+                            "Starred".to_string()
+                        } else {
+                            "Unstarred".to_string()
+                        });
+                        app.reload_current_mailbox();
+                        app.select_path(&path);
+                    }
+                    Err(e) => app.set_status(format!("Star failed: {e}")),
+                }
+            }
+        }
+
+        Action::MarkSpam => {
+            if let Some(path) = app.selected_email_path() {
+                match cli::mark_spam(&app.mailbox_dirs, &path) {
                     Ok(msg) => {
                         app.set_status(if msg.is_empty() {
-                            "Email archived".to_string()
+                            "Marked as spam".to_string()
                         } else {
                             msg
                         });
-                        app.invalidate_cache(Mailbox::Archive);
+                        app.invalidate_cache(Mailbox::Spam);
                         app.reload_current_mailbox();
                     }
-                    Err(e) => app.set_status(format!("Archive failed: {e}")),
+                    Err(e) => app.set_status(format!("Mark spam failed: {e}")),
                 }
             }
         }
 
-        Action::Delete => {
+        Action::MoveTo(mailbox) => {
+            let dest_dir = app.mailbox_dirs[mailbox.index()].clone();
+            if let Some((path, dest_dir)) = app.selected_email_path().zip(dest_dir) {
+                let source = app.selected_mailbox_label();
+                match cli::move_email(&path, &dest_dir) {
+                    Ok(_) => {
+                        if let Some(name) = path.file_name() {
+                            let to = dest_dir.join(name);
+                            push_undo(app, UndoKind::Move { from: path, to });
+                        }
+                        let label = app.mailbox_label(mailbox);
+                        let msg = format!("Moved from {source} to {label} -- press Alt-z to undo");
+                        app.set_status_for(msg, 5);
+                        app.invalidate_all_caches();
+                        app.reload_current_mailbox();
+                    }
+                    Err(e) => app.set_status(format!("Move failed: {e}")),
+                }
+            }
+        }
+
+        Action::PermanentDelete => {
             if let Some(path) = app.selected_email_path() {
-                match cli::delete(&path) {
+                let backup = cli::backup_for_undo(&path).ok();
+                match cli::delete(&app.mailbox_dirs, &path) {
                     Ok(msg) => {
-                        app.set_status(if msg.is_empty() {
+                        let msg = if msg.is_empty() {
                             "Email deleted".to_string()
                         } else {
                             msg
-                        });
+                        };
+                        match backup {
+                            Some(backup) => {
+                                push_undo(app, UndoKind::Delete { backup, original: path });
+                                app.set_status_for(format!("{msg} -- press Alt-z to undo"), 5);
+                            }
+                            None => app.set_status(msg),
+                        }
                         app.reload_current_mailbox();
                     }
-                    Err(e) => app.set_status(format!("Delete failed: {e}")),
+                    Err(e) => {
+                        if let Some(backup) = backup {
+                            let _ = std::fs::remove_file(backup);
+                        }
+                        app.set_status(format!("Delete failed: {e}"));
+                    }
                 }
             }
         }
 
-        Action::CopyPath => {
+        Action::Delete => {
+            let Some(trash_dir) = app.mailbox_dirs[Mailbox::Trash.index()].clone() else {
+                app.set_status("TRASH_DIR not configured".to_string());
+                return Ok(());
+            };
             if let Some(path) = app.selected_email_path() {
-                match cli::copy_to_clipboard(&path.display().to_string()) {
-                    Ok(()) => app.set_status("Path copied to clipboard".to_string()),
-                    Err(e) => app.set_status(format!("Copy failed: {e}")),
+                let origin = app.selected_origin_mailbox();
+                match cli::move_to_trash(&path, &trash_dir) {
+                    Ok(dest) => {
+                        push_undo(app, UndoKind::Move { from: path, to: dest });
+                        app.set_status_for("Moved to trash -- press Alt-z to undo".to_string(), 5);
+                        app.invalidate_cache(origin);
+                        app.invalidate_cache(Mailbox::Trash);
+                        app.reload_current_mailbox();
+                    }
+                    Err(e) => app.set_status(format!("Trash failed: {e}")),
+                }
+            }
+        }
+
+        Action::EmptyTrash => {
+            let Some(trash_dir) = app.mailbox_dirs[Mailbox::Trash.index()].clone() else {
+                app.set_status("TRASH_DIR not configured".to_string());
+                return Ok(());
+            };
+            let mut removed = 0;
+            let mut failure = None;
+            for e in email::load_emails(&trash_dir) {
+                match cli::delete(&app.mailbox_dirs, &e.path) {
+                    Ok(_) => removed += 1,
+                    Err(e) => failure = Some(e),
                 }
             }
+            match failure {
+                Some(e) => app.set_status(format!("Deleted {removed} email(s); failed: {e}")),
+                None => app.set_status(format!("Trash emptied ({removed} deleted)")),
+            }
+            app.reload_current_mailbox();
         }
 
-        Action::Fetch => {
-            app.set_status("Fetching...".to_string());
-            terminal.draw(|frame| ui::view(app, frame))?;
+        Action::RemoveDuplicates(paths) => {
+            let mut removed = 0;
+            let mut failure = None;
+            for path in paths {
+                let backup = cli::backup_for_undo(&path).ok();
+                match cli::delete(&app.mailbox_dirs, &path) {
+                    Ok(_) => {
+                        removed += 1;
+                        if let Some(backup) = backup {
+                            push_undo(app, UndoKind::Delete { backup, original: path });
+                        }
+                    }
+                    Err(e) => {
+                        if let Some(backup) = backup {
+                            let _ = std::fs::remove_file(backup);
+                        }
+                        failure = Some(e);
+                    }
+                }
+            }
+            match failure {
+                Some(e) => app.set_status(format!("Removed {removed} duplicate(s); failed: {e}")),
+                None => app.set_status(format!("Removed {removed} duplicate(s)")),
+            }
+            app.reload_current_mailbox();
+        }
 
-            match cli::fetch() {
-                Ok(msg) => {
-                    app.set_status(if msg.is_empty() {
-                        "Fetch complete".to_string()
-                    } else {
-                        msg
-                    });
-                    app.invalidate_all_caches();
-                    app.reload_current_mailbox();
+        Action::Snooze(until) => {
+            let inbox = app.mailbox_dirs[Mailbox::Inbox.index()].clone();
+            if let (Some(path), Some(inbox)) = (app.selected_email_path(), inbox) {
+                match cli::snooze(&path, until, &inbox) {
+                    Ok(dest) => {
+                        push_undo(app, UndoKind::Move { from: path, to: dest });
+                        let when = until.format("%Y-%m-%d %H:%M");
+                        app.set_status_for(format!("Snoozed until {when} -- Alt-z to undo"), 5);
+                        app.reload_current_mailbox();
+                    }
+                    Err(e) => app.set_status(format!("Snooze failed: {e}")),
+                }
+            }
+        }
+
+        Action::AttachFile(file) => {
+            if let Some(path) = app.selected_email_path() {
+                match cli::attach(&path, &file) {
+                    Ok(()) => {
+                        let name = file.file_name().unwrap_or_default().to_string_lossy();
+                        app.set_status(format!("Attached {name}"));
+                        app.reload_current_mailbox();
+                    }
+                    Err(e) => app.set_status(format!("Attach failed: {e}")),
                 }
-                Err(e) => app.set_status(format!("Fetch failed: {e}")),
             }
         }
 
-        Action::Sync => {
-            app.set_status("Syncing...".to_string());
-            // Force a draw so the user sees the "Syncing..." message
-            terminal.draw(|frame| ui::view(app, frame))?;
+        Action::ExportMbox(paths) => {
+            if app.operation_label.is_some() {
+                app.set_status("Another operation is still running".to_string());
+                return Ok(());
+            }
+            let Some(home) = dirs::home_dir() else {
+                app.set_status("Export failed: no home directory".to_string());
+                return Ok(());
+            };
+            let date = chrono::Local::now().format("%Y%m%d");
+            let output = home.join(format!("beautifulmail-export-{date}.mbox"));
+            let total = paths.len();
+            app.operation_label = Some(format!("Exporting 0/{total}..."));
+            let tx = tasks.clone();
+            std::thread::spawn(move || {
+                let result = cli::export_mbox(&paths, &output, |done| {
+                    let _ = tx.send(BackgroundTask::Progress(format!(
+                        "Exporting {done}/{total}..."
+                    )));
+                })
+                .map(|n| format!("Exported {n} email(s) to {}", output.display()));
+                let _ = tx.send(BackgroundTask::OperationDone { name: "Export", result });
+            });
+        }
 
-            match cli::sync() {
-                Ok(msg) => {
-                    app.set_status(if msg.is_empty() {
-                        "Sync complete".to_string()
-                    } else {
-                        msg
+        Action::MarkAllRead(paths) => {
+            if app.operation_label.is_some() {
+                app.set_status("Another operation is still running".to_string());
+                return Ok(());
+            }
+            let total = paths.len();
+            app.operation_label = Some(format!("Marking {total} emails as read..."));
+            let tx = tasks.clone();
+            std::thread::spawn(move || {
+                let mut result = Ok(format!("Marked {total} email(s) as read"));
+                for (i, path) in paths.iter().enumerate() {
+                    if let Err(e) = cli::mark_read(path) {
+                        result = Err(e.context(format!("marked {i} of {total}")));
+                        break;
+                    }
+                    let _ = tx.send(BackgroundTask::Progress(format!(
+                        "Marking {total} emails as read... {}/{total}",
+                        i + 1
+                    )));
+                }
+                let _ = tx.send(BackgroundTask::OperationDone { name: "Mark read", result });
+            });
+        }
+
+        Action::DiffFromTemplate => {
+            let drafts_dir = app.mailbox_dirs[Mailbox::Drafts.index()].clone();
+            let Some((email, drafts_dir)) = app.selected_email().zip(drafts_dir) else {
+                return Ok(());
+            };
+            let Some(name) = email.template.clone() else {
+                return Ok(());
+            };
+            // The name comes from the draft's frontmatter; keep it inside templates/
+            if name.contains(['/', '\\']) || name.contains("..") {
+                app.set_status(format!("Diff failed: invalid template name {name:?}"));
+                return Ok(());
+            }
+            let template_path = drafts_dir.join("templates").join(format!("{name}.md"));
+            let read = |path: &Path| {
+                std::fs::read_to_string(path)
+                    .map_err(|e| format!("cannot read {}: {e}", path.display()))
+            };
+            match read(&template_path).and_then(|t| Ok((t, read(&email.path)?))) {
+                Ok((template, draft)) => {
+                    // Compare with the template as a new draft would start out:
+                    // variables filled in, and without the added `template` key
+                    let vars = TemplateVars::now();
+                    let (template, _) = template::render(&template, &vars.as_map());
+                    let draft = email::set_frontmatter_field(&draft, "template", None)
+                        .unwrap_or(draft);
+                    app.diff_view = Some(app::DiffView {
+                        template: name,
+                        lines: template::diff_lines(&template, &draft),
+                        scroll: 0,
                     });
+                }
+                Err(e) => app.set_status(format!("Diff failed: {e}")),
+            }
+        }
+
+        Action::ExportPdf(path) => {
+            if app.operation_label.is_some() {
+                app.set_status("Another operation is still running".to_string());
+                return Ok(());
+            }
+            app.operation_label = Some("Exporting PDF...".to_string());
+            let tx = tasks.clone();
+            std::thread::spawn(move || {
+                let _ = tx.send(BackgroundTask::PdfExported(cli::export_pdf(&path)));
+            });
+        }
+
+        Action::Suspend => suspend_to_background(terminal)?,
+        Action::Undo => match app.undo_stack.pop() {
+            Some(entry) => match cli::undo(&entry) {
+                Ok(()) => {
+                    app.set_status(match entry.kind {
+                        UndoKind::Archive { .. } => "Archive undone (local copy restored)",
+                        UndoKind::Delete { .. } => "Delete undone (local copy restored)",
+                        UndoKind::Move { .. } => "Move undone",
+                    }
+                    .to_string());
                     app.invalidate_all_caches();
                     app.reload_current_mailbox();
                 }
-                Err(e) => app.set_status(format!("Sync failed: {e}")),
+                Err(e) => app.set_status(format!("Undo failed: {e}")),
+            },
+            None => app.set_status("Nothing to undo".to_string()),
+        },
+
+        Action::CopyPath => {
+            if let Some(path) = app.selected_email_path() {
+                match cli::copy_to_clipboard(&path.display().to_string()) {
+                    Ok(()) => app.set_status("Path copied to clipboard".to_string()),
+                    Err(e) => app.set_status(format!("Copy failed: {e}")),
+                }
             }
         }
 
+        Action::CopyText(text) => match cli::copy_to_clipboard(&text) {
+            Ok(()) => {
+                let lines = text.lines().count();
+                app.set_status(format!("Copied {lines} line(s) to clipboard"));
+            }
+            Err(e) => app.set_status(format!("Copy failed: {e}")),
+        },
+
+        Action::CopyAddress(addr) => match cli::copy_to_clipboard(&addr) {
+            Ok(()) => app.set_status(format!("Copied {addr}")),
+            Err(e) => app.set_status(format!("Copy failed: {e}")),
+        },
+
+        Action::Fetch => spawn_operation(app, tasks, "Fetch", "Fetching...", cli::fetch),
+        Action::Sync => spawn_operation(app, tasks, "Sync", "Syncing...", cli::sync),
         Action::Reconcile => {
-            app.set_status("Reconciling...".to_string());
-            terminal.draw(|frame| ui::view(app, frame))?;
+            spawn_operation(app, tasks, "Reconcile", "Reconciling...", cli::sync_reconcile)
+        }
 
-            match cli::sync_reconcile() {
+        Action::NewFromTemplate(template) => {
+            let Some(drafts_dir) = app.mailbox_dirs[Mailbox::Drafts.index()].clone() else {
+                app.set_status("Drafts directory not configured".to_string());
+                return Ok(());
+            };
+            let template_path = drafts_dir.join("templates").join(format!("{template}.md"));
+            let name = chrono::Local::now().format("draft-%Y%m%d-%H%M%S").to_string();
+            let vars = TemplateVars::now();
+            match cli::new_draft_from_template(&name, &template_path, &vars) {
                 Ok(msg) => {
-                    app.set_status(if msg.is_empty() {
-                        "Reconcile complete".to_string()
-                    } else {
-                        msg
-                    });
-                    app.invalidate_all_caches();
+                    suspend_terminal(terminal)?;
+                    let _ = cli::edit_file_interactive(&drafts_dir.join(format!("{name}.md")));
+                    resume_terminal(terminal)?;
+                    app.set_status(msg);
+                    app.invalidate_cache(Mailbox::Drafts);
                     app.reload_current_mailbox();
                 }
-                Err(e) => app.set_status(format!("Reconcile failed: {e}")),
+                Err(e) => app.set_status(format!("New from template failed: {e}")),
+            }
+        }
+
+        Action::OpenAttachment(path, mime) => match cli::open_attachment(&path, &mime) {
+            Ok(()) => app.set_status(format!("Opened {}", path.display())),
+            Err(e) => app.set_status(format!("Open failed: {e}")),
+        },
+
+        Action::OpenUrl(url) => match cli::open_url(&url) {
+            Ok(()) => app.set_status(format!("Opened {url}")),
+            Err(e) => app.set_status(format!("Open failed: {e}")),
+        },
+
+        Action::OpenDir => {
+            if let Some(path) = app.selected_email_path() {
+                let dir = path.parent().unwrap_or(&path).display().to_string();
+                match cli::open_dir(&path) {
+                    Ok(()) => app.set_status(format!("Opened {dir}")),
+                    Err(e) => app.set_status_for(format!("{e}: {dir}"), 10),
+                }
             }
         }
     }
@@ -293,16 +879,149 @@ fn handle_action(
     Ok(())
 }
 
+/// Move snoozed emails whose time has passed back into the inbox.
+fn wake_snoozed(app: &mut App) {
+    let Some(inbox) = app.mailbox_dirs[Mailbox::Inbox.index()].clone() else {
+        return;
+    };
+    let (due, _) = email::snoozed_emails(&inbox);
+    if due.is_empty() {
+        return;
+    }
+    let woken = due.iter().filter(|path| cli::unsnooze(path, &inbox).is_ok()).count();
+    app.set_status(format!("{woken} snoozed email(s) back in the inbox"));
+    app.invalidate_cache(Mailbox::Inbox);
+    app.reload_current_mailbox();
+}
+
+/// Send a desktop notification if the inbox grew past `before` emails.
+fn notify_new_mail(app: &mut App, before: usize) {
+    let delta = app.mailbox_counts[Mailbox::Inbox.index()].saturating_sub(before);
+    if delta == 0 || !app.config.notifications {
+        return;
+    }
+    let subject = app.newest_subject(Mailbox::Inbox).unwrap_or_default();
+    if let Err(e) = notify::send_notification(delta, &subject, app.config.notifications_sound) {
+        app.set_status(format!("Notification failed: {e}"));
+    }
+}
+
+/// Run a long `email` command on a worker thread; the status bar shows a spinner
+/// until `BackgroundTask::OperationDone` arrives. Only one runs at a time.
+fn spawn_operation(
+    app: &mut App,
+    tasks: &mpsc::Sender<BackgroundTask>,
+    name: &'static str,
+    label: &str,
+    op: fn(&[Option<PathBuf>; 6]) -> Result<String>,
+) {
+    if app.operation_label.is_some() {
+        return;
+    }
+    app.operation_label = Some(label.to_string());
+    let tx = tasks.clone();
+    let dirs = app.mailbox_dirs.clone();
+    std::thread::spawn(move || {
+        let _ = tx.send(BackgroundTask::OperationDone { name, result: op(&dirs) });
+    });
+}
+
+/// Send each due scheduled draft on a worker thread, reporting back with
+/// `BackgroundTask::ScheduledSent`. A failure is written to the draft's
+/// `send_error` field so it is not retried on every tick.
+fn send_scheduled(
+    tasks: &mpsc::Sender<BackgroundTask>,
+    dirs: [Option<PathBuf>; 6],
+    due: Vec<PathBuf>,
+) {
+    let tx = tasks.clone();
+    std::thread::spawn(move || {
+        let mut sent = 0;
+        let mut failures = Vec::new();
+        for path in &due {
+            match cli::send(&dirs, path) {
+                Ok(_) => sent += 1,
+                Err(e) => {
+                    let _ = cli::record_send_error(path, &format!("{e}"));
+                    failures.push(format!("{e}"));
+                }
+            }
+        }
+        let _ = tx.send(BackgroundTask::ScheduledSent { sent, failures });
+    });
+}
+
+/// Open the selected email in the editor (at `line`, if given) and reload
+/// only when the file changed.
+fn edit_selected(
+    app: &mut App,
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    line: Option<u16>,
+) -> Result<()> {
+    let Some(path) = app.selected_email_path() else {
+        return Ok(());
+    };
+    let before = cli::modified_time(&path);
+    suspend_terminal(terminal)?;
+    let result = match line {
+        Some(line) => cli::edit_file_at_line_interactive(&path, line),
+        None => cli::edit_file_interactive(&path),
+    };
+    resume_terminal(terminal)?;
+    let changed = file_changed(&path, before);
+    match result {
+        Ok(()) if changed => app.set_status("Returned from editor".to_string()),
+        Ok(()) => app.set_status("No changes made".to_string()),
+        Err(e) => app.set_status(format!("Edit failed: {e}")),
+    }
+    if changed {
+        app.reload_current_mailbox();
+    }
+    Ok(())
+}
+
+/// `path` with the home directory shortened to `~`.
+fn home_relative(path: &Path) -> String {
+    match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Whether `path` was modified since `before`. Assumes it was when either
+/// mtime is unavailable, so callers reload rather than miss a change.
+fn file_changed(path: &Path, before: Option<SystemTime>) -> bool {
+    match (before, cli::modified_time(path)) {
+        (Some(before), Some(after)) => before != after,
+        _ => true,
+    }
+}
+
+/// Record a reversible action, removing any backup file evicted from the stack.
+fn push_undo(app: &mut App, kind: UndoKind) {
+    if let Some(stale) = app.push_undo(kind) {
+        let _ = std::fs::remove_file(stale);
+    }
+}
+
 fn suspend_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
     disable_raw_mode()?;
-    execute!(stdout(), LeaveAlternateScreen)?;
+    execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen)?;
     terminal.show_cursor()?;
     Ok(())
 }
 
+/// Stop like a shell job: leave raw mode and the alternate screen, then send
+/// ourselves SIGTSTP. The main loop resumes the terminal on SIGCONT (`fg`).
+fn suspend_to_background(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    suspend_terminal(terminal)?;
+    signal_hook::low_level::raise(signal_hook::consts::SIGTSTP)?;
+    Ok(())
+}
+
 fn resume_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
     enable_raw_mode()?;
-    execute!(stdout(), EnterAlternateScreen)?;
+    execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
     terminal.hide_cursor()?;
     terminal.clear()?;
     Ok(())
@@ -310,14 +1029,14 @@ fn resume_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Res
 
 fn init_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
     enable_raw_mode()?;
-    execute!(stdout(), EnterAlternateScreen)?;
+    execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout());
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
 }
 
 fn restore_terminal() -> Result<()> {
-    execute!(stdout(), LeaveAlternateScreen)?;
+    execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
 }
@@ -325,38 +1044,121 @@ fn restore_terminal() -> Result<()> {
 fn install_panic_hook() {
     let original_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
-        let _ = execute!(stdout(), LeaveAlternateScreen);
+        let _ = execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen);
         let _ = disable_raw_mode();
         original_hook(panic_info);
     }));
 }
 
-fn watcher_loop(tx: mpsc::Sender<WatchEvent>) {
+fn watcher_loop(
+    tx: mpsc::Sender<WatchEvent>,
+    inbox_dir: Arc<Mutex<Option<PathBuf>>>,
+    watcher: WatcherSettings,
+) {
+    let mut backoff_secs = WATCH_INITIAL_BACKOFF_SECS;
+    let mut failures = 0;
+    let (program, args) = watcher.argv.split_first().expect("watcher argv is never empty");
+    let (changed, timeout) = (Some(watcher.exit_changed), Some(watcher.exit_timeout));
     loop {
-        let result = std::process::Command::new("email")
-            .args(["watch", "--timeout", "300"])
+        let result = std::process::Command::new(program)
+            .args(args)
             .stdout(std::process::Stdio::null())
             .stderr(std::process::Stdio::piped())
             .status();
 
         match result {
-            Ok(status) => match status.code() {
-                Some(0) => {
-                    if tx.send(WatchEvent::Changed).is_err() {
-                        break; // receiver dropped, app is quitting
+            Ok(status) if status.code() == changed || status.code() == timeout => {
+                if failures > 0 {
+                    failures = 0;
+                    backoff_secs = WATCH_INITIAL_BACKOFF_SECS;
+                    if tx.send(WatchEvent::Reconnected).is_err() {
+                        break;
                     }
                 }
-                Some(2) => continue, // timeout, restart IDLE
-                _ => {
-                    let _ = tx.send(WatchEvent::Error("Watch connection lost".into()));
-                    std::thread::sleep(std::time::Duration::from_secs(30));
+                // Mail changed, or a timeout (just restart IDLE)
+                if status.code() == changed && tx.send(WatchEvent::Changed).is_err() {
+                    break; // receiver dropped, app is quitting
                 }
-            },
+                std::thread::sleep(watcher.pause);
+            }
+            Ok(_) => {
+                failures += 1;
+                if failures >= WATCH_MAX_FAILURES {
+                    let msg = format!("Watch giving up after {WATCH_MAX_FAILURES} failures");
+                    let _ = tx.send(WatchEvent::Error(msg));
+                    break;
+                }
+                let msg = format!("Watch connection lost, retrying in {backoff_secs}s");
+                if tx.send(WatchEvent::Error(msg)).is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_secs(backoff_secs));
+                backoff_secs = (backoff_secs * 2).min(WATCH_MAX_BACKOFF_SECS);
+            }
             Err(_) => {
-                // email binary not found or not executable -- stop retrying
-                let _ = tx.send(WatchEvent::Error("email watch unavailable".into()));
+                // email binary not found or not executable -- watch the files instead
+                let dir = inbox_dir.lock().ok().and_then(|dir| dir.clone());
+                match dir {
+                    Some(dir) if dir.is_dir() && tx.send(WatchEvent::FilesFallback).is_ok() => {
+                        files_watch_loop(&inbox_dir, &tx);
+                    }
+                    _ => {
+                        let _ = tx.send(WatchEvent::Error("email watch unavailable".into()));
+                    }
+                }
                 break;
             }
         }
     }
 }
+
+/// Poll the inbox directory, reporting when new `.md` files appear. The
+/// directory is re-read each round so an account switch moves the watch.
+/// Runs until the receiver is dropped.
+fn files_watch_loop(inbox_dir: &Mutex<Option<PathBuf>>, tx: &mpsc::Sender<WatchEvent>) {
+    let current_dir = || inbox_dir.lock().ok().and_then(|dir| dir.clone());
+    let scan = |dir: &Option<PathBuf>| -> HashSet<PathBuf> {
+        let Some(dir) = dir else {
+            return HashSet::new();
+        };
+        std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let mut dir = current_dir();
+    let mut known = scan(&dir);
+    loop {
+        std::thread::sleep(FILES_WATCH_INTERVAL);
+        let now_dir = current_dir();
+        if now_dir != dir {
+            // Account switched: start over without reporting the new inbox as new mail
+            dir = now_dir;
+            known = scan(&dir);
+            continue;
+        }
+        let current = scan(&dir);
+        let created = current.iter().any(|p| !known.contains(p));
+        if created && tx.send(WatchEvent::FilesChanged).is_err() {
+            break;
+        }
+        known = current;
+    }
+}
+
+fn scheduler_loop(drafts_dir: &Mutex<Option<PathBuf>>, tx: mpsc::Sender<BackgroundTask>) {
+    loop {
+        // Re-read every tick so an account switch or reload takes effect
+        let dir = drafts_dir.lock().ok().and_then(|dir| dir.clone());
+        let (due, pending) = dir.as_deref().map(email::scheduled_drafts).unwrap_or_default();
+        if tx.send(BackgroundTask::ScheduledSend { due, pending }).is_err() {
+            break; // receiver dropped, app is quitting
+        }
+        std::thread::sleep(SCHEDULE_INTERVAL);
+    }
+}
@@ -0,0 +1,122 @@
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Span;
+
+use crate::theme::ThemeColors;
+
+/// Inline markers and the modifier each one toggles; longer markers first.
+const MARKERS: [(&str, Modifier); 5] = [
+    ("**", Modifier::BOLD),
+    ("~~", Modifier::CROSSED_OUT),
+    ("__", Modifier::BOLD),
+    ("*", Modifier::ITALIC),
+    ("_", Modifier::ITALIC),
+];
+
+/// Whether `line` opens or closes a fenced code block.
+pub fn is_fence(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+/// Style for code spans and the inside of fenced blocks.
+pub fn code_style(c: &ThemeColors) -> Style {
+    Style::default().fg(c.peach).add_modifier(Modifier::DIM)
+}
+
+/// Style one Markdown line on top of `base`: `**bold**`, `*italic*` or
+/// `_italic_`, `` `code` `` and `~~strikethrough~~`, with the markers
+/// dropped. A `# heading` line is mauve and bold. Markers without a
+/// matching closer are kept as literal text.
+pub fn parse_inline(line: &str, c: &ThemeColors, base: Style) -> Vec<Span<'static>> {
+    let base = if is_heading(line) {
+        base.fg(c.mauve).add_modifier(Modifier::BOLD)
+    } else {
+        base
+    };
+
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut push = |text: &str, style: Style| {
+        if text.is_empty() {
+            return;
+        }
+        match spans.last_mut() {
+            Some(last) if last.style == style => last.content.to_mut().push_str(text),
+            _ => spans.push(Span::styled(text.to_string(), style)),
+        }
+    };
+
+    // Markers currently open, innermost last
+    let mut open: Vec<&str> = Vec::new();
+    let style_of = |open: &[&str]| {
+        open.iter().fold(base, |style, marker| {
+            let modifier = MARKERS.iter().find(|(m, _)| m == marker).map(|(_, m)| *m);
+            style.add_modifier(modifier.unwrap_or(Modifier::empty()))
+        })
+    };
+
+    let mut pos = 0;
+    let mut text_start = 0;
+    while pos < line.len() {
+        let rest = &line[pos..];
+        // Code spans take their content verbatim
+        if let Some(len) = rest.strip_prefix('`').and_then(|code| code.find('`')) {
+            push(&line[text_start..pos], style_of(&open));
+            push(&rest[1..=len], code_style(c));
+            pos += len + 2;
+            text_start = pos;
+            continue;
+        }
+        let marker = MARKERS.iter().map(|(m, _)| *m).find(|m| rest.starts_with(m));
+        let Some(marker) = marker else {
+            pos += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+        let before = line[..pos].chars().next_back();
+        let after = rest[marker.len()..].chars().next();
+        if open.last() == Some(&marker) && closes(marker, before, after) {
+            push(&line[text_start..pos], style_of(&open));
+            open.pop();
+            text_start = pos + marker.len();
+        } else if !open.contains(&marker)
+            && opens(marker, before, after)
+            && has_closer(&rest[marker.len()..], marker)
+        {
+            push(&line[text_start..pos], style_of(&open));
+            open.push(marker);
+            text_start = pos + marker.len();
+        }
+        pos += marker.len();
+    }
+    push(&line[text_start..], style_of(&open));
+    spans
+}
+
+/// `# Title` up to `###### Title`.
+fn is_heading(line: &str) -> bool {
+    let hashes = line.bytes().take_while(|&b| b == b'#').count();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with(' ')
+}
+
+/// An opener is followed by text; `_` must also start a word, so
+/// `snake_case` stays literal.
+fn opens(marker: &str, before: Option<char>, after: Option<char>) -> bool {
+    let word_start = !marker.starts_with('_') || !before.is_some_and(char::is_alphanumeric);
+    word_start && after.is_some_and(|ch| !ch.is_whitespace())
+}
+
+/// A closer follows text; `_` must also end a word.
+fn closes(marker: &str, before: Option<char>, after: Option<char>) -> bool {
+    let word_end = !marker.starts_with('_') || !after.is_some_and(char::is_alphanumeric);
+    word_end && before.is_some_and(|ch| !ch.is_whitespace())
+}
+
+/// Whether `rest` holds a place where `marker` could close.
+fn has_closer(rest: &str, marker: &str) -> bool {
+    rest.match_indices(marker).any(|(i, _)| {
+        let before = rest[..i].chars().next_back();
+        let after = rest[i + marker.len()..].chars().next();
+        // A longer run (`**` while looking for `*`) is a different marker
+        let same_run = marker.len() == 1
+            && (rest[..i].ends_with(marker) || rest[i + 1..].starts_with(marker));
+        i > 0 && !same_run && closes(marker, before, after)
+    })
+}
@@ -0,0 +1,51 @@
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+/// Show a desktop notification for `count_delta` new emails, with the newest
+/// one's subject as the body. Uses `notify-send` (libnotify) on Linux and
+/// `osascript` on macOS.
+pub fn send_notification(count_delta: usize, subject: &str, sound: bool) -> Result<()> {
+    let title = if count_delta == 1 {
+        "1 new email".to_string()
+    } else {
+        format!("{count_delta} new emails")
+    };
+
+    let mut cmd = if cfg!(target_os = "macos") {
+        let mut script = format!(
+            "display notification {} with title {}",
+            applescript_quote(subject),
+            applescript_quote(&title)
+        );
+        if sound {
+            script.push_str(" sound name \"default\"");
+        }
+        let mut cmd = Command::new("osascript");
+        cmd.arg("-e").arg(script);
+        cmd
+    } else {
+        let mut cmd = Command::new("notify-send");
+        cmd.arg("--app-name=beautifulmail");
+        if sound {
+            cmd.arg("--hint=string:sound-name:message-new-email");
+        }
+        cmd.arg(&title).arg(subject);
+        cmd
+    };
+
+    let status = cmd
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("Failed to run the notification command")?;
+    if !status.success() {
+        anyhow::bail!("Notification command exited with status: {status}");
+    }
+    Ok(())
+}
+
+/// Quote a string as an AppleScript string literal.
+fn applescript_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,285 @@
+use std::io;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Flex, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Wrap};
+use ratatui::{Frame, Terminal};
+
+use crate::config::Config;
+use crate::theme::ThemeColors;
+use crate::ui::{FileInputEvent, FileInputWidget};
+
+/// Mailboxes asked for by the wizard, with the path suggested for each.
+const PROMPTS: [(&str, &str, &str); 4] = [
+    ("Inbox", "inbox_dir", "~/Mail/inbox"),
+    ("Drafts", "drafts_dir", "~/Mail/drafts"),
+    ("Sent", "sent_dir", "~/Mail/sent"),
+    ("Archive", "archive_dir", "~/Mail/archive"),
+];
+
+/// Marker in front of the path being typed.
+const PROMPT: &str = " > ";
+
+/// Current screen of the wizard.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Welcome,
+    /// Path prompt for `PROMPTS[i]`.
+    Path(usize),
+    Confirm,
+}
+
+struct Wizard {
+    step: Step,
+    input: FileInputWidget,
+    /// Accepted paths, as typed (`~` is expanded when the config is read).
+    paths: Vec<String>,
+    colors: ThemeColors,
+}
+
+/// What a key press did to the wizard.
+enum Outcome {
+    Continue,
+    Skip,
+    Finish,
+}
+
+/// Walk a first-time user through choosing mailbox directories and write
+/// them to the config file. Returns whether a config was written; `q` (or
+/// Esc) at any step skips setup.
+pub fn run_setup_wizard(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<bool> {
+    let mut wizard = Wizard {
+        step: Step::Welcome,
+        input: FileInputWidget::default(),
+        paths: Vec::new(),
+        colors: Config::load().unwrap_or_default().theme.colors(),
+    };
+    loop {
+        terminal.draw(|frame| wizard.render(frame))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match wizard.handle_key(key) {
+            Outcome::Continue => {}
+            Outcome::Skip => return Ok(false),
+            Outcome::Finish => {
+                write_config(&wizard.paths)?;
+                return Ok(true);
+            }
+        }
+    }
+}
+
+impl Wizard {
+    fn handle_key(&mut self, key: KeyEvent) -> Outcome {
+        if (key.code == KeyCode::Esc && self.input.completions.is_empty())
+            || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+        {
+            return Outcome::Skip;
+        }
+        match self.step {
+            Step::Welcome => match key.code {
+                KeyCode::Char('q') => Outcome::Skip,
+                KeyCode::Enter => {
+                    self.step = Step::Path(0);
+                    Outcome::Continue
+                }
+                _ => Outcome::Continue,
+            },
+            Step::Path(i) => {
+                let empty = self.input.input.is_empty();
+                match key.code {
+                    // `q` only skips on an empty prompt, so paths may contain it
+                    KeyCode::Char('q') if empty => return Outcome::Skip,
+                    KeyCode::Backspace if empty && i > 0 => {
+                        // Step back to the previous prompt to edit it
+                        self.edit_previous();
+                        self.step = Step::Path(i - 1);
+                    }
+                    KeyCode::Enter if empty => self.accept(PROMPTS[i].2.to_string()),
+                    _ => {
+                        if let FileInputEvent::Submit(_) = self.input.handle_key(key) {
+                            // Kept as typed; `~` is expanded when the config is read
+                            self.accept(self.input.input.trim().to_string());
+                        }
+                    }
+                }
+                Outcome::Continue
+            }
+            Step::Confirm => match key.code {
+                KeyCode::Char('q') => Outcome::Skip,
+                KeyCode::Enter | KeyCode::Char('y') => Outcome::Finish,
+                KeyCode::Backspace | KeyCode::Char('n') => {
+                    self.edit_previous();
+                    self.step = Step::Path(PROMPTS.len() - 1);
+                    Outcome::Continue
+                }
+                _ => Outcome::Continue,
+            },
+        }
+    }
+
+    /// Record the path for the current prompt and move to the next step.
+    fn accept(&mut self, path: String) {
+        self.paths.push(path);
+        self.input.clear();
+        self.step = match self.step {
+            Step::Path(i) if i + 1 < PROMPTS.len() => Step::Path(i + 1),
+            _ => Step::Confirm,
+        };
+    }
+
+    /// Take back the last answer into the prompt for editing.
+    fn edit_previous(&mut self) {
+        let path = self.paths.pop().unwrap_or_default();
+        self.input.clear();
+        self.input.insert(&path);
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let c = &self.colors;
+        let area = frame.area();
+        frame.render_widget(Block::default().style(Style::default().bg(c.base)), area);
+
+        let horizontal = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(70.min(area.width))])
+            .flex(Flex::Center)
+            .split(area);
+        let vertical = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(14.min(area.height))])
+            .flex(Flex::Center)
+            .split(horizontal[0]);
+
+        let title = match self.step {
+            Step::Welcome => " Welcome to beautifulmail ".to_string(),
+            Step::Path(i) => format!(" Setup {}/{} ", i + 1, PROMPTS.len() + 1),
+            Step::Confirm => format!(" Setup {0}/{0} ", PROMPTS.len() + 1),
+        };
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(c.blue))
+            .style(Style::default().bg(c.base).fg(c.text));
+
+        let text = |s: String| Line::from(Span::styled(s, Style::default().fg(c.text)));
+        let dim = |s: String| Line::from(Span::styled(s, Style::default().fg(c.subtext0)));
+        let mut lines = vec![Line::from("")];
+        let hints = match self.step {
+            Step::Welcome => {
+                lines.push(text(" No mailbox directories are configured yet.".into()));
+                lines.push(text(
+                    " Pick where your Inbox, Drafts, Sent and Archive emails live.".into(),
+                ));
+                lines.push(Line::from(""));
+                lines.push(dim(format!(" They are saved to {}.", config_path_label())));
+                " Enter start   q skip setup"
+            }
+            Step::Path(i) => {
+                let (label, _, default) = PROMPTS[i];
+                lines.push(text(format!(" {label} directory:")));
+                // The typed path is drawn by the input widget over this line
+                let mut prompt = vec![Span::styled(PROMPT, Style::default().fg(c.blue))];
+                if self.input.input.is_empty() {
+                    prompt.push(Span::styled(default, Style::default().fg(c.overlay0)));
+                    prompt.push(Span::styled("\u{2588}", Style::default().fg(c.text)));
+                }
+                lines.push(Line::from(prompt));
+                let typed = self.input.input.as_str();
+                let path = if typed.is_empty() { default } else { typed };
+                lines.push(dim(format!("   = {}", expand(path).display())));
+                " Enter next (empty uses the suggestion)   Tab complete   Backspace back   \
+                 Esc skip setup"
+            }
+            Step::Confirm => {
+                lines.push(text(format!(" Save these directories to {}?", config_path_label())));
+                lines.push(Line::from(""));
+                for ((label, _, _), path) in PROMPTS.iter().zip(&self.paths) {
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("   {label:<9}"), Style::default().fg(c.mauve)),
+                        Span::styled(path.clone(), Style::default().fg(c.text)),
+                    ]));
+                }
+                lines.push(dim("   Missing directories are created.".into()));
+                " Enter/y save   n back   q skip setup"
+            }
+        };
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            hints,
+            Style::default().fg(c.yellow).add_modifier(Modifier::BOLD),
+        )));
+
+        let inner = block.inner(vertical[0]);
+        let content = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+        frame.render_widget(content, vertical[0]);
+
+        if matches!(self.step, Step::Path(_)) && !self.input.input.is_empty() {
+            // Third line: blank, label, then the prompt
+            let offset = PROMPT.len() as u16;
+            let input_area = Rect {
+                x: inner.x + offset.min(inner.width),
+                y: inner.y + 2.min(inner.height),
+                width: inner.width.saturating_sub(offset),
+                height: inner.height.saturating_sub(2).min(1),
+            };
+            self.input.render(c, frame, input_area);
+        }
+    }
+}
+
+/// Expand a leading `~` the way the mailbox directories are resolved.
+fn expand(path: &str) -> PathBuf {
+    PathBuf::from(shellexpand::tilde(path).into_owned())
+}
+
+/// The config file location for display, relative to home when possible.
+fn config_path_label() -> String {
+    let Some(path) = Config::path() else {
+        return "the config file".to_string();
+    };
+    match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(PathBuf::from)) {
+        Some(rel) => format!("~/{}", rel.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Create the chosen directories and append them to the config file as the
+/// first `[[account]]`.
+fn write_config(paths: &[String]) -> Result<()> {
+    let path = Config::path().context("No config directory (HOME is unset)")?;
+    for dir in paths {
+        let dir = expand(dir);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+
+    let mut content = std::fs::read_to_string(&path).unwrap_or_default();
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    content.push_str("[[account]]\nname = \"default\"\n");
+    for ((_, key, _), dir) in PROMPTS.iter().zip(paths) {
+        let value = toml::Value::String(dir.clone());
+        content.push_str(&format!("{key} = {value}\n"));
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
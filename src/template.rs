@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+/// Placeholders left for the `email` CLI to fill in, never reported as unknown.
+const PASSTHROUGH: &[&str] = &["SIGNATURE"];
+
+/// Values available to draft templates as `{{DATE}}`, `{{YEAR}}`,
+/// `{{USER_NAME}}` and `{{USER_EMAIL}}`.
+#[derive(Debug, Clone)]
+pub struct TemplateVars {
+    pub date: String,
+    pub year: String,
+    pub user_name: String,
+    pub user_email: String,
+}
+
+impl TemplateVars {
+    /// Today's date, and the user from `USER_NAME` / `USER_EMAIL` (falling back to `$USER`).
+    pub fn now() -> Self {
+        let today = chrono::Local::now();
+        let user = std::env::var("USER").unwrap_or_default();
+        Self {
+            date: today.format("%Y-%m-%d").to_string(),
+            year: today.format("%Y").to_string(),
+            user_name: std::env::var("USER_NAME").unwrap_or_else(|_| user.clone()),
+            user_email: std::env::var("USER_EMAIL").unwrap_or(user),
+        }
+    }
+
+    pub fn as_map(&self) -> HashMap<&'static str, &str> {
+        HashMap::from([
+            ("DATE", self.date.as_str()),
+            ("YEAR", self.year.as_str()),
+            ("USER_NAME", self.user_name.as_str()),
+            ("USER_EMAIL", self.user_email.as_str()),
+        ])
+    }
+}
+
+/// Replace `{{NAME}}` placeholders with values from `vars`. Unknown names are
+/// left as they are and returned so the caller can warn about them.
+pub fn render(content: &str, vars: &HashMap<&str, &str>) -> (String, Vec<String>) {
+    let mut out = String::with_capacity(content.len());
+    let mut unknown: Vec<String> = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = after[..end].trim();
+        match vars.get(name) {
+            Some(value) => out.push_str(value),
+            None => {
+                out.push_str(&rest[start..start + end + 4]);
+                if !PASSTHROUGH.contains(&name) && !unknown.iter().any(|u| u == name) {
+                    unknown.push(name.to_string());
+                }
+            }
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    (out, unknown)
+}
+
+/// One line of a line-by-line diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Largest `old lines x new lines` table diffed line by line; bigger inputs
+/// are shown as fully replaced.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Line diff turning `old` into `new`, from their longest common subsequence.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let (n, m) = (old.len(), new.len());
+    if n.saturating_mul(m) > MAX_DIFF_CELLS {
+        let removed = old.iter().map(|l| DiffLine::Removed(l.to_string()));
+        return removed.chain(new.iter().map(|l| DiffLine::Added(l.to_string()))).collect();
+    }
+
+    // lcs[i][j]: common lines between old[i..] and new[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    diff.extend(new[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+    diff
+}
@@ -1,17 +1,116 @@
-#![allow(dead_code)]
-
 use ratatui::style::Color;
+use serde::Deserialize;
+
+/// Catppuccin flavour used for all UI colors (`theme = "latte"` in the config).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Latte,
+    Frappe,
+    Macchiato,
+    #[default]
+    Mocha,
+}
+
+/// The named palette colors used by the UI.
+#[derive(Debug, Clone, Copy)]
+pub struct ThemeColors {
+    pub base: Color,
+    pub surface0: Color,
+    pub text: Color,
+    pub subtext0: Color,
+    pub green: Color,
+    pub blue: Color,
+    pub mauve: Color,
+    pub peach: Color,
+    pub yellow: Color,
+    pub red: Color,
+    pub teal: Color,
+    pub overlay0: Color,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [Theme::Latte, Theme::Frappe, Theme::Macchiato, Theme::Mocha];
+
+    pub fn colors(self) -> ThemeColors {
+        PALETTES[self as usize]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::Latte => "Latte",
+            Theme::Frappe => "Frappé",
+            Theme::Macchiato => "Macchiato",
+            Theme::Mocha => "Mocha",
+        }
+    }
+
+    /// The next flavour in `ALL`, wrapping around.
+    pub fn next(self) -> Theme {
+        Theme::ALL[(self as usize + 1) % Theme::ALL.len()]
+    }
+}
 
-// Catppuccin Mocha palette
-pub const BASE: Color = Color::Rgb(30, 30, 46);
-pub const SURFACE0: Color = Color::Rgb(49, 50, 68);
-pub const TEXT: Color = Color::Rgb(205, 214, 244);
-pub const SUBTEXT0: Color = Color::Rgb(166, 173, 200);
-pub const GREEN: Color = Color::Rgb(166, 227, 161);
-pub const BLUE: Color = Color::Rgb(137, 180, 250);
-pub const MAUVE: Color = Color::Rgb(203, 166, 247);
-pub const PEACH: Color = Color::Rgb(250, 179, 135);
-pub const YELLOW: Color = Color::Rgb(249, 226, 175);
-pub const RED: Color = Color::Rgb(243, 139, 168);
-pub const TEAL: Color = Color::Rgb(148, 226, 213);
-pub const OVERLAY0: Color = Color::Rgb(108, 112, 134);
+/// Catppuccin palettes, indexed by `Theme`.
+const PALETTES: [ThemeColors; 4] = [
+    // Latte
+    ThemeColors {
+        base: Color::Rgb(239, 241, 245),
+        surface0: Color::Rgb(204, 208, 218),
+        text: Color::Rgb(76, 79, 105),
+        subtext0: Color::Rgb(108, 111, 133),
+        green: Color::Rgb(64, 160, 43),
+        blue: Color::Rgb(30, 102, 245),
+        mauve: Color::Rgb(136, 57, 239),
+        peach: Color::Rgb(254, 100, 11),
+        yellow: Color::Rgb(223, 142, 29),
+        red: Color::Rgb(210, 15, 57),
+        teal: Color::Rgb(23, 146, 153),
+        overlay0: Color::Rgb(156, 160, 176),
+    },
+    // Frappé
+    ThemeColors {
+        base: Color::Rgb(48, 52, 70),
+        surface0: Color::Rgb(65, 69, 89),
+        text: Color::Rgb(198, 208, 245),
+        subtext0: Color::Rgb(165, 173, 206),
+        green: Color::Rgb(166, 209, 137),
+        blue: Color::Rgb(140, 170, 238),
+        mauve: Color::Rgb(202, 158, 230),
+        peach: Color::Rgb(239, 159, 118),
+        yellow: Color::Rgb(229, 200, 144),
+        red: Color::Rgb(231, 130, 132),
+        teal: Color::Rgb(129, 200, 190),
+        overlay0: Color::Rgb(115, 121, 148),
+    },
+    // Macchiato
+    ThemeColors {
+        base: Color::Rgb(36, 39, 58),
+        surface0: Color::Rgb(54, 58, 79),
+        text: Color::Rgb(202, 211, 245),
+        subtext0: Color::Rgb(165, 173, 203),
+        green: Color::Rgb(166, 218, 149),
+        blue: Color::Rgb(138, 173, 244),
+        mauve: Color::Rgb(198, 160, 246),
+        peach: Color::Rgb(245, 169, 127),
+        yellow: Color::Rgb(238, 212, 159),
+        red: Color::Rgb(237, 135, 150),
+        teal: Color::Rgb(139, 213, 202),
+        overlay0: Color::Rgb(110, 115, 141),
+    },
+    // Mocha
+    ThemeColors {
+        base: Color::Rgb(30, 30, 46),
+        surface0: Color::Rgb(49, 50, 68),
+        text: Color::Rgb(205, 214, 244),
+        subtext0: Color::Rgb(166, 173, 200),
+        green: Color::Rgb(166, 227, 161),
+        blue: Color::Rgb(137, 180, 250),
+        mauve: Color::Rgb(203, 166, 247),
+        peach: Color::Rgb(250, 179, 135),
+        yellow: Color::Rgb(249, 226, 175),
+        red: Color::Rgb(243, 139, 168),
+        teal: Color::Rgb(148, 226, 213),
+        overlay0: Color::Rgb(108, 112, 134),
+    },
+];
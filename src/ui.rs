@@ -1,15 +1,28 @@
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use chrono::{Datelike, NaiveDate};
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::layout::{Alignment, Constraint, Direction, Flex, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, BorderType, Borders, Cell, Clear, Paragraph, Row, Table, TableState, Wrap};
 use ratatui::Frame;
 
-use crate::app::{App, Focus, Mailbox};
-use crate::theme;
+use crate::app::{App, ConfirmDialog, DialogButton, DiffView, Focus, Mailbox, WatchMode};
+use crate::cli;
+use crate::config::{BorderStyle, ColumnId, Config};
+use crate::email::{self, EmailEntry, MailboxStats, PgpStatus, Priority};
+use crate::markdown;
+use crate::template::DiffLine;
+use crate::theme::{Theme, ThemeColors};
 
 /// Render the entire UI from the current app state.
 pub fn view(app: &App, frame: &mut Frame) {
+    let c = &app.theme.colors();
     let area = frame.area();
+    app.body_area.set(Rect::default());
 
     // Vertical: main area + status bar
     let outer = Layout::default()
@@ -22,16 +35,11 @@ pub fn view(app: &App, frame: &mut Frame) {
 
     let show_right = app.terminal_width >= 80;
     let show_sidebar = app.terminal_width >= 40;
+    let sidebar_height = Mailbox::ALL.len() as u16 + 2 * border_width(&app.config);
 
     if show_right {
         // Two-column layout: left (sidebar + list) | right (headers + body)
-        let columns = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(35),
-                Constraint::Percentage(65),
-            ])
-            .split(main_area);
+        let columns = split_columns(main_area, app.pane_split_pct);
 
         let left_col = columns[0];
         let right_col = columns[1];
@@ -40,19 +48,19 @@ pub fn view(app: &App, frame: &mut Frame) {
         let left_panels = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(7), // sidebar: 4 mailboxes + border
-                Constraint::Min(0),    // email list fills rest
+                Constraint::Length(sidebar_height), // sidebar: 6 mailboxes + border
+                Constraint::Min(0),                 // email list fills rest
             ])
             .split(left_col);
 
         render_sidebar(app, frame, left_panels[0]);
         render_email_list(app, frame, left_panels[1]);
 
-        // Right column: headers (fixed 6 lines, matching sidebar) + body
+        // Right column: headers (same height as the sidebar) + body
         let right_panels = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(7),
+                Constraint::Length(sidebar_height),
                 Constraint::Min(0),
             ])
             .split(right_col);
@@ -64,7 +72,7 @@ pub fn view(app: &App, frame: &mut Frame) {
         let left_panels = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(6),
+                Constraint::Length(sidebar_height),
                 Constraint::Min(0),
             ])
             .split(main_area);
@@ -81,24 +89,302 @@ pub fn view(app: &App, frame: &mut Frame) {
 
     // Confirmation dialog overlay (renders on top of everything)
     if let Some(dialog) = &app.confirm_dialog {
-        render_confirm_dialog(dialog, frame, area);
+        render_confirm_dialog(c, dialog, frame, area);
+    }
+
+    // Template picker overlay
+    if let Some(picker) = &app.template_picker {
+        render_template_picker(c, &app.config, picker, frame, area);
+    }
+
+    // Draft versus template diff
+    if let Some(view) = &app.diff_view {
+        render_diff_overlay(c, &app.config, view, frame, area);
+    }
+
+    // Move-to-mailbox picker overlay
+    if app.focus == Focus::MailboxPicker {
+        render_mailbox_picker(app, frame, area);
+    }
+
+    // Account switcher overlay
+    if app.focus == Focus::AccountPicker {
+        render_account_picker(app, frame, area);
+    }
+
+    // New draft recipient prompt
+    if app.focus == Focus::Compose {
+        render_compose(app, frame, area);
+    }
+
+    // Attach file prompt
+    if app.focus == Focus::FileInput {
+        render_file_input(app, frame, area);
+    }
+
+    // Contacts overlay
+    if app.focus == Focus::Contacts {
+        render_contacts_overlay(app, frame, area);
+    }
+
+    // Mailbox statistics overlay
+    if let Some(stats) = &app.stats {
+        render_stats_overlay(app, stats, frame, area);
+    }
+
+    // Unreadable files overlay
+    if app.show_parse_errors {
+        render_parse_errors(app, frame, area);
     }
 
     // Help overlay (renders on top of everything)
     if app.show_help {
-        render_help_overlay(frame, area);
+        render_help_overlay(app, frame, area);
+    }
+}
+
+/// Narrowest terminal that shows the CC column.
+pub const CC_MIN_WIDTH: u16 = 120;
+
+/// Path completions visible at once in a file input dropdown.
+const FILE_COMPLETIONS_SHOWN: usize = 8;
+
+/// Braille spinner shown next to a running background operation.
+#[derive(Debug, Default)]
+pub struct Spinner {
+    frame: usize,
+}
+
+impl Spinner {
+    const FRAMES: [&'static str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+    /// Advance to the next frame and return it.
+    pub fn tick(&mut self) -> &'static str {
+        self.frame = (self.frame + 1) % Self::FRAMES.len();
+        self.current()
+    }
+
+    pub fn current(&self) -> &'static str {
+        Self::FRAMES[self.frame]
+    }
+}
+
+/// What a key did to a [`FileInputWidget`].
+pub enum FileInputEvent {
+    /// Still editing: typed, completed or cycled through the completions.
+    Editing,
+    /// Tab found nothing to complete.
+    NoMatch,
+    /// Enter on a non-empty path, with `~` expanded.
+    Submit(PathBuf),
+    /// Esc with no completions open.
+    Cancel,
+}
+
+/// A path prompt with Tab completion, for any focus that asks for a file.
+#[derive(Debug, Default)]
+pub struct FileInputWidget {
+    pub input: String,
+    /// Paths offered by the last Tab, shown in a dropdown below the input.
+    pub completions: Vec<PathBuf>,
+    /// Completion currently filled into `input`.
+    pub completion_index: usize,
+    /// Text typed before cycling started, restored by Esc.
+    typed: String,
+}
+
+impl FileInputWidget {
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Append typed or pasted text, closing the completions.
+    pub fn insert(&mut self, text: &str) {
+        self.close();
+        self.input.push_str(text);
+    }
+
+    fn close(&mut self) {
+        self.completions.clear();
+        self.completion_index = 0;
+    }
+
+    fn select(&mut self, index: usize) {
+        self.completion_index = index;
+        self.input = self.completions[index].to_string_lossy().into_owned();
+    }
+
+    /// Tab lists the entries of the input's directory starting with its last
+    /// component and fills in the first; further Tabs (or Up/Down) cycle.
+    /// Enter accepts a completion, or submits when none is open.
+    pub fn handle_key(&mut self, key: KeyEvent) -> FileInputEvent {
+        let count = self.completions.len();
+        match key.code {
+            KeyCode::Tab | KeyCode::Down if count > 0 => {
+                self.select((self.completion_index + 1) % count);
+            }
+            KeyCode::BackTab | KeyCode::Up if count > 0 => {
+                self.select((self.completion_index + count - 1) % count);
+            }
+            KeyCode::Tab => {
+                let matches = cli::complete_path(&self.input);
+                if matches.is_empty() {
+                    return FileInputEvent::NoMatch;
+                }
+                self.typed = self.input.clone();
+                self.completions = matches.into_iter().map(PathBuf::from).collect();
+                self.select(0);
+                // A single match is final
+                if self.completions.len() == 1 {
+                    self.close();
+                }
+            }
+            KeyCode::Enter if count > 0 => self.close(),
+            KeyCode::Enter => {
+                let input = self.input.trim();
+                if !input.is_empty() {
+                    let path = PathBuf::from(shellexpand::tilde(input).as_ref());
+                    return FileInputEvent::Submit(path);
+                }
+            }
+            KeyCode::Esc if count > 0 => {
+                self.input = std::mem::take(&mut self.typed);
+                self.close();
+            }
+            KeyCode::Esc => return FileInputEvent::Cancel,
+            KeyCode::Char(c) => {
+                self.close();
+                self.input.push(c);
+            }
+            KeyCode::Backspace => {
+                self.close();
+                self.input.pop();
+            }
+            _ => {}
+        }
+        FileInputEvent::Editing
+    }
+
+    /// Draw the input with a cursor on the first line of `area`, and the open
+    /// completions as a dropdown just below it.
+    pub fn render(&self, c: &ThemeColors, frame: &mut Frame, area: Rect) {
+        let line = Line::from(vec![
+            Span::styled(self.input.as_str(), Style::default().fg(c.text)),
+            Span::styled("\u{2588}", Style::default().fg(c.text)),
+        ]);
+        frame.render_widget(Paragraph::new(line), Rect { height: area.height.min(1), ..area });
+        if self.completions.is_empty() {
+            return;
+        }
+
+        let shown = self.completions.len().min(FILE_COMPLETIONS_SHOWN);
+        let top = area.y + 1;
+        let height = (shown as u16 + 2).min(frame.area().bottom().saturating_sub(top));
+        if height < 3 {
+            return;
+        }
+        let dropdown = Rect { y: top, height, ..area };
+        frame.render_widget(Clear, dropdown);
+
+        let block = Block::default()
+            .title(format!(" {}/{} ", self.completion_index + 1, self.completions.len()))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(c.overlay0))
+            .style(Style::default().bg(c.base));
+        // Keep the highlighted completion inside the visible window
+        let first = (self.completion_index + 1).saturating_sub(FILE_COMPLETIONS_SHOWN);
+        let lines: Vec<Line> = self
+            .completions
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(shown)
+            .map(|(i, path)| {
+                let style = if i == self.completion_index {
+                    Style::default()
+                        .bg(c.surface0)
+                        .fg(c.green)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(c.subtext0)
+                };
+                let path = path.to_string_lossy();
+                let name = path.trim_end_matches('/').rsplit('/').next().unwrap_or(&path);
+                let slash = if path.ends_with('/') { "/" } else { "" };
+                Line::from(Span::styled(format!(" {name}{slash}"), style))
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines).block(block), dropdown);
+    }
+}
+
+/// Split the main area into the left (sidebar + list) and right (headers + body)
+/// columns, giving the left column `left_pct` percent of the width.
+fn split_columns(area: Rect, left_pct: u16) -> std::rc::Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(left_pct),
+            Constraint::Percentage(100 - left_pct),
+        ])
+        .split(area)
+}
+
+/// Width available for body text at the given terminal width (inside the borders).
+pub fn body_inner_width(terminal_width: u16, left_pct: u16, config: &Config) -> usize {
+    let columns = split_columns(Rect::new(0, 0, terminal_width, 1), left_pct);
+    columns[1].width.saturating_sub(2 * border_width(config)) as usize
+}
+
+/// Line set for pane and overlay borders (`border_style`).
+fn border_type(config: &Config) -> BorderType {
+    match config.border_style {
+        BorderStyle::Rounded | BorderStyle::None => BorderType::Rounded,
+        BorderStyle::Plain => BorderType::Plain,
+        BorderStyle::Double => BorderType::Double,
+        BorderStyle::Thick => BorderType::Thick,
+    }
+}
+
+/// Sides with a border: all of them, or none with `border_style = "none"`.
+fn borders(config: &Config) -> Borders {
+    match config.border_style {
+        BorderStyle::None => Borders::NONE,
+        _ => Borders::ALL,
     }
 }
 
+/// Thickness of one pane border in cells (0 without borders).
+pub fn border_width(config: &Config) -> u16 {
+    u16::from(config.border_style != BorderStyle::None)
+}
+
+/// Plain text of each rendered body line, matching what `render_body` draws.
+pub fn body_text_lines(body: &str, width: usize, wrap: bool) -> Vec<String> {
+    let body = body.replace("{{SIGNATURE}}", "[signature]");
+    // Only the text is used, so any palette will do
+    wrap_and_style_body(&Theme::default().colors(), &body, width, wrap, "", None)
+        .into_iter()
+        .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+        .collect()
+}
+
 /// Render the sidebar with mailbox list.
 fn render_sidebar(app: &App, frame: &mut Frame, area: Rect) {
-    let border_style = pane_border_style(app.focus, Focus::Sidebar);
+    let c = &app.theme.colors();
+    let config = &app.config;
+    let border_style = pane_border_style(c, app.focus, Focus::Sidebar);
+    let title = match app.accounts.get(app.active_account) {
+        Some(account) if app.accounts.len() > 1 => format!(" Mail: {} ", account.name),
+        _ => " Mail ".to_string(),
+    };
     let block = Block::default()
-        .title(" Mail ")
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
+        .title(title)
+        .borders(borders(config))
+        .border_type(border_type(config))
         .border_style(border_style)
-        .style(Style::default().bg(theme::BASE));
+        .style(Style::default().bg(c.base));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
@@ -115,52 +401,192 @@ fn render_sidebar(app: &App, frame: &mut Frame, area: Rect) {
         let label = format!(
             "{} {} {} {:>2}",
             marker,
-            mailbox.icon(),
-            mailbox.label(),
+            app.mailbox_icon(*mailbox),
+            app.mailbox_label(*mailbox),
             count
         );
 
         let style = if is_highlighted {
             Style::default()
-                .fg(theme::GREEN)
+                .fg(c.green)
                 .add_modifier(Modifier::BOLD)
+        } else if *mailbox == Mailbox::Spam && count > 0 {
+            Style::default().fg(c.red)
         } else if is_selected {
-            Style::default().fg(theme::BLUE)
+            Style::default().fg(c.blue)
         } else {
-            Style::default().fg(theme::TEXT)
+            Style::default().fg(c.text)
         };
 
-        lines.push(Line::from(Span::styled(label, style)));
+        let mut spans = vec![Span::styled(label, style)];
+        let unread = app.mailbox_unread[i];
+        if unread > 0 {
+            spans.push(Span::styled(format!(" ({unread})"), Style::default().fg(c.peach)));
+        }
+        lines.push(Line::from(spans));
     }
 
     let sidebar_content = Paragraph::new(lines);
     frame.render_widget(sidebar_content, inner);
 }
 
+/// How long ago an email was sent, for dimming older list rows (`C-a`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EmailAge {
+    Today,
+    ThisWeek,
+    ThisMonth,
+    Older,
+}
+
+impl EmailAge {
+    fn of(email: &EmailEntry, today: NaiveDate) -> Self {
+        let Some(date) = email.date() else {
+            return EmailAge::Older;
+        };
+        match (today - date).num_days() {
+            ..=0 => EmailAge::Today,
+            1..=6 => EmailAge::ThisWeek,
+            7..=30 => EmailAge::ThisMonth,
+            _ => EmailAge::Older,
+        }
+    }
+
+    /// Today's emails in bold, then progressively dimmer. Older rows stay
+    /// readable: `surface0` is the selection background.
+    fn style(self, c: &ThemeColors) -> Style {
+        match self {
+            EmailAge::Today => Style::default().fg(c.text).add_modifier(Modifier::BOLD),
+            EmailAge::ThisWeek => Style::default().fg(c.text),
+            EmailAge::ThisMonth => Style::default().fg(c.subtext0),
+            EmailAge::Older => Style::default().fg(c.overlay0),
+        }
+    }
+}
+
+/// Leading list column: a yellow star for starred emails.
+fn star_cell(c: &ThemeColors, email: &EmailEntry) -> Cell<'static> {
+    if email.starred {
+        Cell::from("\u{2605}").style(Style::default().fg(c.yellow))
+    } else {
+        Cell::from("")
+    }
+}
+
+/// Subject prefix: a red `!` for high priority, a dim arrow for low.
+fn priority_marker(c: &ThemeColors, priority: Priority) -> Option<Span<'static>> {
+    match priority {
+        Priority::High => {
+            Some(Span::styled("! ", Style::default().fg(c.red).add_modifier(Modifier::BOLD)))
+        }
+        Priority::Low => Some(Span::styled("\u{2193} ", Style::default().fg(c.subtext0))),
+        Priority::Normal => None,
+    }
+}
+
+/// Date column text, prefixed with the mailbox icon during global search.
+fn date_label(app: &App, email_idx: usize) -> String {
+    let date = &app.emails[email_idx].date_display;
+    if app.global_search_active {
+        format!("{} {date}", app.mailbox_icon(app.origin_mailbox(email_idx)))
+    } else {
+        date.clone()
+    }
+}
+
+/// Short label for a date filter: `Jan 1–Jan 31`, with years when they differ
+/// from the current one and `…` for an open end.
+fn date_range_label((from, to): (NaiveDate, NaiveDate)) -> String {
+    let this_year = chrono::Local::now().year();
+    let fmt = |d: NaiveDate| {
+        if d == NaiveDate::MIN || d == NaiveDate::MAX {
+            "\u{2026}".to_string()
+        } else if d.year() == this_year {
+            d.format("%b %-d").to_string()
+        } else {
+            d.format("%b %-d %Y").to_string()
+        }
+    };
+    if from == to {
+        fmt(from)
+    } else {
+        format!("{}\u{2013}{}", fmt(from), fmt(to))
+    }
+}
+
 /// Render the email list as a table, with optional search bar.
 fn render_email_list(app: &App, frame: &mut Frame, area: Rect) {
-    let border_style = pane_border_style(app.focus, Focus::List);
-    let title = if !app.search_query.is_empty() && app.focus != Focus::Search {
+    let c = &app.theme.colors();
+    let config = &app.config;
+    let today = chrono::Local::now().date_naive();
+    let border_style = pane_border_style(c, app.focus, Focus::List);
+    let mut title = if app.global_search_active {
         if app.search_includes_body {
-            format!(" {} (content search) ", app.active_mailbox.label())
+            " All mailboxes (content search) ".to_string()
         } else {
-            format!(" {} (filtered) ", app.active_mailbox.label())
+            " All mailboxes (search) ".to_string()
         }
     } else {
-        format!(" {} ", app.active_mailbox.label())
+        let label = match app.date_range {
+            Some(range) => format!(
+                "{} ({})",
+                app.mailbox_label(app.active_mailbox),
+                date_range_label(range)
+            ),
+            None => app.mailbox_label(app.active_mailbox).to_string(),
+        };
+        if !app.search_query.is_empty() && app.focus != Focus::Search {
+            if let Some((field, name)) = &app.address_filter {
+                format!(" {label} ({} {name}) ", field.label())
+            } else if app.search_fuzzy {
+                format!(" {label} (fuzzy) ")
+            } else if app.search_includes_body {
+                format!(" {label} (content search) ")
+            } else {
+                format!(" {label} (filtered) ")
+            }
+        } else {
+            format!(" {label} ")
+        }
     };
+    let sort = app.sort();
+    let arrow = if sort.asc { '\u{2191}' } else { '\u{2193}' };
+    title.push_str(&format!("{arrow} {} ", sort.column.label()));
+    if app.scheduled_only {
+        title.push_str("[scheduled] ");
+    }
+    if app.starred_only {
+        title.push_str("[starred] ");
+    }
+    if app.duplicates_only {
+        title.push_str("[duplicates] ");
+    }
+    if let Some(status) = &app.status_filter {
+        title.push_str(&format!("[{status}] "));
+    }
+    if app.high_priority_only {
+        title.push_str("[high priority] ");
+    }
+    if app.thread_view {
+        title.push_str("[threads] ");
+    }
     let block = Block::default()
         .title(title)
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
+        .borders(borders(config))
+        .border_type(border_type(config))
         .border_style(border_style)
-        .style(Style::default().bg(theme::BASE));
+        .style(Style::default().bg(c.base));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
     // Split inner area for optional search bar
-    let search_visible = app.focus == Focus::Search || !app.search_query.is_empty();
+    let search_visible = app.focus == Focus::Search
+        || app.focus == Focus::DateFilter
+        || app.focus == Focus::JumpSearch
+        || app.focus == Focus::SnoozeInput
+        || !app.search_query.is_empty()
+        || app.global_search_active;
     let (search_area, list_area) = if search_visible {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -171,224 +597,505 @@ fn render_email_list(app: &App, frame: &mut Frame, area: Rect) {
         (None, inner)
     };
 
-    // Render search bar
-    if let Some(search_rect) = search_area {
-        let prefix = if app.search_includes_body { "\\" } else { "/" };
+    // Render search bar (or the date filter / snooze prompt while it is being edited)
+    let prompt = match app.focus {
+        Focus::DateFilter => Some(("date: ", app.date_filter_input.as_str())),
+        Focus::SnoozeInput => Some(("snooze until: ", app.snooze_input.as_str())),
+        Focus::JumpSearch => Some(("jump: ", app.jump_query.as_deref().unwrap_or_default())),
+        _ => None,
+    };
+    if let (Some(search_rect), Some((label, input))) = (search_area, prompt) {
+        let spans = vec![
+            Span::styled(label, Style::default().fg(c.blue)),
+            Span::styled(input, Style::default().fg(c.text)),
+            Span::styled("\u{2588}", Style::default().fg(c.blue)),
+        ];
+        frame.render_widget(Paragraph::new(Line::from(spans)), search_rect);
+    } else if let Some(search_rect) = search_area {
+        let prefix = match (app.global_search_active, app.search_includes_body) {
+            (true, _) if app.search_fuzzy => "g~",
+            (false, _) if app.search_fuzzy => "~",
+            (true, true) => "g\\",
+            (true, false) => "g/",
+            (false, true) => "\\",
+            (false, false) => "/",
+        };
         let mut spans = vec![
-            Span::styled(prefix, Style::default().fg(theme::BLUE)),
-            Span::styled(app.search_query.as_str(), Style::default().fg(theme::TEXT)),
+            Span::styled(prefix, Style::default().fg(c.blue)),
+            Span::styled(app.search_query.as_str(), Style::default().fg(c.text)),
         ];
         if app.focus == Focus::Search {
             spans.push(Span::styled(
                 "\u{2588}",
-                Style::default().fg(theme::BLUE),
+                Style::default().fg(c.blue),
             ));
         }
+        if !app.search_query.is_empty() {
+            spans.push(match app.emails.len() {
+                0 => Span::styled(" (no matches)", Style::default().fg(c.red)),
+                1 => Span::styled(" (1 match)", Style::default().fg(c.subtext0)),
+                n => Span::styled(format!(" ({n} matches)"), Style::default().fg(c.subtext0)),
+            });
+        }
         frame.render_widget(Paragraph::new(Line::from(spans)), search_rect);
     }
 
+    app.list_area.set(Rect::default());
+    app.list_visible_rows.set(list_area.height.saturating_sub(1));
     if app.emails.is_empty() {
-        let msg = if !app.search_query.is_empty() {
+        let filtered = app.global_search_active
+            || app.starred_only
+            || app.status_filter.is_some()
+            || app.high_priority_only
+            || app.scheduled_only
+            || app.date_range.is_some();
+        let msg = if !app.search_query.is_empty() || filtered {
             "  No matching emails".to_string()
         } else {
             format!(
-                "\n  No emails in {}\n\n  Press f to fetch new emails",
-                app.active_mailbox.label()
+                "\n  No emails in {}\n\n  Press ff to fetch new emails",
+                app.mailbox_label(app.active_mailbox)
             )
         };
         let empty =
-            Paragraph::new(msg).style(Style::default().fg(theme::SUBTEXT0));
+            Paragraph::new(msg).style(Style::default().fg(c.subtext0));
         frame.render_widget(empty, list_area);
         return;
     }
 
     // Calculate column widths from available space
     let available_width = list_area.width as usize;
-    let date_width = 10; // YYYY-MM-DD
-    let spacing = 3; // gaps between columns
-
-    if available_width > 45 {
-        // 3 columns: DATE + CONTACT + SUBJECT
-        let contact_width =
-            15.min(available_width.saturating_sub(date_width + spacing + 10));
-        let subject_width =
-            available_width.saturating_sub(date_width + contact_width + spacing);
-
-        let header = Row::new(vec![
-            Cell::from("DATE").style(Style::default().fg(theme::SUBTEXT0)),
-            Cell::from("CONTACT").style(Style::default().fg(theme::SUBTEXT0)),
-            Cell::from("SUBJECT").style(Style::default().fg(theme::SUBTEXT0)),
-        ])
-        .height(1);
+    // Narrow panes keep only the date, status and subject
+    let wide = available_width > 45;
+    let mut columns: Vec<ColumnId> = app
+        .list_columns
+        .iter()
+        .copied()
+        .filter(|col| match col {
+            ColumnId::Contact => wide,
+            // Paperclip column, wide layout only
+            ColumnId::Attachments => wide && app.terminal_width >= 80,
+            _ => true,
+        })
+        .collect();
+    // CC column, when toggled on a wide terminal, follows the contact
+    if wide && app.show_cc && app.terminal_width >= CC_MIN_WIDTH {
+        let at = columns
+            .iter()
+            .position(|&col| col == ColumnId::Contact)
+            .map_or_else(|| columns.len() - 1, |i| i + 1);
+        columns.insert(at, ColumnId::Cc);
+    }
+
+    // YYYY-MM-DD, prefixed with the origin mailbox icon during global search
+    let date_width = if app.global_search_active { 12 } else { 10 };
+    let fixed_width = |col: ColumnId| match col {
+        ColumnId::Date => date_width,
+        ColumnId::Status => 9,
+        ColumnId::Attachments => 2,
+        ColumnId::Cc => 12,
+        ColumnId::Contact | ColumnId::Subject => 0,
+    };
+    // Star marker plus one gap per column
+    let fixed = 1 + columns.len() + columns.iter().map(|&col| fixed_width(col)).sum::<usize>();
+    let contact_width = if columns.contains(&ColumnId::Contact) {
+        15.min(available_width.saturating_sub(fixed + 10))
+    } else {
+        0
+    };
+    let subject_width = available_width.saturating_sub(fixed + contact_width);
 
-        let rows: Vec<Row> = app
-            .emails
+    let mut header_cells = vec![Cell::from("")];
+    header_cells.extend(
+        columns
             .iter()
-            .enumerate()
-            .map(|(i, email)| {
-                let is_selected = i == app.list_index;
-                let contact = truncate(
-                    email.display_contact(app.active_mailbox),
-                    contact_width,
-                );
-                let subject = truncate(&email.subject, subject_width);
-
-                let row_style = if is_selected {
-                    Style::default().bg(theme::SURFACE0).fg(theme::GREEN)
+            .map(|col| Cell::from(col.title()).style(Style::default().fg(c.subtext0))),
+    );
+    let header = Row::new(header_cells).height(1);
+
+    // Only the visible window of rows is built, keeping the cursor centered
+    let display_rows = app.display_rows();
+    let visible = app.list_visible_rows.get() as usize;
+    let visible_start = app
+        .list_index
+        .saturating_sub(visible / 2)
+        .min(display_rows.len().saturating_sub(visible));
+    let rows: Vec<Row> = display_rows
+        .into_iter()
+        .enumerate()
+        .skip(visible_start)
+        .take(visible)
+        .map(|(row, (i, is_reply))| {
+            let email = &app.emails[i];
+            let is_selected = row == app.list_index;
+            let mailbox = app.origin_mailbox(i);
+
+            let row_style = if is_selected {
+                Style::default().bg(c.surface0).fg(c.green)
+            } else {
+                let base = if app.age_highlighting {
+                    EmailAge::of(email, today).style(c)
                 } else {
-                    Style::default().fg(theme::TEXT)
+                    Style::default().fg(c.text)
                 };
+                // Thread replies stay dimmed on top of the age style
+                if is_reply {
+                    base.patch(Style::default().fg(c.subtext0))
+                } else {
+                    base
+                }
+            };
 
-                Row::new(vec![
-                    Cell::from(email.date_display.clone()),
-                    Cell::from(contact),
-                    Cell::from(subject),
-                ])
-                .style(row_style)
-            })
-            .collect();
-
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(date_width as u16),
-                Constraint::Length(contact_width as u16),
-                Constraint::Min(subject_width as u16),
-            ],
-        )
+            let fuzzy = app.fuzzy_matches(email, mailbox);
+            let mut cells = vec![star_cell(c, email)];
+            cells.extend(columns.iter().map(|col| match col {
+                ColumnId::Date => Cell::from(date_label(app, i)),
+                ColumnId::Contact => {
+                    let contact = truncate(email.display_contact(mailbox), contact_width);
+                    let hits = fuzzy.as_ref().map(|(_, contact)| (0, contact.as_slice()));
+                    let contact = list_match_line(app, c, contact, is_selected, hits);
+                    if app.config.sender_colors && !is_selected {
+                        let color = app.sender_color(email.contact_address(mailbox));
+                        Cell::from(contact).style(Style::default().fg(color))
+                    } else {
+                        Cell::from(contact)
+                    }
+                }
+                ColumnId::Cc => {
+                    let cc = email.cc_summary().unwrap_or_default();
+                    Cell::from(truncate(&cc, fixed_width(ColumnId::Cc)))
+                }
+                ColumnId::Status => {
+                    Cell::from(truncate(&email.status, fixed_width(ColumnId::Status)))
+                }
+                ColumnId::Attachments => {
+                    if email.has_attachments {
+                        Cell::from("\u{1F4CE}").style(Style::default().fg(c.peach))
+                    } else {
+                        Cell::from(" ")
+                    }
+                }
+                ColumnId::Subject => {
+                    let marker = priority_marker(c, email.priority);
+                    let marker_width = marker.as_ref().map_or(0, Span::width);
+                    let width = subject_width.saturating_sub(marker_width);
+                    let listed = list_subject(app, email);
+                    // Skip the "[DUP] " tag and the reply indent when mapping matches
+                    let offset = listed.chars().count() - email.subject.chars().count()
+                        + if is_reply { 5 } else { 0 };
+                    let hits = fuzzy.as_ref().map(|(subject, _)| (offset, subject.as_slice()));
+                    let subject = thread_subject(&listed, is_reply, width);
+                    let mut line = list_match_line(app, c, subject, is_selected, hits);
+                    if let Some(marker) = marker {
+                        line.spans.insert(0, marker);
+                    }
+                    Cell::from(line)
+                }
+            }));
+            Row::new(cells).style(row_style)
+        })
+        .collect();
+
+    let mut widths = vec![Constraint::Length(1)];
+    widths.extend(columns.iter().map(|&col| match col {
+        ColumnId::Contact => Constraint::Length(contact_width as u16),
+        ColumnId::Subject => Constraint::Min(subject_width as u16),
+        col => Constraint::Length(fixed_width(col) as u16),
+    }));
+    let table = Table::new(rows, widths)
         .header(header)
         .column_spacing(1)
         .row_highlight_style(
             Style::default()
-                .bg(theme::SURFACE0)
-                .fg(theme::GREEN)
+                .bg(c.surface0)
+                .fg(c.green)
                 .add_modifier(Modifier::BOLD),
         );
 
-        let mut state = TableState::default();
-        state.select(Some(app.list_index));
-        frame.render_stateful_widget(table, list_area, &mut state);
-    } else {
-        // 2 columns: DATE + SUBJECT only
-        let subject_width = available_width.saturating_sub(date_width + 2);
+    let mut state = TableState::default().with_selected(Some(app.list_index - visible_start));
+    frame.render_stateful_widget(table, list_area, &mut state);
+    app.list_area.set(list_area);
+    app.list_offset.set(visible_start);
 
-        let header = Row::new(vec![
-            Cell::from("DATE").style(Style::default().fg(theme::SUBTEXT0)),
-            Cell::from("SUBJECT").style(Style::default().fg(theme::SUBTEXT0)),
-        ])
-        .height(1);
+    if app.tooltip_visible {
+        render_hover_tooltip(app, frame);
+    }
+}
 
-        let rows: Vec<Row> = app
-            .emails
-            .iter()
-            .enumerate()
-            .map(|(i, email)| {
-                let is_selected = i == app.list_index;
-                let subject = truncate(&email.subject, subject_width);
+/// Floating preview of the first body lines of the hovered list row.
+fn render_hover_tooltip(app: &App, frame: &mut Frame) {
+    let c = &app.theme.colors();
+    let Some(row) = app.hover_index else {
+        return;
+    };
+    let Some(email) = app.email_index_at(row).map(|i| &app.emails[i]) else {
+        return;
+    };
+    let list_area = app.list_area.get();
+    let Some(rel) = row.checked_sub(app.list_offset.get()) else {
+        return;
+    };
+    // +1 skips the column header row
+    let row_y = list_area.y + 1 + rel as u16;
+    if row_y >= list_area.bottom() {
+        return;
+    }
 
-                let row_style = if is_selected {
-                    Style::default().bg(theme::SURFACE0).fg(theme::GREEN)
-                } else {
-                    Style::default().fg(theme::TEXT)
-                };
+    let lines: Vec<Line> = email
+        .body
+        .trim_start()
+        .lines()
+        .take(3)
+        .map(|l| Line::from(l.to_string()))
+        .collect();
+    if lines.is_empty() {
+        return;
+    }
 
-                Row::new(vec![
-                    Cell::from(email.date_display.clone()),
-                    Cell::from(subject),
-                ])
-                .style(row_style)
-            })
-            .collect();
+    let screen = frame.area();
+    let width = 60u16.min(list_area.width.saturating_sub(2)).min(screen.width);
+    let height = (lines.len() as u16 + 1).min(4);
+    // Above the row when there is room, below it otherwise
+    let y = if row_y >= screen.y + height {
+        row_y - height
+    } else {
+        (row_y + 1).min(screen.bottom().saturating_sub(height))
+    };
+    let tooltip_area = Rect::new(list_area.x + 1, y, width, height).intersection(screen);
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(date_width as u16),
-                Constraint::Min(subject_width as u16),
-            ],
-        )
-        .header(header)
-        .column_spacing(1)
-        .row_highlight_style(
-            Style::default()
-                .bg(theme::SURFACE0)
-                .fg(theme::GREEN)
-                .add_modifier(Modifier::BOLD),
-        );
+    let block = Block::default()
+        .borders(Borders::TOP)
+        .border_style(Style::default().fg(c.overlay0))
+        .style(Style::default().bg(c.base));
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(c.subtext0));
+
+    frame.render_widget(Clear, tooltip_area);
+    frame.render_widget(paragraph, tooltip_area);
+}
 
-        let mut state = TableState::default();
-        state.select(Some(app.list_index));
-        frame.render_stateful_widget(table, list_area, &mut state);
+/// Subject as shown in the list, with a `[DUP]` tag on older duplicate copies.
+fn list_subject<'a>(app: &App, email: &'a EmailEntry) -> Cow<'a, str> {
+    if app.duplicate_paths.contains(&email.path) {
+        Cow::Owned(format!("[DUP] {}", email.subject))
+    } else {
+        Cow::Borrowed(&email.subject)
+    }
+}
+
+/// Subject cell text, indented under its thread root when it is a reply.
+fn thread_subject(subject: &str, is_reply: bool, width: usize) -> String {
+    if is_reply {
+        format!("  \u{2514}\u{2500} {}", truncate(subject, width.saturating_sub(5)))
+    } else {
+        truncate(subject, width)
     }
 }
 
-/// Render a single header field as a styled Line.
-fn header_line<'a>(label: &'a str, value: &'a str, color: Color) -> Line<'a> {
+/// Render a single header field as a styled Line (with a `>` marker when focused).
+fn header_line<'a>(
+    c: &ThemeColors,
+    label: &'a str,
+    value: &'a str,
+    color: Color,
+    focused: bool,
+) -> Line<'a> {
+    let style = focused_style(c, color, focused);
     Line::from(vec![
         Span::styled(
-            format!(" {label}: "),
-            Style::default().fg(color).add_modifier(Modifier::BOLD),
+            format!("{}{label}: ", marker(focused)),
+            style.add_modifier(Modifier::BOLD),
         ),
-        Span::styled(value, Style::default().fg(color)),
+        Span::styled(value, style),
     ])
 }
 
 /// Render the email headers panel (fixed height, scrollable when focused).
 fn render_headers(app: &App, frame: &mut Frame, area: Rect) {
-    let border_style = pane_border_style(app.focus, Focus::Headers);
+    let c = &app.theme.colors();
+    let config = &app.config;
+    let border_style = pane_border_style(c, app.focus, Focus::Headers);
     let block = Block::default()
         .title(" Headers ")
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
+        .borders(borders(config))
+        .border_type(border_type(config))
         .border_style(border_style)
-        .style(Style::default().bg(theme::BASE));
+        .style(Style::default().bg(c.base));
 
-    let selected = app.emails.get(app.list_index);
+    let selected = app.selected_email();
     if selected.is_none() {
         let inner = block.inner(area);
         frame.render_widget(block, area);
         frame.render_widget(
             Paragraph::new("  No email selected")
-                .style(Style::default().fg(theme::SUBTEXT0)),
+                .style(Style::default().fg(c.subtext0)),
             inner,
         );
         return;
     }
 
     let email = selected.unwrap();
-    let mut lines: Vec<Line> = Vec::new();
+    let header_lines = header_lines(email);
+    let cursor = if app.focus == Focus::Headers {
+        Some(app.headers_selected_line.min(header_lines.len() - 1))
+    } else {
+        None
+    };
 
-    lines.push(header_line("From", &email.from, theme::GREEN));
-    lines.push(header_line("To", &email.to, theme::BLUE));
-    if let Some(cc) = &email.cc {
-        if !cc.is_empty() {
-            lines.push(header_line("Cc", cc, theme::BLUE));
-        }
+    // Date, status and attachment count on one line
+    let mut date_status = format!("{}  [{}]", email.date_display, email.status);
+    match email.num_attachments() {
+        0 => {}
+        1 => date_status.push_str("  1 attachment"),
+        n => date_status.push_str(&format!("  {n} attachments")),
+    }
+    if let Some(error) = &email.send_error {
+        date_status.push_str(&format!("  scheduled send failed: {error}"));
     }
-    lines.push(header_line("Subj", &email.subject, theme::YELLOW));
 
-    // Date and status on one line
-    let date_status = format!("{}  [{}]", email.date_display, email.status);
-    lines.push(header_line("Date", &date_status, theme::MAUVE));
+    let lines: Vec<Line> = header_lines
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            let focused = cursor == Some(i);
+            match *header {
+                HeaderLine::From => header_line(c, "From", &email.from, c.green, focused),
+                HeaderLine::ReplyTo => {
+                    let reply_to = email.distinct_reply_to().unwrap_or_default();
+                    header_line(c, "Reply-To", reply_to, c.teal, focused)
+                }
+                HeaderLine::To => header_line(c, "To", &email.to, c.blue, focused),
+                HeaderLine::Cc => {
+                    header_line(c, "Cc", email.cc.as_deref().unwrap_or(""), c.blue, focused)
+                }
+                HeaderLine::Subject => header_line(c, "Subj", &email.subject, c.yellow, focused),
+                HeaderLine::Date => header_line(c, "Date", &date_status, c.mauve, focused),
+                HeaderLine::Pgp => {
+                    let status = app
+                        .pgp_status
+                        .get(&email.path)
+                        .copied()
+                        .unwrap_or(PgpStatus::Unknown);
+                    let (text, color) = match status {
+                        PgpStatus::Valid => ("[PGP: \u{2713} valid]", c.green),
+                        PgpStatus::Invalid => ("[PGP: \u{2717} invalid]", c.red),
+                        PgpStatus::Checking => ("[PGP: checking\u{2026}]", c.subtext0),
+                        PgpStatus::Unknown => ("[PGP: ? unknown]", c.subtext0),
+                    };
+                    let style = focused_style(c, color, focused);
+                    Line::from(Span::styled(format!("{} {text}", marker(focused)), style))
+                }
+                HeaderLine::Attachment(idx) => {
+                    let attachment = &email.attachments[idx];
+                    Line::from(Span::styled(
+                        format!(
+                            "{} \u{1F4CE} {}  ({})",
+                            marker(focused),
+                            attachment.filename,
+                            email::format_size(attachment.size_bytes)
+                        ),
+                        focused_style(c, c.peach, focused),
+                    ))
+                }
+            }
+        })
+        .collect();
 
+    // Keep the cursor visible, but don't scroll past the last line once everything fits
+    let visible = block.inner(area).height as usize;
+    let max_scroll = lines.len().saturating_sub(visible);
+    let scroll = cursor.map_or(0, |i| (i + 1).saturating_sub(visible)).min(max_scroll);
     let content = Paragraph::new(lines)
         .block(block)
         .wrap(Wrap { trim: false })
-        .scroll((app.headers_scroll, 0));
+        .scroll((scroll as u16, 0));
     frame.render_widget(content, area);
 }
 
+/// A line of the headers pane, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderLine {
+    From,
+    ReplyTo,
+    To,
+    Cc,
+    Subject,
+    Date,
+    Pgp,
+    Attachment(usize),
+}
+
+/// Lines shown in the headers pane: From, [Reply-To], To, [Cc], Subj, Date, [PGP],
+/// attachments.
+pub fn header_lines(email: &EmailEntry) -> Vec<HeaderLine> {
+    let mut lines = vec![HeaderLine::From];
+    if email.distinct_reply_to().is_some() {
+        lines.push(HeaderLine::ReplyTo);
+    }
+    lines.push(HeaderLine::To);
+    if email.cc.as_ref().is_some_and(|cc| !cc.is_empty()) {
+        lines.push(HeaderLine::Cc);
+    }
+    lines.extend([HeaderLine::Subject, HeaderLine::Date]);
+    if email.pgp_signature.is_some() {
+        lines.push(HeaderLine::Pgp);
+    }
+    lines.extend((0..email.attachments.len()).map(HeaderLine::Attachment));
+    lines
+}
+
+/// Cursor marker drawn in front of the focused header line.
+fn marker(focused: bool) -> &'static str {
+    if focused {
+        ">"
+    } else {
+        " "
+    }
+}
+
+/// `color`, or green bold when the line has the headers cursor.
+fn focused_style(c: &ThemeColors, color: Color, focused: bool) -> Style {
+    if focused {
+        Style::default().fg(c.green).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(color)
+    }
+}
+
 /// Render the email body panel (scrollable, focused via Focus::Preview).
 fn render_body(app: &App, frame: &mut Frame, area: Rect) {
-    let border_style = pane_border_style(app.focus, Focus::Preview);
+    let c = &app.theme.colors();
+    let config = &app.config;
+    let border_style = pane_border_style(c, app.focus, Focus::Preview);
+    let title = if app.body_select_mode {
+        let (start, end) = app.body_selection();
+        format!(" Body (VISUAL {} lines) ", end - start + 1)
+    } else if app.show_raw {
+        " Body [source] ".to_string()
+    } else if app.search_match_lines.is_empty() {
+        " Body ".to_string()
+    } else {
+        format!(
+            " Body (match {}/{}) ",
+            app.search_match_index.map_or(0, |i| i + 1),
+            app.search_match_lines.len()
+        )
+    };
+    let title = if app.body_wrap {
+        title
+    } else {
+        format!("{title}[nowrap] ")
+    };
     let block = Block::default()
-        .title(" Body ")
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
+        .title(title)
+        .borders(borders(config))
+        .border_type(border_type(config))
         .border_style(border_style)
-        .style(Style::default().bg(theme::BASE));
+        .style(Style::default().bg(c.base));
 
-    let selected = app.emails.get(app.list_index);
+    app.body_area.set(area);
+    let selected = app.selected_email();
     if selected.is_none() {
         frame.render_widget(block, area);
         return;
@@ -399,15 +1106,57 @@ fn render_body(app: &App, frame: &mut Frame, area: Rect) {
 
     // Pre-wrap text ourselves so quoted continuation lines keep their prefix
     let inner_width = block.inner(area).width as usize;
-    let lines: Vec<Line> = wrap_and_style_body(&body, inner_width);
+    let query = if app.search_includes_body {
+        app.search_query.as_str()
+    } else {
+        ""
+    };
+    let mut lines: Vec<Line> = match app.raw_source.as_deref().filter(|_| app.show_raw) {
+        Some(source) => raw_source_lines(c, source),
+        None => {
+            let attribution = app.config.attribution_pattern.as_deref();
+            wrap_and_style_body(c, &body, inner_width, app.body_wrap, query, attribution)
+        }
+    };
+
+    // Highlight the link selected with `u`
+    if let Some((_, url)) = app.urls.get(app.url_index) {
+        let link_style = Style::default()
+            .fg(c.blue)
+            .add_modifier(Modifier::UNDERLINED);
+        lines = lines
+            .into_iter()
+            .map(|line| highlight_in_line(line, url, link_style, false))
+            .collect();
+    }
+
+    if app.body_select_mode {
+        let (start, end) = app.body_selection();
+        for line in lines.iter_mut().take(end as usize + 1).skip(start as usize) {
+            for span in line.spans.iter_mut() {
+                span.style = span.style.add_modifier(Modifier::REVERSED);
+            }
+        }
+    }
 
     let content = Paragraph::new(lines)
         .block(block)
-        .scroll((app.preview_scroll, 0));
+        .scroll((app.preview_scroll, app.preview_hscroll));
 
     frame.render_widget(content, area);
 }
 
+/// Lines of a raw email file, unwrapped, with the frontmatter fences highlighted.
+fn raw_source_lines<'a>(c: &ThemeColors, source: &'a str) -> Vec<Line<'a>> {
+    source
+        .lines()
+        .map(|line| {
+            let color = if line.trim_end() == "---" { c.mauve } else { c.subtext0 };
+            Line::from(Span::styled(line, Style::default().fg(color)))
+        })
+        .collect()
+}
+
 /// Parse quote depth and return (depth, remaining content after `>` markers).
 fn parse_quote_depth(line: &str) -> (usize, &str) {
     let trimmed = line.trim_start();
@@ -425,61 +1174,94 @@ fn parse_quote_depth(line: &str) -> (usize, &str) {
 }
 
 /// Wrap body text manually, preserving quote prefixes on continuation lines.
-fn wrap_and_style_body<'a>(body: &'a str, width: usize) -> Vec<Line<'a>> {
+/// Without `wrap`, each body line stays a single line.
+/// Occurrences of `query` (case-insensitive) are shown reversed, and lines
+/// matching the `attribution` pattern are dimmed.
+fn wrap_and_style_body<'a>(
+    c: &ThemeColors,
+    body: &'a str,
+    width: usize,
+    wrap: bool,
+    query: &str,
+    attribution: Option<&str>,
+) -> Vec<Line<'a>> {
     let mut result: Vec<Line> = Vec::new();
+    let wrap_line = |text: &str, width: usize| {
+        if wrap {
+            word_wrap(text, width)
+        } else {
+            vec![text.to_string()]
+        }
+    };
 
+    let mut in_fence = false;
     for line in body.lines() {
+        // Fenced code is shown verbatim, fences included
+        if markdown::is_fence(line) || in_fence {
+            in_fence ^= markdown::is_fence(line);
+            for wrapped in wrap_line(line, width) {
+                result.push(Line::from(Span::styled(wrapped, markdown::code_style(c))));
+            }
+            continue;
+        }
+
         // Signature placeholder
         if line.trim() == "[signature]" {
             result.push(Line::from(Span::styled(
                 "  -- signature --".to_string(),
-                Style::default().fg(theme::OVERLAY0),
+                Style::default().fg(c.overlay0),
             )));
             continue;
         }
 
         let (depth, content) = parse_quote_depth(line);
 
-        if depth == 0 {
-            // Regular or attribution line -- simple word wrap
-            let style = if is_attribution(line.trim()) {
-                Style::default()
-                    .fg(theme::SUBTEXT0)
-                    .add_modifier(Modifier::ITALIC)
-            } else {
-                Style::default().fg(theme::TEXT)
-            };
-            for wrapped in word_wrap(content, width) {
+        if depth == 0 && email::is_attribution(line.trim(), attribution) {
+            let style = Style::default()
+                .fg(c.subtext0)
+                .add_modifier(Modifier::ITALIC);
+            for wrapped in wrap_line(content, width) {
                 result.push(Line::from(Span::styled(wrapped, style)));
             }
+        } else if depth == 0 {
+            // Regular line -- Markdown styled, wrapped on its visible text
+            let spans = markdown::parse_inline(content, c, Style::default().fg(c.text));
+            let text: String = spans.iter().map(|s| s.content.as_ref()).collect();
+            let mut pos = 0;
+            for wrapped in wrap_line(&text, width) {
+                // Wrapped lines are pieces of `text`, minus the spaces at breaks
+                let start = pos + text[pos..].find(wrapped.as_str()).unwrap_or(0);
+                pos = start + wrapped.len();
+                result.push(Line::from(slice_spans(&spans, start..pos)));
+            }
         } else {
             // Quoted line -- wrap with prefix on every continuation
             let prefix = "\u{2502} ".repeat(depth);
             let prefix_width = depth * 2; // "│ " is 2 chars per level
             let text_width = width.saturating_sub(prefix_width);
 
-            let is_attr = is_attribution(content.trim());
+            let is_attr = email::is_attribution(content.trim(), attribution);
             let text_style = if is_attr {
                 Style::default()
-                    .fg(theme::SUBTEXT0)
+                    .fg(c.subtext0)
                     .add_modifier(Modifier::ITALIC)
             } else {
                 match depth {
-                    1 => Style::default().fg(theme::OVERLAY0),
-                    _ => Style::default().fg(theme::SURFACE0),
+                    1 => Style::default().fg(c.overlay0),
+                    _ => Style::default().fg(c.surface0),
                 }
             };
 
             if text_width < 5 {
                 // Too narrow to wrap meaningfully
                 result.push(Line::from(vec![
-                    Span::styled(prefix, Style::default().fg(theme::BLUE)),
+                    Span::styled(prefix, Style::default().fg(c.blue)),
                     Span::styled(content.to_string(), text_style),
                 ]));
             } else {
-                for wrapped in word_wrap(content, text_width) {
+                for wrapped in wrap_line(content, text_width) {
                     result.push(Line::from(vec![
-                        Span::styled(prefix.clone(), Style::default().fg(theme::BLUE)),
+                        Span::styled(prefix.clone(), Style::default().fg(c.blue)),
                         Span::styled(wrapped, text_style),
                     ]));
                 }
@@ -487,12 +1269,116 @@ fn wrap_and_style_body<'a>(body: &'a str, width: usize) -> Vec<Line<'a>> {
         }
     }
 
+    if !query.is_empty() {
+        let matched = Style::default().add_modifier(Modifier::REVERSED);
+        result = result
+            .into_iter()
+            .map(|line| highlight_in_line(line, query, matched, true))
+            .collect();
+    }
+
     result
 }
 
-/// Check if a line is an attribution ("On ..., ... wrote:").
-fn is_attribution(line: &str) -> bool {
-    line.starts_with("On ") && line.ends_with("wrote:")
+/// The parts of `spans` covering the byte `range` of their joined text.
+fn slice_spans(spans: &[Span<'static>], range: std::ops::Range<usize>) -> Vec<Span<'static>> {
+    let mut sliced = Vec::new();
+    let mut offset = 0;
+    for span in spans {
+        let len = span.content.len();
+        let start = range.start.clamp(offset, offset + len) - offset;
+        let end = range.end.clamp(offset, offset + len) - offset;
+        if start < end {
+            sliced.push(Span::styled(span.content[start..end].to_string(), span.style));
+        }
+        offset += len;
+    }
+    sliced
+}
+
+/// A list cell with occurrences of the active search query highlighted.
+/// The selected row keeps its plain highlight.
+/// With `fuzzy`, highlights the given char positions of the field, which starts
+/// `offset` chars into `text`, instead of substring hits.
+fn list_match_line(
+    app: &App,
+    c: &ThemeColors,
+    text: String,
+    is_selected: bool,
+    fuzzy: Option<(usize, &[usize])>,
+) -> Line<'static> {
+    if is_selected || app.search_query.is_empty() {
+        return Line::from(text);
+    }
+    let matched = Style::default().fg(c.yellow).add_modifier(Modifier::BOLD);
+    let Some((offset, positions)) = fuzzy else {
+        return highlight_in_line(Line::from(text), &app.search_query, matched, true);
+    };
+    // A trailing ellipsis stands in for truncated text and is never a match
+    let visible = text.chars().count() - usize::from(text.ends_with('\u{2026}'));
+    let mut spans: Vec<Span> = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, ch) in text.chars().enumerate() {
+        let hit = i < visible && i >= offset && positions.contains(&(i - offset));
+        if hit != run_matched && !run.is_empty() {
+            let style = if run_matched { matched } else { Style::default() };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_matched = hit;
+        run.push(ch);
+    }
+    if !run.is_empty() {
+        let style = if run_matched { matched } else { Style::default() };
+        spans.push(Span::styled(run, style));
+    }
+    Line::from(spans)
+}
+
+/// Restyle every occurrence of `needle` inside a line's spans with `patch`.
+/// With `ignore_case`, ASCII letters match regardless of case.
+fn highlight_in_line<'a>(
+    line: Line<'a>,
+    needle: &str,
+    patch: Style,
+    ignore_case: bool,
+) -> Line<'a> {
+    if needle.is_empty() {
+        return line;
+    }
+    // ASCII lowercasing keeps byte offsets identical to the original text
+    let needle = if ignore_case {
+        needle.to_ascii_lowercase()
+    } else {
+        needle.to_string()
+    };
+    let mut spans: Vec<Span> = Vec::new();
+    for span in line.spans {
+        let text = span.content.as_ref();
+        let haystack = if ignore_case {
+            text.to_ascii_lowercase()
+        } else {
+            text.to_string()
+        };
+        if !haystack.contains(&needle) {
+            spans.push(span);
+            continue;
+        }
+        let mut pos = 0;
+        while let Some(found) = haystack[pos..].find(&needle) {
+            let start = pos + found;
+            let end = start + needle.len();
+            if start > pos {
+                spans.push(Span::styled(text[pos..start].to_string(), span.style));
+            }
+            spans.push(Span::styled(text[start..end].to_string(), span.style.patch(patch)));
+            pos = end;
+        }
+        if pos < text.len() {
+            spans.push(Span::styled(text[pos..].to_string(), span.style));
+        }
+    }
+    Line::from(spans)
 }
 
 /// Simple word wrap: split text into lines that fit within `width` chars.
@@ -537,18 +1423,73 @@ fn word_wrap(text: &str, width: usize) -> Vec<String> {
     lines
 }
 
+/// Compact duration for the status bar: `45s`, `12m`, `2h34m`, `3d4h`.
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("{}d{}h", secs / 86400, secs % 86400 / 3600),
+    }
+}
+
 /// Render the status bar at the bottom.
 fn render_status_bar(app: &App, frame: &mut Frame, area: Rect) {
-    // Right side: optional WATCHING indicator + mailbox name + count
+    let c = &app.theme.colors();
+    // Right side: watcher state + mailbox name + count
     let total = app.mailbox_counts[app.active_mailbox.index()];
     let shown = app.emails.len();
-    let watch_prefix = if app.watcher_active { "WATCHING " } else { "" };
-    let mailbox_text = if !app.search_query.is_empty() && shown != total {
-        format!("{} {}/{} ", app.active_mailbox.label(), shown, total)
+    let since = |at: Option<Instant>| at.map(|t| format!(" {}", format_elapsed(t.elapsed())));
+    let (watch_text, watch_color) = if app.watcher_active {
+        let uptime = since(app.watcher_connected_at).unwrap_or_default();
+        let mode = match app.watch_mode {
+            WatchMode::Idle => "IDLE",
+            WatchMode::Files => "fs",
+        };
+        (format!("WATCHING ({mode}){uptime} "), c.teal)
     } else {
-        format!("{} {} ", app.active_mailbox.label(), total)
+        let downtime = since(app.watcher_disconnected_at).unwrap_or_default();
+        (format!("OFFLINE{downtime} "), c.red)
+    };
+    let mailbox_text = if shown != total {
+        format!("{} {}/{} ", app.mailbox_label(app.active_mailbox), shown, total)
+    } else {
+        format!("{} {} ", app.mailbox_label(app.active_mailbox), total)
     };
-    let right_len = (watch_prefix.len() + mailbox_text.len() + 1) as u16;
+
+    let mut right_spans = vec![Span::styled(" ", Style::default())];
+    if app.scheduled_pending > 0 {
+        right_spans.push(Span::styled(
+            format!("\u{23F0}{} ", app.scheduled_pending),
+            Style::default().fg(c.yellow),
+        ));
+    }
+    if app.snoozed_count > 0 && app.active_mailbox == Mailbox::Inbox {
+        right_spans.push(Span::styled(
+            format!("\u{1F4A4}{} ", app.snoozed_count),
+            Style::default().fg(c.mauve),
+        ));
+    }
+    if let Some(slot) = app.recording {
+        right_spans.push(Span::styled(
+            format!("[recording @{slot}] "),
+            Style::default().fg(c.red),
+        ));
+    }
+    right_spans.push(Span::styled(watch_text, Style::default().fg(watch_color)));
+    if app.focus == Focus::Preview {
+        right_spans.push(Span::styled(
+            format!("Preview  {}w {}l  ", app.body_word_count, app.body_line_count),
+            Style::default().fg(c.subtext0),
+        ));
+    }
+    right_spans.push(Span::styled(
+        mailbox_text,
+        Style::default().fg(c.blue),
+    ));
+    let right_line = Line::from(right_spans);
+    let right_len = right_line.width() as u16;
 
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -556,115 +1497,218 @@ fn render_status_bar(app: &App, frame: &mut Frame, area: Rect) {
         .split(area);
 
     // Left side: hints or status message
-    let left_content = if let Some(msg) = &app.status_message {
+    let mut left_content = if let Some(label) = &app.operation_label {
+        Line::from(Span::styled(
+            format!(" {} {}", app.spinner.current(), label),
+            Style::default().fg(c.teal),
+        ))
+    } else if let Some(msg) = &app.status_message {
         Line::from(vec![
             Span::styled(" ", Style::default()),
-            Span::styled(msg.as_str(), Style::default().fg(theme::GREEN)),
+            Span::styled(msg.as_str(), Style::default().fg(c.green)),
         ])
     } else {
         match app.focus {
+            Focus::Stats => Line::from(desc_span(c, " Press any key to close")),
             Focus::Sidebar => Line::from(vec![
-                hint_span(" j/k"),
-                desc_span("nav "),
-                hint_span("Enter"),
-                desc_span("select "),
-                hint_span("/"),
-                desc_span("search "),
-                hint_span("?"),
-                desc_span("help "),
-                hint_span("q"),
-                desc_span("quit"),
+                hint_span(c, " j/k"),
+                desc_span(c, "nav "),
+                hint_span(c, "Enter"),
+                desc_span(c, "select "),
+                hint_span(c, "/"),
+                desc_span(c, "search "),
+                hint_span(c, "?"),
+                desc_span(c, "help "),
+                hint_span(c, "q"),
+                desc_span(c, "quit"),
+            ]),
+            Focus::List => list_hints(app),
+            Focus::Headers => {
+                let mut spans = vec![hint_span(c, " j/k"), desc_span(c, "move ")];
+                match app.focused_header() {
+                    Some(HeaderLine::From) => spans.extend([
+                        hint_span(c, "Enter"),
+                        desc_span(c, "reply "),
+                        hint_span(c, "c"),
+                        desc_span(c, "copy "),
+                    ]),
+                    Some(HeaderLine::ReplyTo | HeaderLine::To | HeaderLine::Cc) => {
+                        spans.extend([hint_span(c, "c"), desc_span(c, "copy ")]);
+                    }
+                    _ => {}
+                }
+                if app.focused_attachment().is_some() {
+                    spans.extend([hint_span(c, "o"), desc_span(c, "open ")]);
+                }
+                spans.extend([
+                    hint_span(c, "h"),
+                    desc_span(c, "back "),
+                    hint_span(c, "l"),
+                    desc_span(c, "body "),
+                    hint_span(c, "?"),
+                    desc_span(c, "help "),
+                    hint_span(c, "q"),
+                    desc_span(c, "quit"),
+                ]);
+                Line::from(spans)
+            }
+            Focus::Preview if app.body_select_mode => Line::from(vec![
+                hint_span(c, " j/k"),
+                desc_span(c, "extend "),
+                hint_span(c, "y"),
+                desc_span(c, "copy "),
+                hint_span(c, "Esc"),
+                desc_span(c, "cancel"),
+            ]),
+            Focus::Preview => {
+                let mut spans = vec![
+                    hint_span(c, " j/k"),
+                    desc_span(c, "scroll "),
+                    hint_span(c, "d/C-u"),
+                    desc_span(c, "page "),
+                ];
+                let has_links = app
+                    .selected_email()
+                    .is_some_and(|e| !email::extract_urls(&e.body).is_empty());
+                if has_links {
+                    spans.extend([
+                        hint_span(c, "u"),
+                        desc_span(c, "next-link "),
+                        hint_span(c, "Enter"),
+                        desc_span(c, "open "),
+                    ]);
+                }
+                spans.extend([
+                    hint_span(c, "h"),
+                    desc_span(c, "back "),
+                    hint_span(c, "/"),
+                    desc_span(c, "search "),
+                    hint_span(c, "?"),
+                    desc_span(c, "help "),
+                    hint_span(c, "q"),
+                    desc_span(c, "quit"),
+                ]);
+                Line::from(spans)
+            }
+            Focus::Contacts => Line::from(vec![
+                hint_span(c, " j/k"),
+                desc_span(c, "nav "),
+                hint_span(c, "Enter"),
+                desc_span(c, "show emails "),
+                hint_span(c, "c"),
+                desc_span(c, "copy address "),
+                hint_span(c, "Esc"),
+                desc_span(c, "close"),
+            ]),
+            Focus::MailboxPicker => Line::from(vec![
+                hint_span(c, " Enter"),
+                desc_span(c, "move "),
+                hint_span(c, "Esc"),
+                desc_span(c, "cancel"),
             ]),
-            Focus::List => Line::from(vec![
-                hint_span(" e"),
-                desc_span("edit "),
-                hint_span("r"),
-                desc_span("reply "),
-                hint_span("a"),
-                desc_span("archive "),
-                hint_span("A"),
-                desc_span("approve "),
-                hint_span("x"),
-                desc_span("send "),
-                hint_span("n"),
-                desc_span("new "),
-                hint_span("/"),
-                desc_span("filter "),
-                hint_span("\\"),
-                desc_span("search "),
-                hint_span("?"),
-                desc_span("help"),
+            Focus::AccountPicker => Line::from(vec![
+                hint_span(c, " Enter"),
+                desc_span(c, "switch account "),
+                hint_span(c, "Esc"),
+                desc_span(c, "cancel"),
             ]),
-            Focus::Headers => Line::from(vec![
-                hint_span(" j/k"),
-                desc_span("scroll "),
-                hint_span("h"),
-                desc_span("back "),
-                hint_span("l"),
-                desc_span("body "),
-                hint_span("?"),
-                desc_span("help "),
-                hint_span("q"),
-                desc_span("quit"),
+            Focus::Compose => Line::from(vec![
+                hint_span(c, " Up/Down"),
+                desc_span(c, "choose "),
+                hint_span(c, "Tab"),
+                desc_span(c, "complete "),
+                hint_span(c, "Enter"),
+                desc_span(c, "create draft "),
+                hint_span(c, "Esc"),
+                desc_span(c, "cancel"),
             ]),
-            Focus::Preview => Line::from(vec![
-                hint_span(" j/k"),
-                desc_span("scroll "),
-                hint_span("d/u"),
-                desc_span("page "),
-                hint_span("h"),
-                desc_span("back "),
-                hint_span("/"),
-                desc_span("search "),
-                hint_span("?"),
-                desc_span("help "),
-                hint_span("q"),
-                desc_span("quit"),
+            Focus::SnoozeInput => Line::from(vec![
+                hint_span(c, " Enter"),
+                desc_span(c, "snooze "),
+                hint_span(c, "Esc"),
+                desc_span(c, "cancel "),
+                desc_span(c, "(tomorrow, 2025-06-01, 2025-06-01 14:30)"),
+            ]),
+            Focus::FileInput => Line::from(vec![
+                hint_span(c, " Tab"),
+                desc_span(c, "complete "),
+                hint_span(c, "Enter"),
+                desc_span(c, "attach "),
+                hint_span(c, "Esc"),
+                desc_span(c, "cancel"),
+            ]),
+            Focus::JumpSearch => Line::from(vec![
+                hint_span(c, " Enter"),
+                desc_span(c, "keep "),
+                hint_span(c, "Esc"),
+                desc_span(c, "cancel "),
+                desc_span(c, "(then n/N jump between matches)"),
+            ]),
+            Focus::DateFilter => Line::from(vec![
+                hint_span(c, " Enter"),
+                desc_span(c, "apply "),
+                hint_span(c, "Esc"),
+                desc_span(c, "clear "),
+                desc_span(c, "(today, yesterday, last week, last month, 2024-01-01..2024-01-31)"),
             ]),
             Focus::Search => {
                 let mut spans = vec![
-                    hint_span(" Enter"),
-                    desc_span("confirm "),
-                    hint_span("Esc"),
-                    desc_span("cancel"),
+                    hint_span(c, " Enter"),
+                    desc_span(c, "confirm "),
+                    hint_span(c, "Esc"),
+                    desc_span(c, "cancel "),
+                    hint_span(c, "Up/Down"),
+                    desc_span(c, "history"),
                 ];
                 if app.search_includes_body {
-                    spans.push(desc_span(" (content search)"));
+                    spans.push(desc_span(c, " (content search)"));
                 }
                 Line::from(spans)
             }
         }
     };
 
+    // Breadcrumb goes in front of whatever the left side shows
+    left_content.spans.insert(
+        0,
+        Span::styled(format!(" {}", app.breadcrumb()), Style::default().fg(c.overlay0)),
+    );
+    if let Some(count) = app.pending_count {
+        left_content
+            .spans
+            .insert(1, Span::styled(format!(" [{count}]"), Style::default().fg(c.yellow)));
+    }
+    // Fixed width so switching modes does not shift the rest of the bar
+    let mode = app.mode();
+    let mode_color = match mode {
+        "VISUAL" => c.yellow,
+        "INSERT" => c.blue,
+        "SEARCH" => c.mauve,
+        "CMD" => c.peach,
+        _ => c.green,
+    };
+    let mode_style = Style::default().fg(mode_color).add_modifier(Modifier::BOLD);
+    left_content.spans.insert(0, Span::styled(format!(" {mode:<8}"), mode_style));
     let left = Paragraph::new(left_content)
-        .style(Style::default().fg(theme::SUBTEXT0).bg(theme::SURFACE0));
+        .style(Style::default().fg(c.subtext0).bg(c.surface0));
     frame.render_widget(left, chunks[0]);
 
-    let mut right_spans = vec![Span::styled(" ", Style::default())];
-    if app.watcher_active {
-        right_spans.push(Span::styled(
-            watch_prefix,
-            Style::default().fg(theme::TEAL),
-        ));
-    }
-    right_spans.push(Span::styled(
-        mailbox_text,
-        Style::default().fg(theme::BLUE),
-    ));
-    let right = Paragraph::new(Line::from(right_spans))
-        .style(Style::default().bg(theme::SURFACE0))
+    let right = Paragraph::new(right_line)
+        .style(Style::default().bg(c.surface0))
         .alignment(Alignment::Right);
     frame.render_widget(right, chunks[1]);
 }
 
 /// Render a centered confirmation dialog overlay.
 fn render_confirm_dialog(
-    dialog: &crate::app::ConfirmDialog,
+    c: &ThemeColors,
+    dialog: &ConfirmDialog,
     frame: &mut Frame,
     area: Rect,
 ) {
-    // Size the dialog
-    let dialog_width = 40u16.min(area.width.saturating_sub(4));
+    // Size the dialog, widening it for long details (send warnings)
+    let detail_width = dialog.detail.chars().count().min(68) as u16 + 4;
+    let dialog_width = detail_width.max(40).min(area.width.saturating_sub(4));
     let dialog_height = 7u16;
 
     // Center it
@@ -688,46 +1732,647 @@ fn render_confirm_dialog(
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(theme::YELLOW))
-        .style(Style::default().bg(theme::BASE));
+        .border_style(Style::default().fg(c.yellow))
+        .style(Style::default().bg(c.base));
 
     let lines = vec![
         Line::from(Span::styled(
             &dialog.title,
             Style::default()
-                .fg(theme::YELLOW)
+                .fg(c.yellow)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(Span::styled(
             truncate(&dialog.detail, dialog_width.saturating_sub(4) as usize),
-            Style::default().fg(theme::TEXT),
+            Style::default().fg(c.text),
         )),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("  [y]", Style::default().fg(theme::GREEN)),
-            Span::styled("es  ", Style::default().fg(theme::TEXT)),
-            Span::styled("[n]", Style::default().fg(theme::RED)),
-            Span::styled("o", Style::default().fg(theme::TEXT)),
-        ]),
+        Line::from(dialog_button_spans(c, &dialog.buttons, dialog.selected_button)),
     ];
 
     let content = Paragraph::new(lines).block(block);
     frame.render_widget(content, dialog_area);
 }
 
+/// `[y]es  [t]rash  [n]o`: each key is colored, the last (cancelling) one in red,
+/// and the button Enter would pick is reversed.
+fn dialog_button_spans<'a>(
+    c: &ThemeColors,
+    buttons: &'a [DialogButton],
+    selected: usize,
+) -> Vec<Span<'a>> {
+    let palette = [c.green, c.yellow, c.red];
+    let mut spans = vec![Span::raw(" ")];
+    for (i, button) in buttons.iter().enumerate() {
+        let color = if i + 1 == buttons.len() { c.red } else { palette[i % palette.len()] };
+        let modifier = if i == selected { Modifier::REVERSED } else { Modifier::empty() };
+        // Fold the key into the label when it starts with it ("[y]es")
+        let rest = match button.label.strip_prefix(button.key) {
+            Some(rest) => rest.to_string(),
+            None => format!(" {}", button.label),
+        };
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            format!("[{}]", button.key),
+            Style::default().fg(color).add_modifier(modifier),
+        ));
+        spans.push(Span::styled(rest, Style::default().fg(c.text).add_modifier(modifier)));
+        spans.push(Span::raw(" "));
+    }
+    spans
+}
+
+/// Render a centered picker for moving the selected email to another mailbox.
+fn render_mailbox_picker(app: &App, frame: &mut Frame, area: Rect) {
+    let c = &app.theme.colors();
+    let config = &app.config;
+    let picker_width = 30u16.min(area.width.saturating_sub(4));
+    let picker_height = Mailbox::ALL.len() as u16 + 4;
+
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(picker_width)])
+        .flex(Flex::Center)
+        .split(area);
+
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(picker_height)])
+        .flex(Flex::Center)
+        .split(horizontal[0]);
+
+    let picker_area = vertical[0];
+    frame.render_widget(Clear, picker_area);
+
+    let block = Block::default()
+        .title(" Move to ")
+        .borders(borders(config))
+        .border_type(border_type(config))
+        .border_style(Style::default().fg(c.yellow))
+        .style(Style::default().bg(c.base));
+
+    let mut lines: Vec<Line> = Mailbox::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, mailbox)| {
+            let style = if i == app.mailbox_picker_index {
+                Style::default()
+                    .bg(c.surface0)
+                    .fg(c.green)
+                    .add_modifier(Modifier::BOLD)
+            } else if app.mailbox_dirs[i].is_none() {
+                Style::default().fg(c.overlay0)
+            } else {
+                Style::default().fg(c.text)
+            };
+            let icon = app.mailbox_icon(*mailbox);
+            let label = app.mailbox_label(*mailbox);
+            Line::from(Span::styled(format!(" {icon} {label}"), style))
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        hint_span(c, " j/k"),
+        desc_span(c, "nav "),
+        hint_span(c, "Enter"),
+        desc_span(c, "move "),
+        hint_span(c, "Esc"),
+        desc_span(c, "cancel"),
+    ]));
+
+    let content = Paragraph::new(lines).block(block);
+    frame.render_widget(content, picker_area);
+}
+
+/// Render a centered picker listing the configured accounts.
+fn render_account_picker(app: &App, frame: &mut Frame, area: Rect) {
+    let c = &app.theme.colors();
+    let config = &app.config;
+    let picker_width = 30u16.min(area.width.saturating_sub(4));
+    let picker_height = (app.accounts.len() as u16 + 4).min(area.height);
+
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(picker_width)])
+        .flex(Flex::Center)
+        .split(area);
+
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(picker_height)])
+        .flex(Flex::Center)
+        .split(horizontal[0]);
+
+    let picker_area = vertical[0];
+    frame.render_widget(Clear, picker_area);
+
+    let block = Block::default()
+        .title(" Accounts ")
+        .borders(borders(config))
+        .border_type(border_type(config))
+        .border_style(Style::default().fg(c.yellow))
+        .style(Style::default().bg(c.base));
+
+    let mut lines: Vec<Line> = app
+        .accounts
+        .iter()
+        .enumerate()
+        .map(|(i, account)| {
+            let style = if i == app.account_picker_index {
+                Style::default()
+                    .bg(c.surface0)
+                    .fg(c.green)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(c.text)
+            };
+            let marker = if i == app.active_account { "\u{25cf}" } else { " " };
+            Line::from(Span::styled(format!(" {marker} {}", account.name), style))
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        hint_span(c, " j/k"),
+        desc_span(c, "nav "),
+        hint_span(c, "Enter"),
+        desc_span(c, "switch "),
+        hint_span(c, "Esc"),
+        desc_span(c, "cancel"),
+    ]));
+
+    let content = Paragraph::new(lines).block(block);
+    frame.render_widget(content, picker_area);
+}
+
+/// Render the list of email files that failed to parse, with the reason below each,
+/// scrolled to `app.parse_errors_scroll`.
+fn render_parse_errors(app: &App, frame: &mut Frame, area: Rect) {
+    let c = &app.theme.colors();
+    let config = &app.config;
+    let width = 80u16.min(area.width.saturating_sub(4));
+    let text_width = width.saturating_sub(4) as usize;
+
+    let mut lines: Vec<Line> = Vec::new();
+    for (path, error) in &app.parse_errors {
+        lines.push(Line::from(Span::styled(
+            format!(" {}", truncate(&path.display().to_string(), text_width)),
+            Style::default().fg(c.text),
+        )));
+        lines.push(Line::from(Span::styled(
+            format!("   {}", truncate(error, text_width.saturating_sub(2))),
+            Style::default().fg(c.red),
+        )));
+    }
+    let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .split(area);
+
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .split(horizontal[0]);
+
+    let errors_area = vertical[0];
+    frame.render_widget(Clear, errors_area);
+
+    let block = Block::default()
+        .title(format!(" Unreadable files ({}) ", app.parse_errors.len()))
+        .borders(borders(config))
+        .border_type(border_type(config))
+        .border_style(Style::default().fg(c.red))
+        .style(Style::default().bg(c.base));
+
+    let errors = Paragraph::new(lines).block(block).scroll((app.parse_errors_scroll, 0));
+    frame.render_widget(errors, errors_area);
+}
+
+/// Render the contacts overlay: senders ranked by email count, 70% of the screen tall.
+fn render_contacts_overlay(app: &App, frame: &mut Frame, area: Rect) {
+    let c = &app.theme.colors();
+    let config = &app.config;
+    let width = 80u16.min(area.width.saturating_sub(4));
+    let height = (area.height * 7 / 10).max(5).min(area.height);
+
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .split(area);
+
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .split(horizontal[0]);
+
+    let contacts_area = vertical[0];
+    frame.render_widget(Clear, contacts_area);
+
+    let block = Block::default()
+        .title(format!(" Contacts ({}) ", app.contact_stats.len()))
+        .borders(borders(config))
+        .border_type(border_type(config))
+        .border_style(Style::default().fg(c.blue))
+        .style(Style::default().bg(c.base));
+
+    let header = Row::new(vec![
+        Cell::from("COUNT").style(Style::default().fg(c.subtext0)),
+        Cell::from("ADDRESS").style(Style::default().fg(c.subtext0)),
+        Cell::from("NAME").style(Style::default().fg(c.subtext0)),
+    ]);
+    let rows: Vec<Row> = app
+        .contact_stats
+        .iter()
+        .map(|contact| {
+            Row::new(vec![
+                Cell::from(format!("{:>5}", contact.count)),
+                Cell::from(contact.address.as_str()),
+                Cell::from(contact.name.as_str()),
+            ])
+            .style(Style::default().fg(c.text))
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [Constraint::Length(5), Constraint::Percentage(55), Constraint::Min(0)],
+    )
+    .header(header)
+    .column_spacing(2)
+    .block(block)
+    .row_highlight_style(
+        Style::default()
+            .bg(c.surface0)
+            .fg(c.green)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    let mut state = TableState::default().with_selected(Some(app.contacts_index));
+    frame.render_stateful_widget(table, contacts_area, &mut state);
+}
+
+/// Render the new-draft `To:` prompt with address suggestions below it.
+fn render_compose(app: &App, frame: &mut Frame, area: Rect) {
+    let c = &app.theme.colors();
+    let config = &app.config;
+    let suggestions = app.compose_suggestions();
+    let width = 60u16.min(area.width.saturating_sub(4));
+    let height = suggestions.len() as u16 + 3;
+
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .split(area);
+
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .split(horizontal[0]);
+
+    let compose_area = vertical[0];
+    frame.render_widget(Clear, compose_area);
+
+    let block = Block::default()
+        .title(" New draft ")
+        .borders(borders(config))
+        .border_type(border_type(config))
+        .border_style(Style::default().fg(c.yellow))
+        .style(Style::default().bg(c.base));
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled(" To: ", Style::default().fg(c.blue).add_modifier(Modifier::BOLD)),
+        Span::styled(app.compose_to.as_str(), Style::default().fg(c.text)),
+        Span::styled("\u{2588}", Style::default().fg(c.text)),
+    ])];
+    lines.extend(suggestions.iter().enumerate().map(|(i, entry)| {
+        let style = if app.compose_selected == Some(i) {
+            Style::default()
+                .bg(c.surface0)
+                .fg(c.green)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(c.subtext0)
+        };
+        Line::from(Span::styled(format!("   {entry}"), style))
+    }));
+
+    let content = Paragraph::new(lines).block(block);
+    frame.render_widget(content, compose_area);
+}
+
+/// Render the attach prompt; its completions drop down below the path.
+fn render_file_input(app: &App, frame: &mut Frame, area: Rect) {
+    const LABEL: &str = " Path: ";
+    let c = &app.theme.colors();
+    let config = &app.config;
+    let width = 70u16.min(area.width.saturating_sub(4));
+    let height = 3;
+
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .split(area);
+
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .split(horizontal[0]);
+
+    let input_area = vertical[0];
+    frame.render_widget(Clear, input_area);
+
+    let block = Block::default()
+        .title(" Attach file ")
+        .borders(borders(config))
+        .border_type(border_type(config))
+        .border_style(Style::default().fg(c.yellow))
+        .style(Style::default().bg(c.base));
+
+    let inner = block.inner(input_area);
+    frame.render_widget(block, input_area);
+    let label = Span::styled(LABEL, Style::default().fg(c.blue).add_modifier(Modifier::BOLD));
+    frame.render_widget(Paragraph::new(Line::from(label)), inner);
+
+    let label_width = LABEL.len() as u16;
+    let path_area = Rect {
+        x: inner.x + label_width.min(inner.width),
+        width: inner.width.saturating_sub(label_width),
+        ..inner
+    };
+    app.attach_input.render(c, frame, path_area);
+}
+
+/// Render the mailbox statistics overlay (`?S`).
+fn render_stats_overlay(app: &App, stats: &MailboxStats, frame: &mut Frame, area: Rect) {
+    const BAR_WIDTH: usize = 20;
+    const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    let c = &app.theme.colors();
+    let config = &app.config;
+
+    let section = |title: &str| -> Line {
+        Line::from(Span::styled(
+            format!("  {title}"),
+            Style::default().fg(c.mauve).add_modifier(Modifier::BOLD),
+        ))
+    };
+    let bar_line = |label: &str, count: usize, max: usize| -> Line {
+        // Any non-zero count gets at least one block
+        let len = if count == 0 { 0 } else { (count * BAR_WIDTH / max.max(1)).max(1) };
+        Line::from(vec![
+            Span::styled(format!("  {:<20} ", truncate(label, 20)), Style::default().fg(c.text)),
+            Span::styled("\u{2588}".repeat(len), Style::default().fg(c.blue)),
+            Span::styled(format!(" {count}"), Style::default().fg(c.subtext0)),
+        ])
+    };
+    let date =
+        |d: Option<NaiveDate>| d.map_or("-".to_string(), |d| d.format("%Y-%m-%d").to_string());
+
+    let mut lines = vec![section("MAILBOXES")];
+    for mailbox in Mailbox::ALL {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {} {:<18}", app.mailbox_icon(mailbox), app.mailbox_label(mailbox)),
+                Style::default().fg(c.text),
+            ),
+            Span::styled(
+                app.mailbox_counts[mailbox.index()].to_string(),
+                Style::default().fg(c.subtext0),
+            ),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(section("DATES"));
+    lines.push(Line::from(Span::styled(
+        format!("  Oldest {}   Newest {}", date(stats.oldest), date(stats.newest)),
+        Style::default().fg(c.text),
+    )));
+    lines.push(Line::from(""));
+    lines.push(section("TOP SENDERS"));
+    let max_sender = stats.top_senders.first().map_or(0, |(_, n)| *n);
+    for (sender, count) in &stats.top_senders {
+        lines.push(bar_line(sender, *count, max_sender));
+    }
+    lines.push(Line::from(""));
+    lines.push(section("BY WEEKDAY"));
+    let max_day = stats.weekdays.iter().copied().max().unwrap_or(0);
+    for (day, count) in DAYS.iter().zip(stats.weekdays) {
+        lines.push(bar_line(day, count, max_day));
+    }
+
+    let width = 56u16.min(area.width.saturating_sub(4));
+    let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .split(area);
+
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .split(horizontal[0]);
+
+    let stats_area = vertical[0];
+    frame.render_widget(Clear, stats_area);
+
+    let block = Block::default()
+        .title(format!(
+            " {} statistics ({} emails) ",
+            app.mailbox_label(app.active_mailbox),
+            stats.total
+        ))
+        .borders(borders(config))
+        .border_type(border_type(config))
+        .border_style(Style::default().fg(c.blue))
+        .style(Style::default().bg(c.base));
+
+    frame.render_widget(Paragraph::new(lines).block(block), stats_area);
+}
+
+/// Render the draft-versus-template diff, scrolled to `view.scroll`.
+fn render_diff_overlay(
+    c: &ThemeColors,
+    config: &Config,
+    view: &DiffView,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let diff_width = 100u16.min(area.width.saturating_sub(4));
+    let diff_height = area.height.saturating_sub(2);
+
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(diff_width)])
+        .flex(Flex::Center)
+        .split(area);
+
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(diff_height)])
+        .flex(Flex::Center)
+        .split(horizontal[0]);
+
+    let diff_area = vertical[0];
+    frame.render_widget(Clear, diff_area);
+
+    let block = Block::default()
+        .title(format!(" Changes since template {} ", view.template))
+        .title_bottom(Line::from(vec![
+            hint_span(c, " j/k"),
+            desc_span(c, "scroll "),
+            hint_span(c, "Esc/q"),
+            desc_span(c, "close "),
+        ]))
+        .borders(borders(config))
+        .border_type(border_type(config))
+        .border_style(Style::default().fg(c.blue))
+        .style(Style::default().bg(c.base));
+
+    let lines: Vec<Line> = view
+        .lines
+        .iter()
+        .map(|line| {
+            let (marker, text, color) = match line {
+                DiffLine::Same(text) => (' ', text, c.subtext0),
+                DiffLine::Added(text) => ('+', text, c.green),
+                DiffLine::Removed(text) => ('-', text, c.red),
+            };
+            Line::from(Span::styled(format!("{marker} {text}"), Style::default().fg(color)))
+        })
+        .collect();
+
+    let diff = Paragraph::new(lines).block(block).scroll((view.scroll, 0));
+    frame.render_widget(diff, diff_area);
+}
+
+/// Render a centered picker listing draft templates.
+fn render_template_picker(
+    c: &ThemeColors,
+    config: &Config,
+    picker: &crate::app::TemplatePicker,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let picker_width = 40u16.min(area.width.saturating_sub(4));
+    // One row per template plus borders and the key hint line
+    let picker_height = (picker.templates.len() as u16 + 4).min(area.height.saturating_sub(2));
+
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(picker_width)])
+        .flex(Flex::Center)
+        .split(area);
+
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(picker_height)])
+        .flex(Flex::Center)
+        .split(horizontal[0]);
+
+    let picker_area = vertical[0];
+    frame.render_widget(Clear, picker_area);
+
+    let block = Block::default()
+        .title(" New from template ")
+        .borders(borders(config))
+        .border_type(border_type(config))
+        .border_style(Style::default().fg(c.blue))
+        .style(Style::default().bg(c.base));
+
+    let mut lines: Vec<Line> = picker
+        .templates
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let style = if i == picker.selected {
+                Style::default()
+                    .bg(c.surface0)
+                    .fg(c.green)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(c.text)
+            };
+            let label = truncate(name, picker_width.saturating_sub(4) as usize);
+            Line::from(Span::styled(format!(" {label}"), style))
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        hint_span(c, " j/k"),
+        desc_span(c, "nav "),
+        hint_span(c, "Enter"),
+        desc_span(c, "create "),
+        hint_span(c, "Esc"),
+        desc_span(c, "cancel"),
+    ]));
+
+    // Keep the selection visible when the list is taller than the popup
+    let visible = picker_height.saturating_sub(4) as usize;
+    let scroll = picker.selected.saturating_sub(visible.saturating_sub(1)) as u16;
+    let content = Paragraph::new(lines).block(block).scroll((scroll, 0));
+    frame.render_widget(content, picker_area);
+}
+
+/// List hints for the selected email: approve only for unapproved drafts, send
+/// only for drafts or approved emails, reply only for received or sent mail.
+fn list_hints(app: &App) -> Line<'static> {
+    let c = &app.theme.colors();
+    let selected = app.email_index_at(app.list_index);
+    let mailbox = selected.map_or(app.active_mailbox, |i| app.origin_mailbox(i));
+    let status = selected.map(|i| app.emails[i].status.as_str());
+    let approved = status == Some("approved");
+
+    // Keys come from the active mailbox's keymap, so overrides show up here
+    let keymap = app.list_keymap();
+    let key = |action: &str| keymap.hint(action).unwrap_or("").to_string();
+    let mut spans = vec![Span::raw(" ")];
+    let mut hint = |keys: String, desc: &'static str| {
+        if !keys.is_empty() {
+            spans.extend([hint_span(c, keys), desc_span(c, desc)]);
+        }
+    };
+    hint(key("edit"), "edit ");
+    if matches!(mailbox, Mailbox::Inbox | Mailbox::Archive | Mailbox::Sent) {
+        hint(format!("{}/{}", key("reply"), key("reply_all")), "reply ");
+    }
+    hint(key("forward"), "fwd ");
+    hint(key("archive"), "archive ");
+    if mailbox == Mailbox::Drafts && status.is_some() && !approved {
+        hint(key("approve"), "approve ");
+    }
+    if status.is_some() && (mailbox == Mailbox::Drafts || approved) {
+        hint(key("send"), "send ");
+    }
+    hint(key("new"), "new ");
+    hint(key("filter"), "filter ");
+    hint(key("search_body"), "search ");
+    hint(key("help"), "help");
+    Line::from(spans)
+}
+
 /// Styled span for a keybinding hint (e.g. "Enter").
-fn hint_span(key: &str) -> Span<'_> {
-    Span::styled(key, Style::default().fg(theme::BLUE))
+fn hint_span<'a>(c: &ThemeColors, key: impl Into<Cow<'a, str>>) -> Span<'a> {
+    Span::styled(key, Style::default().fg(c.blue))
 }
 
 /// Styled span for a keybinding description (e.g. "edit ").
-fn desc_span(desc: &str) -> Span<'_> {
-    Span::styled(desc, Style::default().fg(theme::SUBTEXT0))
+fn desc_span<'a>(c: &ThemeColors, desc: &'a str) -> Span<'a> {
+    Span::styled(desc, Style::default().fg(c.subtext0))
 }
 
 /// Truncate a string to fit in `max_width` chars, adding ellipsis if needed.
-fn truncate(s: &str, max_width: usize) -> String {
+pub fn truncate(s: &str, max_width: usize) -> String {
     if max_width <= 3 {
         return s.chars().take(max_width).collect();
     }
@@ -740,10 +2385,193 @@ fn truncate(s: &str, max_width: usize) -> String {
     }
 }
 
-/// Render a full-screen help overlay listing all keybindings.
-fn render_help_overlay(frame: &mut Frame, area: Rect) {
+/// Key bindings listed in the help overlay, by section.
+const HELP_SECTIONS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "GLOBAL",
+        &[
+            ("q", "Quit"),
+            ("1-6", "Jump to mailbox"),
+            ("s", "Focus sidebar"),
+            ("Tab", "Cycle focus forward"),
+            ("Shift+Tab", "Cycle focus backward"),
+            ("/", "Filter by metadata"),
+            ("\\", "Search email content"),
+            ("g/ / g\\", "Search all mailboxes"),
+            ("C-f (search)", "Toggle fuzzy matching (~)"),
+            ("?", "Toggle this help"),
+            ("?S", "Mailbox statistics"),
+            ("?E", "Files that could not be read"),
+            ("Alt-z", "Undo archive / delete / move"),
+            ("Ctrl-z", "Suspend to the shell (fg resumes)"),
+            ("[ / ] / =", "Narrow / widen / reset list"),
+            ("Ctrl-t", "Cycle color theme"),
+            ("Ctrl-c", "Toggle CC column (wide terminals)"),
+            ("Ctrl-a", "Toggle dimming of older emails"),
+            ("Ctrl-k", "Contacts by email count"),
+            ("Alt-a", "Switch account ([[account]])"),
+            ("Qa ... Q", "Record macro into slot a-z"),
+            ("@a", "Replay macro a"),
+            ("Ctrl-r", "Reconcile mailbox (list / sidebar)"),
+        ],
+    ),
+    (
+        "SIDEBAR",
+        &[
+            ("j/k", "Navigate mailboxes"),
+            ("gg / G", "First / last mailbox"),
+            ("Ctrl-g", "Show sidebar position"),
+            ("Enter/l", "Select mailbox"),
+            ("Esc/h", "Return to list"),
+        ],
+    ),
+    (
+        "EMAIL LIST",
+        &[
+            ("j/k", "Navigate emails"),
+            ("gg / G", "Jump to top / bottom"),
+            ("7j / 80k", "Repeat a motion (counts start with 7-9)"),
+            ("C-/", "Find without filtering (n/N)"),
+            ("C-d / C-u", "Half-page down / up"),
+            ("C-f / C-b", "Page down / up"),
+            ("h / l", "Focus sidebar / body"),
+            ("H", "Focus headers"),
+            ("Enter / e", "Open in editor"),
+            ("r / R", "Reply / Reply-all"),
+            ("F", "Forward"),
+            ("a", "Archive"),
+            ("m", "Move to mailbox"),
+            ("!", "Mark as spam"),
+            ("* / g*", "Toggle star / Show starred only"),
+            ("d", "Delete (to Trash; permanent in Trash)"),
+            ("C-x (Trash)", "Empty the trash"),
+            ("A", "Approve draft"),
+            ("x / X", "Send / Send all approved"),
+            ("y", "Copy file path"),
+            ("C-o", "Open the email's folder"),
+            ("t", "Toggle thread view"),
+            ("D", "Filter by date range"),
+            ("z (Inbox)", "Snooze until a date/time"),
+            ("+ (Drafts)", "Attach a file (Tab completes)"),
+            ("C-e", "Export the listed emails to ~/*.mbox"),
+            ("M-m", "Mark every email in the mailbox read"),
+            ("C-p", "Export email to PDF (pandoc)"),
+            ("O / o", "Cycle sort column / flip order"),
+            ("M-f / M-t", "Only this sender / recipient"),
+            ("M-d", "Show duplicates (D removes a [DUP] copy)"),
+            ("n", "New draft (asks for To:)"),
+            ("T", "New draft from template"),
+            ("M-c (Drafts)", "Diff draft against its template"),
+            ("S (Drafts)", "Show scheduled drafts"),
+            ("ff / C-s", "Fetch / Sync"),
+            ("fa/fd/fs/fu", "Only approved/draft/sent/unread"),
+            ("fp", "Only high priority"),
+            ("fc", "Clear status and priority filters"),
+        ],
+    ),
+    (
+        "HEADERS",
+        &[
+            ("j/k", "Move between header lines"),
+            ("Enter", "Reply (From) / open (attachment)"),
+            ("c", "Copy address (From/To/Cc)"),
+            ("o", "Open attachment"),
+            ("F / T", "Only this sender / recipient"),
+            ("h / l", "Back to list / body"),
+        ],
+    ),
+    (
+        "BODY",
+        &[
+            ("e", "Edit at the start of the body"),
+            ("w", "Toggle word wrap (h/l scroll when off)"),
+            ("V", "Toggle raw source view"),
+            ("j/k", "Scroll line by line"),
+            ("d / C-u", "Half-page down / up"),
+            ("u", "Highlight next link"),
+            ("Enter", "Open highlighted link"),
+            ("n / N", "Next / previous search match"),
+            ("v / y", "Select lines / copy selection"),
+            ("Esc/h", "Return to list"),
+        ],
+    ),
+];
+
+/// One line of the help overlay.
+pub struct HelpEntry {
+    pub section: &'static str,
+    pub key: &'static str,
+    pub desc: &'static str,
+}
+
+impl HelpEntry {
+    /// Whether the key or description contains `query` (already lowercased).
+    fn matches(&self, query: &str) -> bool {
+        self.key.to_lowercase().contains(query) || self.desc.to_lowercase().contains(query)
+    }
+}
+
+/// Every help entry in display order.
+pub fn help_entries() -> Vec<HelpEntry> {
+    HELP_SECTIONS
+        .iter()
+        .flat_map(|&(section, entries)| {
+            entries.iter().map(move |&(key, desc)| HelpEntry { section, key, desc })
+        })
+        .collect()
+}
+
+/// Render the help overlay listing all keybindings, narrowed to those whose
+/// key or description contains the typed filter.
+fn render_help_overlay(app: &App, frame: &mut Frame, area: Rect) {
+    let c = &app.theme.colors();
+    let query = app.help_query.to_lowercase();
+
+    let section = |title: &str| -> Line {
+        Line::from(Span::styled(
+            format!("  {title}"),
+            Style::default()
+                .fg(c.mauve)
+                .add_modifier(Modifier::BOLD),
+        ))
+    };
+
+    let entry = |key: &str, desc: &str| -> Line {
+        Line::from(vec![
+            Span::styled(format!("  {key:<12}"), Style::default().fg(c.blue)),
+            Span::styled(desc.to_string(), Style::default().fg(c.text)),
+        ])
+    };
+
+    let mut lines = vec![if app.help_query.is_empty() {
+        Line::from(Span::styled("  type to filter", Style::default().fg(c.overlay0)))
+    } else {
+        Line::from(vec![
+            Span::styled("  / ", Style::default().fg(c.blue)),
+            Span::styled(app.help_query.clone(), Style::default().fg(c.text)),
+            Span::styled("\u{2588}", Style::default().fg(c.blue)),
+        ])
+    }];
+    let matched = Style::default().fg(c.yellow).add_modifier(Modifier::BOLD);
+    let mut current = None;
+    for help in help_entries().iter().filter(|e| e.matches(&query)) {
+        if current != Some(help.section) {
+            current = Some(help.section);
+            lines.push(Line::from(""));
+            lines.push(section(help.section));
+        }
+        lines.push(highlight_in_line(entry(help.key, help.desc), &query, matched, true));
+    }
+    if current.is_none() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "  No matching keys",
+            Style::default().fg(c.subtext0),
+        )));
+    }
+
     let help_width = 50u16.min(area.width.saturating_sub(4));
-    let help_height = 38u16.min(area.height.saturating_sub(2));
+    let help_height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
 
     let horizontal = Layout::default()
         .direction(Direction::Horizontal)
@@ -764,64 +2592,8 @@ fn render_help_overlay(frame: &mut Frame, area: Rect) {
         .title(" Help ")
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(theme::BLUE))
-        .style(Style::default().bg(theme::BASE));
-
-    let section = |title: &str| -> Line {
-        Line::from(Span::styled(
-            format!("  {title}"),
-            Style::default()
-                .fg(theme::MAUVE)
-                .add_modifier(Modifier::BOLD),
-        ))
-    };
-
-    let entry = |key: &str, desc: &str| -> Line {
-        Line::from(vec![
-            Span::styled(format!("  {key:<12}"), Style::default().fg(theme::BLUE)),
-            Span::styled(desc.to_string(), Style::default().fg(theme::TEXT)),
-        ])
-    };
-
-    let lines = vec![
-        section("GLOBAL"),
-        entry("q", "Quit"),
-        entry("1/2/3/4", "Jump to mailbox"),
-        entry("s", "Focus sidebar"),
-        entry("Tab", "Cycle focus forward"),
-        entry("Shift+Tab", "Cycle focus backward"),
-        entry("/", "Filter by metadata"),
-        entry("\\", "Search email content"),
-        entry("?", "Toggle this help"),
-        Line::from(""),
-        section("SIDEBAR"),
-        entry("j/k", "Navigate mailboxes"),
-        entry("Enter/l", "Select mailbox"),
-        entry("Esc/h", "Return to list"),
-        Line::from(""),
-        section("EMAIL LIST"),
-        entry("j/k", "Navigate emails"),
-        entry("gg / G", "Jump to top / bottom"),
-        entry("h / l", "Focus sidebar / body"),
-        entry("Enter / e", "Open in editor"),
-        entry("r / R", "Reply / Reply-all"),
-        entry("a", "Archive"),
-        entry("d", "Delete"),
-        entry("A", "Approve draft"),
-        entry("x / X", "Send / Send all approved"),
-        entry("y", "Copy file path"),
-        entry("n", "New draft"),
-        entry("f / F / S", "Fetch / Sync / Reconcile"),
-        Line::from(""),
-        section("HEADERS"),
-        entry("j/k", "Scroll headers"),
-        entry("h / l", "Back to list / body"),
-        Line::from(""),
-        section("BODY"),
-        entry("j/k", "Scroll line by line"),
-        entry("d/u", "Half-page down / up"),
-        entry("Esc/h", "Return to list"),
-    ];
+        .border_style(Style::default().fg(c.blue))
+        .style(Style::default().bg(c.base));
 
     let help = Paragraph::new(lines)
         .block(block)
@@ -830,12 +2602,25 @@ fn render_help_overlay(frame: &mut Frame, area: Rect) {
 }
 
 /// Return border style based on whether this pane is focused.
-fn pane_border_style(current_focus: Focus, pane: Focus) -> Style {
+fn pane_border_style(c: &ThemeColors, current_focus: Focus, pane: Focus) -> Style {
     let focused =
-        current_focus == pane || (current_focus == Focus::Search && pane == Focus::List);
+        current_focus == pane
+            || (matches!(
+                current_focus,
+                Focus::Search
+                    | Focus::DateFilter
+                    | Focus::MailboxPicker
+                    | Focus::Compose
+                    | Focus::Stats
+                    | Focus::SnoozeInput
+                    | Focus::Contacts
+                    | Focus::AccountPicker
+                    | Focus::JumpSearch
+                    | Focus::FileInput
+            ) && pane == Focus::List);
     if focused {
-        Style::default().fg(theme::BLUE)
+        Style::default().fg(c.blue)
     } else {
-        Style::default().fg(theme::OVERLAY0)
+        Style::default().fg(c.overlay0)
     }
 }